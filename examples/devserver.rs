@@ -0,0 +1,45 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Serves a directory for the WASM integration tests.
+//!
+//! ```text
+//! cargo run --example devserver --features devserver [DIR] [ADDR]
+//! ```
+//!
+//! With no `DIR`, serves a fresh fixture directory containing the `5MB.zip`
+//! the tests expect. Run the WASM tests against it with
+//! `ASYNC_FILE_TEST_ORIGIN=<printed origin> scripts/wasm32/tests`.
+
+#[cfg(not(target_arch = "wasm32"))]
+use async_file::devserver::DevServer;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+/// The size of the `5MB.zip` fixture.
+#[cfg(not(target_arch = "wasm32"))]
+const FIXTURE_LEN: usize = 5 * 1024 * 1024;
+
+// The server is native-only; it serves files *to* the WASM backend.
+#[cfg(target_arch = "wasm32")]
+fn main() {}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> std::io::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let root = match args.next() {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            let dir = std::env::temp_dir().join("async_file_devserver_fixtures");
+            std::fs::create_dir_all(&dir)?;
+            let fixture: Vec<u8> = (0..FIXTURE_LEN).map(|i| (i % 251) as u8).collect();
+            std::fs::write(dir.join("5MB.zip"), fixture)?;
+            dir
+        }
+    };
+    let addr = args.next().unwrap_or_else(|| "127.0.0.1:8000".to_string());
+    let server = DevServer::bind(&root, addr)?;
+    println!("serving {} at {}", root.display(), server.origin());
+    loop {
+        std::thread::park();
+    }
+}
@@ -0,0 +1,39 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Access-pattern hints for the operating system's page cache.
+
+/// How a file is about to be read, passed to
+/// [`File::advise`](crate::File::advise) or
+/// [`OpenOptions::advice`](crate::OpenOptions::advice).
+///
+/// Advice is a hint: it never changes what a read returns, only how the
+/// operating system schedules readahead and caching around it.
+///
+/// | Advice | Linux, Android | macOS, iOS | Windows (at open only) |
+/// |---|---|---|---|
+/// | `Normal` | `POSIX_FADV_NORMAL` | readahead on | no flags |
+/// | `Sequential` | `POSIX_FADV_SEQUENTIAL` | readahead on | `FILE_FLAG_SEQUENTIAL_SCAN` |
+/// | `Random` | `POSIX_FADV_RANDOM` | readahead off | `FILE_FLAG_RANDOM_ACCESS` |
+/// | `WillNeed` | `POSIX_FADV_WILLNEED` | `F_RDADVISE` | ignored |
+/// | `DontNeed` | `POSIX_FADV_DONTNEED` | ignored | ignored |
+///
+/// Advice is ignored on other platforms and on WASM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum Advice {
+    /// No particular pattern; the default.
+    #[default]
+    Normal,
+    /// The range will be read from start to end. Readahead is made more
+    /// aggressive.
+    Sequential,
+    /// The range will be read in no particular order. Readahead is disabled,
+    /// so small scattered reads don't pull in data that won't be used.
+    Random,
+    /// The range will be read soon. The operating system starts loading it
+    /// into the page cache in the background.
+    WillNeed,
+    /// The range won't be read again soon. Its cached pages may be dropped,
+    /// making room for data that will be.
+    DontNeed,
+}
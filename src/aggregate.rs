@@ -0,0 +1,53 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Small-read aggregation for [`File`](crate::File).
+//!
+//! Parsers that read a handful of bytes at a time turn into one backend round trip
+//! per field. When aggregation is enabled on a file, reads smaller than
+//! [`PAGE_SIZE`] are served from a page read in a single physical operation.
+
+use crate::Data;
+
+/// The size of the physical read used to satisfy small reads.
+pub(crate) const PAGE_SIZE: usize = 4096;
+
+/// Per-file page state.
+#[derive(Debug, Default)]
+pub(crate) struct PageBuffer {
+    pub(crate) enabled: bool,
+    data: Option<Data>,
+    pos: usize,
+}
+
+impl PageBuffer {
+    /// Bytes read physically but not yet returned to the caller.
+    pub(crate) fn remaining(&self) -> usize {
+        self.data.as_ref().map_or(0, |data| data.len() - self.pos)
+    }
+
+    /// Replaces the page with freshly read bytes.
+    pub(crate) fn fill(&mut self, data: Data) {
+        self.data = Some(data);
+        self.pos = 0;
+    }
+
+    /// Returns up to `len` buffered bytes.
+    pub(crate) fn take(&mut self, len: usize) -> Data {
+        let Some(data) = &self.data else {
+            return Data::from_boxed_slice(Box::new([]));
+        };
+        let end = self.pos + len.min(data.len() - self.pos);
+        let out = Data::from_boxed_slice(data[self.pos..end].into());
+        self.pos = end;
+        if self.pos == data.len() {
+            self.clear();
+        }
+        out
+    }
+
+    /// Discards any buffered bytes.
+    pub(crate) fn clear(&mut self) {
+        self.data = None;
+        self.pos = 0;
+    }
+}
@@ -0,0 +1,336 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! The priority of the task making a call, for code that isn't handed one,
+//! and the deadline and handle of the operation making it.
+
+use crate::{Priority, sys};
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+thread_local! {
+    /// Set while a [`WithPriority`] is being polled on this thread.
+    static CURRENT: Cell<Option<Priority>> = const { Cell::new(None) };
+
+    /// Set while a [`WithDeadline`] is being polled on this thread.
+    static DEADLINE: Cell<Option<sys::Instant>> = const { Cell::new(None) };
+
+    /// Set while a [`WithHandle`] is being polled on this thread.
+    static OPERATION: RefCell<Option<OperationHandle>> = const { RefCell::new(None) };
+}
+
+/// Returns the priority of the task that is running, for call sites that
+/// aren't passed one.
+///
+/// This is the priority given to the innermost [`with_priority`] being
+/// polled, or else that of the running `some_executor` task, or else
+/// [`Priority::Unknown`], which the crate schedules as
+/// [`Priority::Utility`]. Libraries in the middle of a stack can pass it to
+/// every call and leave the choice to whoever spawned the task.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{File, current_priority};
+///
+/// // A library function with no priority parameter
+/// async fn load_config() -> Result<async_file::Data, async_file::Error> {
+///     let file = File::open("config.toml", current_priority()).await?;
+///     file.read_all(current_priority()).await
+/// }
+/// # load_config().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn current_priority() -> Priority {
+    CURRENT.with(Cell::get).unwrap_or_else(|| {
+        some_executor::task::TASK_PRIORITY
+            .with(|priority| priority.copied())
+            .unwrap_or(Priority::Unknown)
+    })
+}
+
+/// Runs `future` with [`current_priority`] returning `priority` whenever it
+/// is polled.
+///
+/// Scopes nest: within an inner `with_priority`, the inner priority applies.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{Priority, current_priority, with_priority};
+///
+/// let priority = with_priority(Priority::Background, async { current_priority() }).await;
+/// assert_eq!(priority, Priority::Background);
+/// # Ok(())
+/// # }
+/// ```
+pub fn with_priority<F: Future>(priority: Priority, future: F) -> WithPriority<F> {
+    WithPriority {
+        priority,
+        future: Box::pin(future),
+    }
+}
+
+/// The future returned by [`with_priority`].
+#[must_use = "futures do nothing unless awaited"]
+pub struct WithPriority<F> {
+    priority: Priority,
+    future: Pin<Box<F>>,
+}
+
+impl<F> std::fmt::Debug for WithPriority<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WithPriority")
+            .field("priority", &self.priority)
+            .finish()
+    }
+}
+
+impl<F: Future> Future for WithPriority<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        /// Puts back the outer priority, even if the poll panics.
+        struct Restore(Option<Priority>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT.with(|current| current.set(self.0));
+            }
+        }
+
+        let _restore = Restore(CURRENT.with(|current| current.replace(Some(self.priority))));
+        self.future.as_mut().poll(cx)
+    }
+}
+
+/// Returns the deadline of the operation being polled, if it has one.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn current_deadline() -> Option<sys::Instant> {
+    DEADLINE.with(Cell::get)
+}
+
+/// Runs `future` with [`current_deadline`] returning `deadline` whenever it is
+/// polled. With no deadline, an outer one still applies.
+pub(crate) fn with_deadline<F: Future>(
+    deadline: Option<sys::Instant>,
+    future: F,
+) -> WithDeadline<F> {
+    WithDeadline {
+        deadline,
+        future: Box::pin(future),
+    }
+}
+
+pub(crate) struct WithDeadline<F> {
+    deadline: Option<sys::Instant>,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for WithDeadline<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        /// Puts back the outer deadline, even if the poll panics.
+        struct Restore(Option<sys::Instant>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                DEADLINE.with(|current| current.set(self.0));
+            }
+        }
+
+        let Some(deadline) = self.deadline else {
+            return self.future.as_mut().poll(cx);
+        };
+        let _restore = Restore(DEADLINE.with(|current| current.replace(Some(deadline))));
+        self.future.as_mut().poll(cx)
+    }
+}
+
+/// A handle on the priority of an operation, which can be raised (or
+/// lowered) while its calls wait in the queue.
+///
+/// Attach the handle to an operation's future with
+/// [`attach`](OperationHandle::attach). On native platforms, each call the
+/// operation queues for a worker thread takes its place from the handle's
+/// priority at the time a thread comes free, not from the priority it was
+/// made with, so [`set_priority`](OperationHandle::set_priority) moves calls
+/// already waiting, without cancelling and re-issuing the operation. Calls
+/// already running are not affected, and neither are the throttle or the I/O
+/// priority hints given to the operating system, which use the priority the
+/// call was made with. Clones share the priority. On WASM the handle has no
+/// effect.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{File, OperationHandle, Priority};
+///
+/// let file = File::open("thumbnails/42.png", Priority::Background).await?;
+/// let handle = OperationHandle::new(Priority::Background);
+/// let read = handle.attach(file.read_all(Priority::Background));
+///
+/// // The item scrolled into view: the read is needed now.
+/// handle.set_priority(Priority::UserInteractive);
+/// let thumbnail = read.await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct OperationHandle {
+    priority: Arc<Mutex<Priority>>,
+}
+
+impl OperationHandle {
+    /// Creates a handle with the given priority.
+    pub fn new(priority: Priority) -> Self {
+        OperationHandle {
+            priority: Arc::new(Mutex::new(priority)),
+        }
+    }
+
+    /// Returns `future` with this handle attached: its calls queue at the
+    /// handle's priority whenever it is polled.
+    ///
+    /// Attaching inside another attached future takes precedence over the
+    /// outer handle.
+    pub fn attach<F: Future>(&self, future: F) -> WithHandle<F> {
+        WithHandle {
+            handle: self.clone(),
+            future: Box::pin(future),
+        }
+    }
+
+    /// Changes the priority of calls waiting in the queue, and of those made
+    /// from now on.
+    pub fn set_priority(&self, priority: Priority) {
+        *self.priority.lock().unwrap() = priority;
+        sys::reschedule();
+    }
+
+    /// Returns the handle's priority.
+    pub fn priority(&self) -> Priority {
+        *self.priority.lock().unwrap()
+    }
+}
+
+/// The future returned by [`OperationHandle::attach`].
+#[must_use = "futures do nothing unless awaited"]
+pub struct WithHandle<F> {
+    handle: OperationHandle,
+    future: Pin<Box<F>>,
+}
+
+impl<F> std::fmt::Debug for WithHandle<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WithHandle")
+            .field("handle", &self.handle)
+            .finish()
+    }
+}
+
+impl<F: Future> Future for WithHandle<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        /// Puts back the outer handle, even if the poll panics.
+        struct Restore(Option<OperationHandle>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let outer = self.0.take();
+                OPERATION.with(|current| *current.borrow_mut() = outer);
+            }
+        }
+
+        let handle = Some(self.handle.clone());
+        let _restore = Restore(OPERATION.with(|current| current.replace(handle)));
+        self.future.as_mut().poll(cx)
+    }
+}
+
+/// Returns the handle attached to the operation being polled, if any.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn current_operation() -> Option<OperationHandle> {
+    OPERATION.with(|current| current.borrow().clone())
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::{current_priority, with_priority};
+    use crate::Priority;
+
+    #[test_executors::async_test]
+    async fn test_with_priority() {
+        assert_eq!(current_priority(), Priority::Unknown);
+        let (outer, inner, after) = with_priority(Priority::Background, async {
+            let outer = current_priority();
+            let inner =
+                with_priority(Priority::UserInteractive, async { current_priority() }).await;
+            (outer, inner, current_priority())
+        })
+        .await;
+        assert_eq!(outer, Priority::Background);
+        assert_eq!(inner, Priority::UserInteractive);
+        assert_eq!(after, Priority::Background);
+        assert_eq!(current_priority(), Priority::Unknown);
+    }
+
+    #[test_executors::async_test]
+    async fn test_with_deadline() {
+        use super::{current_deadline, with_deadline};
+        let deadline = std::time::Instant::now();
+        let (outer, inner) = with_deadline(Some(deadline), async {
+            let inner = with_deadline(None, async { current_deadline() }).await;
+            (current_deadline(), inner)
+        })
+        .await;
+        assert_eq!(outer, Some(deadline));
+        assert_eq!(inner, Some(deadline));
+        assert_eq!(current_deadline(), None);
+    }
+
+    #[test_executors::async_test]
+    async fn test_operation_handle() {
+        use super::{OperationHandle, current_operation};
+        let outer = OperationHandle::new(Priority::Background);
+        let inner = OperationHandle::new(Priority::Utility);
+        let (in_outer, in_inner) = outer
+            .attach(async {
+                let in_inner = inner
+                    .attach(async { current_operation().map(|handle| handle.priority()) })
+                    .await;
+                (
+                    current_operation().map(|handle| handle.priority()),
+                    in_inner,
+                )
+            })
+            .await;
+        assert_eq!(in_outer, Some(Priority::Background));
+        assert_eq!(in_inner, Some(Priority::Utility));
+        assert!(current_operation().is_none());
+        outer.clone().set_priority(Priority::UserInteractive);
+        assert_eq!(outer.priority(), Priority::UserInteractive);
+    }
+
+    #[test_executors::async_test]
+    async fn test_task_priority() {
+        use some_executor::task::TASK_PRIORITY;
+        let (task, scoped) = TASK_PRIORITY
+            .scope(Priority::Background, async {
+                let scoped =
+                    with_priority(Priority::UserInitiated, async { current_priority() }).await;
+                (current_priority(), scoped)
+            })
+            .await;
+        assert_eq!(task, Priority::Background);
+        assert_eq!(scoped, Priority::UserInitiated);
+    }
+}
@@ -0,0 +1,154 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! A synchronous `Read + Seek` adapter over [`File`].
+//!
+//! Code written against `std::io::Read` can't await, so [`BlockingReader`]
+//! blocks the calling thread on each of the file's operations. Native reads
+//! already run on worker threads and wake the caller from there, so no
+//! executor needs to be running.
+
+use crate::{File, Priority};
+use std::future::Future;
+use std::io::SeekFrom;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+/// A [`File`] implementing [`std::io::Read`] and [`std::io::Seek`], returned
+/// by [`File::into_blocking_reader`].
+///
+/// Each call blocks the calling thread until the file's operation finishes,
+/// using the priority given at construction. Don't use it on a thread that
+/// runs async tasks: they can't make progress while it blocks. Errors are
+/// converted to [`std::io::Error`] by its `From<Error>` implementation, so
+/// the original [`Error`](crate::Error) can be recovered with
+/// [`get_ref`](std::io::Error::get_ref).
+///
+/// Each `read` makes one read of the file; wrap the reader in a
+/// [`std::io::BufReader`] for code that reads a few bytes at a time.
+///
+/// Not available on WASM, where the thread can't block.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{File, Priority};
+/// use std::io::Read;
+///
+/// let file = File::open("/dev/zero", Priority::unit_test()).await?;
+/// // Hand the reader to code that only knows std::io.
+/// let parse = move || {
+///     let mut reader = file.into_blocking_reader(Priority::unit_test());
+///     let mut header = [0; 16];
+///     reader.read_exact(&mut header)?;
+///     Ok::<_, std::io::Error>(header)
+/// };
+/// let header = std::thread::spawn(parse).join().unwrap().unwrap();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct BlockingReader {
+    file: File,
+    priority: Priority,
+}
+
+impl File {
+    /// Wraps the file in a [`BlockingReader`], whose reads and seeks block
+    /// the calling thread.
+    pub fn into_blocking_reader(self, priority: Priority) -> BlockingReader {
+        BlockingReader {
+            file: self,
+            priority,
+        }
+    }
+}
+
+impl BlockingReader {
+    /// Returns the file.
+    pub fn get_ref(&self) -> &File {
+        &self.file
+    }
+
+    /// Returns the file, for operations not covered by `Read` and `Seek`.
+    pub fn get_mut(&mut self) -> &mut File {
+        &mut self.file
+    }
+
+    /// Returns the file, which continues from where the reader left off.
+    pub fn into_inner(self) -> File {
+        self.file
+    }
+}
+
+impl std::io::Read for BlockingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let data = block_on(self.file.read(buf.len(), self.priority))?;
+        buf[..data.len()].copy_from_slice(&data);
+        Ok(data.len())
+    }
+}
+
+impl std::io::Seek for BlockingReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        Ok(block_on(self.file.seek(pos, self.priority))?)
+    }
+}
+
+/// Wakes the thread blocked in [`block_on`].
+struct Unpark(std::thread::Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Polls `future` on the current thread, parking it between polls.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(Unpark(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        // A wake that comes before this leaves a token, so none is lost.
+        std::thread::park();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{File, Priority};
+    use std::io::{Read, Seek, SeekFrom};
+
+    #[test]
+    fn test_read_and_seek() {
+        let path = crate::tests::temp_path("blocking");
+        let contents: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        std::fs::write(&path, &contents).unwrap();
+        let file = super::block_on(File::open(&path, Priority::unit_test())).unwrap();
+        let mut reader = file.into_blocking_reader(Priority::unit_test());
+
+        let mut buf = [0; 10];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, &contents[..10]);
+        assert_eq!(reader.seek(SeekFrom::Current(90)).unwrap(), 100);
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, &contents[100..]);
+
+        assert_eq!(reader.seek(SeekFrom::End(-4)).unwrap(), 996);
+        let file = reader.into_inner();
+        let tail = super::block_on(file.read(10, Priority::unit_test())).unwrap();
+        assert_eq!(&*tail, &contents[996..]);
+    }
+}
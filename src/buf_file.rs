@@ -0,0 +1,432 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Buffered reading on top of [`File`].
+//!
+//! [`File::read`] dispatches every call to the backend: a trip through the blocking
+//! pool on std platforms, or a ranged fetch on WASM. Parsers that pull a few bytes
+//! at a time (headers, varints, tags) pay that cost on every call. [`BufFile`]
+//! amortizes it by reading a larger chunk once and serving small reads from memory.
+//!
+//! For text processing, [`BufFile::read_until`] and [`BufFile::lines`] split the
+//! file on a delimiter without reading the whole file into memory first.
+
+use crate::{Data, Error, File, Metadata, Priority, sys};
+use std::future::Future;
+use std::io::SeekFrom;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// The default capacity of a [`BufFile`]'s internal buffer, in bytes.
+const DEFAULT_BUF_SIZE: usize = 8 * 1024;
+
+/// A [`File`] with an internal read buffer.
+///
+/// `BufFile` exposes the same read/seek/metadata surface as [`File`], plus
+/// [`fill_buf`](BufFile::fill_buf) and [`consume`](BufFile::consume) for
+/// callers that want to inspect buffered bytes without copying them out.
+///
+/// # Buffering Behavior
+///
+/// - Reads smaller than the buffer capacity are served from the buffer,
+///   refilling it with a single backend read when it runs dry.
+/// - Reads at least as large as the buffer capacity bypass the buffer when it
+///   is empty, so large reads are not split or copied.
+/// - Seeking discards the buffer.
+///
+/// # Constraints
+///
+/// Operations take `&mut self`, so the single-operation constraint of the
+/// underlying [`File`] is upheld by the borrow checker.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{BufFile, File, Priority};
+///
+/// let file = File::open("/dev/zero", Priority::unit_test()).await?;
+/// let mut reader = BufFile::new(file);
+///
+/// // Each of these is served from a single 8 KiB backend read
+/// for _ in 0..16 {
+///     let field = reader.read(4, Priority::unit_test()).await?;
+///     assert_eq!(field.len(), 4);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct BufFile {
+    file: File,
+    capacity: usize,
+    buf: Data,
+    pos: usize,
+}
+
+impl BufFile {
+    /// Creates a new `BufFile` with a default buffer capacity (currently 8 KiB).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{BufFile, File, Priority};
+    ///
+    /// let file = File::open("/dev/zero", Priority::unit_test()).await?;
+    /// let reader = BufFile::new(file);
+    /// assert_eq!(reader.capacity(), 8 * 1024);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(file: File) -> Self {
+        Self::with_capacity(DEFAULT_BUF_SIZE, file)
+    }
+
+    /// Creates a new `BufFile` with the specified buffer capacity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{BufFile, File, Priority};
+    ///
+    /// let file = File::open("/dev/zero", Priority::unit_test()).await?;
+    /// let reader = BufFile::with_capacity(64 * 1024, file);
+    /// assert_eq!(reader.capacity(), 64 * 1024);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_capacity(capacity: usize, file: File) -> Self {
+        assert!(capacity > 0, "BufFile capacity must be nonzero");
+        BufFile {
+            file,
+            capacity,
+            buf: Data::from_boxed_slice(Box::new([])),
+            pos: 0,
+        }
+    }
+
+    /// Returns the capacity of the internal buffer.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the bytes currently buffered but not yet consumed.
+    ///
+    /// Unlike [`fill_buf`](BufFile::fill_buf), this never performs I/O.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf[self.pos..]
+    }
+
+    /// Returns a reference to the underlying file.
+    pub fn get_ref(&self) -> &File {
+        &self.file
+    }
+
+    /// Unwraps this `BufFile`, returning the underlying file.
+    ///
+    /// Any buffered data is discarded. The underlying file's position will be
+    /// ahead of the logical position of this `BufFile` by the length of
+    /// [`buffer`](BufFile::buffer).
+    pub fn into_inner(self) -> File {
+        self.file
+    }
+
+    /// Returns the buffered bytes, refilling the buffer from the file if it is empty.
+    ///
+    /// An empty slice indicates end of file. Call [`consume`](BufFile::consume)
+    /// to mark bytes as read.
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - The priority for the backend read, if one is needed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{BufFile, File, Priority};
+    ///
+    /// let file = File::open("/dev/zero", Priority::unit_test()).await?;
+    /// let mut reader = BufFile::new(file);
+    ///
+    /// let available = reader.fill_buf(Priority::unit_test()).await?.len();
+    /// assert!(available > 0);
+    /// reader.consume(available);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fill_buf(&mut self, priority: Priority) -> Result<&[u8], Error> {
+        if self.pos >= self.buf.len() {
+            self.buf = self.file.read(self.capacity, priority).await?;
+            self.pos = 0;
+        }
+        Ok(self.buffer())
+    }
+
+    /// Marks `amt` buffered bytes as consumed.
+    ///
+    /// The amount is clamped to the number of buffered bytes.
+    pub fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.buf.len());
+    }
+
+    /// Reads up to `buf_size` bytes.
+    ///
+    /// Returns fewer bytes than requested if the buffer holds fewer bytes, and an
+    /// empty `Data` at end of file. Large reads against an empty buffer are
+    /// forwarded directly to [`File::read`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{BufFile, File, Priority};
+    ///
+    /// let file = File::open("/dev/zero", Priority::unit_test()).await?;
+    /// let mut reader = BufFile::new(file);
+    ///
+    /// let magic = reader.read(4, Priority::unit_test()).await?;
+    /// assert_eq!(magic.as_ref(), &[0, 0, 0, 0]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read(&mut self, buf_size: usize, priority: Priority) -> Result<Data, Error> {
+        if self.pos >= self.buf.len() && buf_size >= self.capacity {
+            return self.file.read(buf_size, priority).await;
+        }
+        let available = self.fill_buf(priority).await?;
+        let take = available.len().min(buf_size);
+        let out = Data::from_boxed_slice(available[..take].into());
+        self.consume(take);
+        Ok(out)
+    }
+
+    /// Reads bytes until the delimiter `byte` or end of file is reached.
+    ///
+    /// The delimiter, if found, is included in the returned `Data`. An empty
+    /// `Data` indicates end of file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{BufFile, File, Priority};
+    ///
+    /// let file = File::open("records.bin", Priority::unit_test()).await?;
+    /// let mut reader = BufFile::new(file);
+    ///
+    /// // Read a NUL-terminated record
+    /// let record = reader.read_until(0, Priority::unit_test()).await?;
+    /// if record.last() == Some(&0) {
+    ///     println!("record of {} bytes", record.len() - 1);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_until(&mut self, byte: u8, priority: Priority) -> Result<Data, Error> {
+        let mut out = Vec::new();
+        loop {
+            let available = self.fill_buf(priority).await?;
+            if available.is_empty() {
+                break;
+            }
+            match available.iter().position(|&b| b == byte) {
+                Some(i) => {
+                    out.extend_from_slice(&available[..=i]);
+                    self.consume(i + 1);
+                    break;
+                }
+                None => {
+                    let len = available.len();
+                    out.extend_from_slice(available);
+                    self.consume(len);
+                }
+            }
+        }
+        Ok(Data::from_boxed_slice(out.into_boxed_slice()))
+    }
+
+    /// Reads the next line, without its trailing `\n` or `\r\n`.
+    ///
+    /// Returns `None` at end of file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or the line is not valid UTF-8.
+    pub async fn read_line(&mut self, priority: Priority) -> Result<Option<String>, Error> {
+        let line = self.read_until(b'\n', priority).await?;
+        if line.is_empty() {
+            return Ok(None);
+        }
+        let mut line = line.into_boxed_slice().into_vec();
+        if line.last() == Some(&b'\n') {
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+        }
+        match String::from_utf8(line) {
+            Ok(line) => Ok(Some(line)),
+            Err(e) => Err(Error(sys::Error::InvalidUtf8(e.utf8_error()))),
+        }
+    }
+
+    /// Returns a stream over the lines of this file.
+    ///
+    /// Each item is a line without its trailing `\n` or `\r\n`, as returned by
+    /// [`read_line`](BufFile::read_line). All reads are issued at `priority`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{BufFile, File, Priority};
+    ///
+    /// let file = File::open("server.log", Priority::unit_test()).await?;
+    /// let mut lines = BufFile::new(file).lines(Priority::unit_test());
+    ///
+    /// while let Some(line) = lines.next_line().await {
+    ///     let line = line?;
+    ///     if line.contains("ERROR") {
+    ///         println!("{line}");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn lines(self, priority: Priority) -> Lines {
+        Lines {
+            priority,
+            state: LinesState::Idle(Box::new(self)),
+        }
+    }
+
+    /// Seeks to a position in the file, discarding the buffer.
+    ///
+    /// `SeekFrom::Current` is interpreted relative to the logical position of
+    /// this `BufFile`, accounting for bytes that were buffered but not consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{BufFile, File, Priority};
+    /// use std::io::SeekFrom;
+    ///
+    /// let file = File::open("/dev/zero", Priority::unit_test()).await?;
+    /// let mut reader = BufFile::new(file);
+    ///
+    /// reader.read(10, Priority::unit_test()).await?;
+    /// let pos = reader.seek(SeekFrom::Current(5), Priority::unit_test()).await?;
+    /// assert_eq!(pos, 15);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn seek(&mut self, pos: SeekFrom, priority: Priority) -> Result<u64, Error> {
+        let pos = match pos {
+            SeekFrom::Current(offset) => {
+                let remainder = (self.buf.len() - self.pos) as i64;
+                SeekFrom::Current(offset - remainder)
+            }
+            other => other,
+        };
+        self.discard_buffer();
+        self.file.seek(pos, priority).await
+    }
+
+    /// Returns metadata about the underlying file.
+    ///
+    /// See [`File::metadata`].
+    pub async fn metadata(&self, priority: Priority) -> Result<Metadata, Error> {
+        self.file.metadata(priority).await
+    }
+
+    fn discard_buffer(&mut self) {
+        self.buf = Data::from_boxed_slice(Box::new([]));
+        self.pos = 0;
+    }
+}
+
+impl From<File> for BufFile {
+    fn from(file: File) -> Self {
+        BufFile::new(file)
+    }
+}
+
+type LineFuture =
+    Pin<Box<dyn Future<Output = (Box<BufFile>, Result<Option<String>, Error>)> + Send>>;
+
+/// A stream over the lines of a [`BufFile`].
+///
+/// Created by [`BufFile::lines`]. Implements [`futures_core::Stream`]; callers
+/// without a stream combinator library can use [`next_line`](Lines::next_line).
+///
+/// The stream ends after the last line, or after the first error.
+pub struct Lines {
+    priority: Priority,
+    state: LinesState,
+}
+
+enum LinesState {
+    Idle(Box<BufFile>),
+    Reading(LineFuture),
+    Done,
+}
+
+impl Lines {
+    /// Returns the next line, or `None` when the stream is exhausted.
+    pub async fn next_line(&mut self) -> Option<Result<String, Error>> {
+        std::future::poll_fn(|cx| Pin::new(&mut *self).poll_next_line(cx)).await
+    }
+
+    pub(crate) fn poll_next_line(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<String, Error>>> {
+        loop {
+            match std::mem::replace(&mut self.state, LinesState::Done) {
+                LinesState::Idle(mut reader) => {
+                    let priority = self.priority;
+                    self.state = LinesState::Reading(Box::pin(async move {
+                        let line = reader.read_line(priority).await;
+                        (reader, line)
+                    }));
+                }
+                LinesState::Reading(mut fut) => match fut.as_mut().poll(cx) {
+                    Poll::Pending => {
+                        self.state = LinesState::Reading(fut);
+                        return Poll::Pending;
+                    }
+                    Poll::Ready((reader, Ok(Some(line)))) => {
+                        self.state = LinesState::Idle(reader);
+                        return Poll::Ready(Some(Ok(line)));
+                    }
+                    Poll::Ready((_, Ok(None))) => return Poll::Ready(None),
+                    Poll::Ready((_, Err(e))) => return Poll::Ready(Some(Err(e))),
+                },
+                LinesState::Done => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+impl futures_core::Stream for Lines {
+    type Item = Result<String, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_next_line(cx)
+    }
+}
+
+impl std::fmt::Debug for Lines {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Lines")
+            .field("priority", &self.priority)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,274 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Operations over many files that report per-item outcomes.
+//!
+//! A batch that fails on its hundredth file should not throw away the ninety-nine
+//! that succeeded. The operations here keep going past individual failures and
+//! return a [`BulkResult`], so callers can use what worked and retry only what
+//! failed.
+
+use crate::walk::Walker;
+use crate::{Data, EntryKind, Error, File, Priority, SymlinkPolicy, WalkOptions, sys};
+use std::path::{Path, PathBuf};
+
+/// The outcome of an operation over many paths.
+///
+/// Each path ends up in exactly one of [`succeeded`](BulkResult::succeeded) or
+/// [`failed`](BulkResult::failed), in the order it was processed.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() {
+/// use async_file::{read_many, Priority};
+///
+/// let result = read_many(["a.json", "b.json"], Priority::unit_test()).await;
+/// for (path, data) in result.succeeded() {
+///     println!("{}: {} bytes", path.display(), data.len());
+/// }
+/// if !result.is_ok() {
+///     // try again later, but only for what didn't work
+///     let retry: Vec<_> = result.failed_paths().cloned().collect();
+///     let _ = read_many(retry, Priority::unit_test()).await;
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct BulkResult<T> {
+    succeeded: Vec<(PathBuf, T)>,
+    failed: Vec<(PathBuf, Error)>,
+}
+
+impl<T> BulkResult<T> {
+    pub(crate) fn new() -> Self {
+        BulkResult {
+            succeeded: Vec::new(),
+            failed: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, path: PathBuf, result: Result<T, Error>) {
+        match result {
+            Ok(value) => self.succeeded.push((path, value)),
+            Err(e) => self.failed.push((path, e)),
+        }
+    }
+
+    /// Returns the paths that succeeded, with their results.
+    pub fn succeeded(&self) -> &[(PathBuf, T)] {
+        &self.succeeded
+    }
+
+    /// Returns the paths that failed, with their errors.
+    pub fn failed(&self) -> &[(PathBuf, Error)] {
+        &self.failed
+    }
+
+    /// Returns the paths that failed, suitable for a retry.
+    pub fn failed_paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.failed.iter().map(|(path, _)| path)
+    }
+
+    /// Returns `true` if nothing failed.
+    pub fn is_ok(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Consumes the result, returning the paths that succeeded with their results.
+    pub fn into_succeeded(self) -> Vec<(PathBuf, T)> {
+        self.succeeded
+    }
+}
+
+/// Reads several whole files.
+///
+/// Files are read one after another at `priority`, as if by
+/// [`File::read_all`]. A file that cannot be opened or read is recorded as a
+/// failure and the remaining files are still read.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() {
+/// use async_file::{read_many, Priority};
+///
+/// let result = read_many(["config.yml", "schema.sql"], Priority::unit_test()).await;
+/// for (path, error) in result.failed() {
+///     eprintln!("couldn't read {}: {}", path.display(), error);
+/// }
+/// # }
+/// ```
+pub async fn read_many<P: AsRef<Path>>(
+    paths: impl IntoIterator<Item = P>,
+    priority: Priority,
+) -> BulkResult<Data> {
+    let mut result = BulkResult::new();
+    for path in paths {
+        let path = path.as_ref();
+        let data = match File::open(path, priority).await {
+            Ok(file) => file.read_all(priority).await,
+            Err(e) => Err(e),
+        };
+        result.push(path.to_owned(), data);
+    }
+    result
+}
+
+/// Options controlling [`copy_dir_all_with`].
+///
+/// # Dry runs
+///
+/// With [`dry_run`](CopyDirOptions::dry_run) set, the source tree is walked and
+/// reported exactly as a real copy would report it, but nothing is created or
+/// written. Each file that would be copied is reported with its current
+/// length, so sync tools and installers can show what is about to change.
+///
+/// # Symlinks
+///
+/// The source tree is walked with [`walk_dir`](crate::walk_dir) semantics, and
+/// [`symlinks`](CopyDirOptions::symlinks) chooses what happens to links. With
+/// the default [`SymlinkPolicy::NoFollow`], a link is copied as a regular file
+/// holding its target's contents, and links to directories fail to copy. With
+/// [`SymlinkPolicy::Follow`], linked directories are copied as directories.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() {
+/// use async_file::{copy_dir_all_with, CopyDirOptions, Priority};
+///
+/// let options = CopyDirOptions::new().dry_run(true);
+/// let plan = copy_dir_all_with("assets", "backup/assets", &options, Priority::unit_test()).await;
+/// for (path, len) in plan.succeeded() {
+///     println!("would copy {} ({len} bytes)", path.display());
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CopyDirOptions {
+    dry_run: bool,
+    walk: WalkOptions,
+}
+
+impl CopyDirOptions {
+    /// Creates options with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether to report what would be copied without touching the filesystem.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Returns whether this is a dry run.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Sets how symlinks and junctions in the source tree are treated.
+    pub fn symlinks(mut self, policy: SymlinkPolicy) -> Self {
+        self.walk = self.walk.symlinks(policy);
+        self
+    }
+
+    /// Sets whether to skip the contents of directories on other volumes than
+    /// `from`. See [`WalkOptions::same_volume`].
+    pub fn same_volume(mut self, same_volume: bool) -> Self {
+        self.walk = self.walk.same_volume(same_volume);
+        self
+    }
+}
+
+/// Recursively copies a directory.
+///
+/// `to` and any missing parents are created, and every file below `from` is
+/// copied to the corresponding path below `to` with [`copy`](crate::copy).
+/// Symlinks are not followed as directories; a symlink is copied as a regular
+/// file holding its target's contents.
+///
+/// Failures do not stop the copy. Each file is reported by its source path,
+/// with the number of bytes copied on success. A directory that cannot be
+/// listed or created is reported as a failure by its source path; files that
+/// would have been copied into it are not reported.
+///
+/// On WASM, where files are served over HTTP, the copy always fails.
+///
+/// This is [`copy_dir_all_with`] with default options.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() {
+/// use async_file::{copy_dir_all, Priority};
+///
+/// let result = copy_dir_all("assets", "backup/assets", Priority::unit_test()).await;
+/// println!("copied {} files", result.succeeded().len());
+/// # }
+/// ```
+pub async fn copy_dir_all(
+    from: impl AsRef<Path>,
+    to: impl AsRef<Path>,
+    priority: Priority,
+) -> BulkResult<u64> {
+    copy_dir_all_with(from, to, &CopyDirOptions::default(), priority).await
+}
+
+/// Recursively copies a directory with the given options.
+///
+/// See [`copy_dir_all`] and [`CopyDirOptions`].
+pub async fn copy_dir_all_with(
+    from: impl AsRef<Path>,
+    to: impl AsRef<Path>,
+    options: &CopyDirOptions,
+    priority: Priority,
+) -> BulkResult<u64> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    let mut result = BulkResult::new();
+    if !options.dry_run {
+        if let Err(e) = sys::create_dir_all(to, priority).await {
+            result.failed.push((from.to_owned(), Error(e)));
+            return result;
+        }
+    }
+    // Directories whose destination could not be created; their contents are skipped.
+    let mut failed_dirs: Vec<PathBuf> = Vec::new();
+    let mut walker = Walker::new(from, &options.walk, priority);
+    while let Some(next) = walker.next().await {
+        let (path, kind) = match next {
+            Ok(entry) => entry,
+            Err((path, e)) => {
+                result.failed.push((path, e));
+                continue;
+            }
+        };
+        if failed_dirs.iter().any(|dir| path.starts_with(dir)) {
+            continue;
+        }
+        let dest = to.join(path.strip_prefix(from).expect("walk stays below its root"));
+        match kind {
+            EntryKind::Dir if options.dry_run => {}
+            EntryKind::Dir => {
+                if let Err(e) = sys::create_dir_all(&dest, priority).await {
+                    failed_dirs.push(path.clone());
+                    result.failed.push((path, Error(e)));
+                }
+            }
+            _ if options.dry_run => {
+                let len = source_len(&path, priority).await;
+                result.push(path, len);
+            }
+            _ => {
+                let copied = crate::copy(&path, &dest, priority).await;
+                result.push(path, copied);
+            }
+        }
+    }
+    result
+}
+
+/// The number of bytes a copy of `path` would transfer.
+async fn source_len(path: &Path, priority: Priority) -> Result<u64, Error> {
+    Ok(crate::metadata(path, priority).await?.len())
+}
@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Interop with the [`bytes`] crate.
+//!
+//! Much of the async ecosystem (hyper, tonic, object_store) passes buffers as
+//! [`Bytes`]. Converting a [`Data`] hands its allocation to the `Bytes` as the
+//! owner, so file contents reach those libraries without being copied.
+
+use crate::Data;
+use bytes::{Buf, Bytes};
+
+impl From<Data> for Bytes {
+    /// Wraps the buffer without copying. The memory stays counted against the
+    /// [memory budget](crate::set_memory_budget) until the last `Bytes`
+    /// referring to it is dropped.
+    fn from(data: Data) -> Self {
+        Bytes::from_owner(data)
+    }
+}
+
+impl Buf for Data {
+    fn remaining(&self) -> usize {
+        self.len()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        assert!(
+            cnt <= self.len(),
+            "cannot advance past the end of Data: {cnt} > {}",
+            self.len()
+        );
+        self.range.start += cnt;
+    }
+
+    /// Shares the buffer instead of copying, like [`Data::slice`].
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        let bytes = Bytes::from(self.slice(..len));
+        self.advance(len);
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Data;
+    use bytes::{Buf, Bytes};
+
+    #[test]
+    fn test_into_bytes_shares_buffer() {
+        let data = Data::from_boxed_slice((0..10u8).collect());
+        let ptr = data.as_ptr();
+        let bytes = Bytes::from(data);
+        assert_eq!(bytes.as_ptr(), ptr);
+        assert_eq!(&bytes[..], &(0..10u8).collect::<Vec<_>>()[..]);
+    }
+
+    #[test]
+    fn test_buf() {
+        let mut data = Data::from_boxed_slice((0..10u8).collect());
+        assert_eq!(data.get_u8(), 0);
+        assert_eq!(data.get_u16(), 0x0102);
+        let ptr = data.as_ptr();
+        let taken = data.copy_to_bytes(3);
+        assert_eq!(taken.as_ptr(), ptr);
+        assert_eq!(&taken[..], &[3, 4, 5]);
+        assert_eq!(data.remaining(), 4);
+        assert_eq!(data.chunk(), &[6, 7, 8, 9]);
+    }
+}
@@ -0,0 +1,368 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! A versioned on-disk cache of derived files.
+//!
+//! Most applications that read assets also keep files derived from them:
+//! decoded images, compiled shaders, downloaded indexes. [`CacheDir`] keeps
+//! those under one directory per format version, publishes each entry with a
+//! write-then-rename so readers never see a partial file, and evicts the oldest
+//! entries once the cache outgrows its size limit.
+
+use crate::{Data, Error, File, Metadata, Priority, sys};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+/// Prefix of the file name of every published entry.
+const ENTRY_PREFIX: &str = "e-";
+/// Prefix of the file name of entries still being written.
+const TEMP_PREFIX: &str = ".tmp-";
+/// The longest file name an encoded key may produce, leaving room below the
+/// common 255-byte limit.
+const MAX_NAME_LEN: usize = 200;
+
+static NEXT_TEMP: AtomicU64 = AtomicU64::new(0);
+
+/// Options controlling [`CacheDir::open_with`].
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{CacheDir, CacheDirOptions, Priority};
+///
+/// let options = CacheDirOptions::new().max_size(512 * 1024 * 1024);
+/// let cache = CacheDir::open_with("cache/thumbnails", 3, &options, Priority::unit_test()).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CacheDirOptions {
+    max_size: Option<u64>,
+}
+
+impl CacheDirOptions {
+    /// Creates options with default values: the cache may grow without limit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the total size, in bytes, above which the oldest entries are evicted.
+    pub fn max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Returns the configured size limit, if any.
+    pub fn get_max_size(&self) -> Option<u64> {
+        self.max_size
+    }
+}
+
+/// A directory of cached files, keyed by string and tied to a format version.
+///
+/// Entries live in a subdirectory named after the version (`v3` for version 3).
+/// Opening the cache with a new version removes the directories of every other
+/// version, so a change to the format of cached data only needs a version bump.
+///
+/// # Publication
+///
+/// A new entry is written to a temporary file in the cache directory and then
+/// renamed into place, so a reader (in this process or another) sees either
+/// the whole entry or no entry at all. The file is synced to the disk before
+/// the rename, and on Unix the directory after it, so the same holds after a
+/// crash. Two tasks building the same key at once both run their builder;
+/// whichever publishes last wins.
+///
+/// # Eviction
+///
+/// With a [`max_size`](CacheDirOptions::max_size), every insertion is followed
+/// by a pass that removes the oldest entries, by the time they were published,
+/// until the cache fits again. Reading an entry does not count as a use: an
+/// entry read on every run is still evicted before one published after it.
+/// The entry just inserted is never evicted by its own insertion. The pass
+/// lists the directory and queries each entry, so it is meant for caches of
+/// hundreds or thousands of entries, not millions.
+///
+/// # Keys
+///
+/// Keys are arbitrary non-empty strings. They are escaped into file names that
+/// are safe on every platform, including case-insensitive ones; keys that would
+/// need more than 200 bytes once escaped are rejected.
+///
+/// On WASM, where files are served over HTTP, opening a cache always fails.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{CacheDir, Priority};
+///
+/// let cache = CacheDir::open("cache/shaders", 1, Priority::unit_test()).await?;
+/// let compiled = cache
+///     .get_or_insert_with("water.wgsl", || async {
+///         Ok::<_, async_file::Error>(b"compiled bytes".to_vec())
+///     }, Priority::unit_test())
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CacheDir {
+    dir: PathBuf,
+    options: CacheDirOptions,
+}
+
+impl CacheDir {
+    /// Opens the cache rooted at `root` for `version`, with default options.
+    ///
+    /// See [`open_with`](CacheDir::open_with).
+    pub async fn open(
+        root: impl AsRef<Path>,
+        version: u32,
+        priority: Priority,
+    ) -> Result<Self, Error> {
+        Self::open_with(root, version, &CacheDirOptions::new(), priority).await
+    }
+
+    /// Opens the cache rooted at `root` for `version`.
+    ///
+    /// The directory for `version` is created if needed. Directories left by
+    /// other versions are removed; failure to remove one is ignored, and it is
+    /// tried again the next time the cache is opened.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache directory cannot be created or listed.
+    pub async fn open_with(
+        root: impl AsRef<Path>,
+        version: u32,
+        options: &CacheDirOptions,
+        priority: Priority,
+    ) -> Result<Self, Error> {
+        let root = root.as_ref();
+        let current = format!("v{version}");
+        let dir = root.join(&current);
+        sys::create_dir_all(&dir, priority).await.map_err(Error)?;
+        for entry in sys::read_dir(root, priority).await.map_err(Error)? {
+            let Some(name) = entry.path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if entry.is_dir && name != current && is_version_dir(name) {
+                let _ = crate::remove_dir_all(&entry.path, priority).await;
+            }
+        }
+        Ok(CacheDir {
+            dir,
+            options: options.clone(),
+        })
+    }
+
+    /// Returns the directory holding this version's entries.
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the contents of the entry for `key`, or `None` if there is none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `key` is not a valid key, or the entry exists but
+    /// cannot be read.
+    pub async fn get(&self, key: &str, priority: Priority) -> Result<Option<Data>, Error> {
+        let path = self.entry_path(key)?;
+        match File::open(&path, priority).await {
+            Ok(file) => file.read_all(priority).await.map(Some),
+            // The entry may have been evicted between the lookup and the open.
+            Err(_) if !sys::exists(&path, priority).await => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns the contents of the entry for `key`, building and publishing it
+    /// first if there is none.
+    ///
+    /// `builder` runs only on a miss. Its error type must accept this crate's
+    /// [`Error`], which is returned for failures reading or writing the cache;
+    /// an error from `builder` itself is returned as is, and nothing is
+    /// published.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{CacheDir, File, Priority};
+    ///
+    /// let cache = CacheDir::open("cache/thumbnails", 1, Priority::unit_test()).await?;
+    /// let thumbnail = cache
+    ///     .get_or_insert_with("photos/cat.jpg", || async {
+    ///         let original = File::open("photos/cat.jpg", Priority::unit_test()).await?;
+    ///         let bytes = original.read_all(Priority::unit_test()).await?;
+    ///         Ok::<_, async_file::Error>(bytes[..bytes.len().min(1024)].to_vec())
+    ///     }, Priority::unit_test())
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_or_insert_with<F, Fut, E>(
+        &self,
+        key: &str,
+        builder: F,
+        priority: Priority,
+    ) -> Result<Data, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<u8>, E>>,
+        E: From<Error>,
+    {
+        if let Some(data) = self.get(key, priority).await? {
+            return Ok(data);
+        }
+        let contents = builder().await?;
+        let data = Data::from_boxed_slice(contents.into_boxed_slice());
+        let path = self.entry_path(key)?;
+        let data = self.publish(&path, data, priority).await?;
+        if let Some(max_size) = self.options.max_size {
+            self.evict(max_size, &path, priority).await?;
+        }
+        Ok(data)
+    }
+
+    /// Removes the entry for `key`. Returns `false` if there was none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `key` is not a valid key, or the entry exists but
+    /// cannot be removed.
+    pub async fn remove(&self, key: &str, priority: Priority) -> Result<bool, Error> {
+        let path = self.entry_path(key)?;
+        match sys::remove_file(&path, priority).await {
+            Ok(()) => Ok(true),
+            Err(_) if !sys::exists(&path, priority).await => Ok(false),
+            Err(e) => Err(Error(e)),
+        }
+    }
+
+    fn entry_path(&self, key: &str) -> Result<PathBuf, Error> {
+        encode_key(key)
+            .map(|name| self.dir.join(name))
+            .ok_or(Error(sys::Error::InvalidCacheKey))
+    }
+
+    /// Writes `data` beside `path` and renames it into place, returning it again.
+    async fn publish(&self, path: &Path, data: Data, priority: Priority) -> Result<Data, Error> {
+        let temp = self.dir.join(format!(
+            "{TEMP_PREFIX}{}-{}",
+            std::process::id(),
+            NEXT_TEMP.fetch_add(1, Ordering::Relaxed)
+        ));
+        let written = async {
+            {
+                let file = sys::WriteFile::create(&temp, priority).await?;
+                // write_all consumes its buffer, so keep a copy to hand back.
+                let copy = sys::Data::from_boxed_slice(Box::from(&*data));
+                file.write_all(copy, priority).await?;
+                // Without this, a crash soon after the rename can leave an
+                // entry in place with none of its contents.
+                file.sync_all(priority).await?;
+            }
+            // The handle is closed before the rename, which Windows requires.
+            sys::rename(&temp, path, priority).await
+        }
+        .await;
+        if let Err(e) = written {
+            let _ = sys::remove_file(&temp, priority).await;
+            return Err(Error(e));
+        }
+        sys::sync_dir(&self.dir, priority).await.map_err(Error)?;
+        Ok(data)
+    }
+
+    /// Removes the oldest entries other than `keep` until the cache fits in
+    /// `max_size` bytes.
+    async fn evict(&self, max_size: u64, keep: &Path, priority: Priority) -> Result<(), Error> {
+        let mut entries: Vec<(SystemTime, PathBuf, u64)> = Vec::new();
+        let mut total = 0;
+        for entry in sys::read_dir(&self.dir, priority).await.map_err(Error)? {
+            let is_entry = entry
+                .path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(ENTRY_PREFIX));
+            if !is_entry || entry.is_dir {
+                continue;
+            }
+            // An entry removed by someone else since the listing takes no space.
+            let Ok(metadata) = sys::symlink_metadata(&entry.path, priority).await else {
+                continue;
+            };
+            let metadata = Metadata(metadata);
+            total += metadata.len();
+            if entry.path != keep {
+                let published = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                entries.push((published, entry.path, metadata.len()));
+            }
+        }
+        entries.sort();
+        for (_, path, len) in entries {
+            if total <= max_size {
+                break;
+            }
+            match sys::remove_file(&path, priority).await {
+                Ok(()) => total -= len,
+                Err(_) if !sys::exists(&path, priority).await => total -= len,
+                Err(e) => return Err(Error(e)),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_version_dir(name: &str) -> bool {
+    name.strip_prefix('v')
+        .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Escapes `key` into an entry file name, or returns `None` if it can't be one.
+///
+/// Only lowercase ASCII letters, digits, `-` and `_` are kept; every other byte
+/// becomes `%XX`. That keeps names distinct on case-insensitive filesystems and
+/// free of dots, so they can't collide with temporary files or Windows device
+/// names once prefixed.
+fn encode_key(key: &str) -> Option<String> {
+    if key.is_empty() {
+        return None;
+    }
+    let mut name = String::from(ENTRY_PREFIX);
+    for byte in key.bytes() {
+        match byte {
+            b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' => name.push(byte as char),
+            _ => name.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    (name.len() <= MAX_NAME_LEN).then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_key, is_version_dir};
+
+    #[test]
+    fn test_encode_key() {
+        assert_eq!(encode_key("shader_1-a").as_deref(), Some("e-shader_1-a"));
+        assert_eq!(encode_key("A.b/c").as_deref(), Some("e-%41%2Eb%2Fc"));
+        assert_eq!(encode_key(""), None);
+        assert!(encode_key(&"a".repeat(198)).is_some());
+        assert_eq!(encode_key(&"a".repeat(199)), None);
+    }
+
+    #[test]
+    fn test_is_version_dir() {
+        assert!(is_version_dir("v0"));
+        assert!(is_version_dir("v12"));
+        assert!(!is_version_dir("v"));
+        assert!(!is_version_dir("vx"));
+        assert!(!is_version_dir("data"));
+    }
+}
@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Capabilities of the filesystem containing a path.
+
+use crate::{Error, Priority, sys};
+use std::path::Path;
+
+/// What the filesystem containing a path can do, as reported by [`probe`].
+///
+/// Higher-level tools can use this to pick a strategy up front (say, skip
+/// reflink copies on a filesystem without them, or avoid names that differ
+/// only by case) instead of discovering the limitation through a failure.
+///
+/// Capabilities are determined from the filesystem type and what the platform
+/// reports for it, without creating or modifying anything. A `true` answer
+/// means the filesystem type supports the feature; a particular volume may
+/// still have it disabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FsCapabilities {
+    pub(crate) case_sensitive: bool,
+    pub(crate) max_filename_len: usize,
+    pub(crate) reflink: bool,
+    pub(crate) sparse: bool,
+    pub(crate) xattr: bool,
+}
+
+impl FsCapabilities {
+    /// Returns `true` if names differing only by case refer to different files.
+    pub fn case_sensitive(&self) -> bool {
+        self.case_sensitive
+    }
+
+    /// Returns the maximum length of a single path component.
+    ///
+    /// The unit is platform-specific: bytes on Unix, UTF-16 code units on Windows.
+    pub fn max_filename_len(&self) -> usize {
+        self.max_filename_len
+    }
+
+    /// Returns `true` if files can share extents through copy-on-write clones
+    /// (reflinks on Linux, `clonefile` on macOS, block cloning on Windows).
+    pub fn supports_reflink(&self) -> bool {
+        self.reflink
+    }
+
+    /// Returns `true` if files can contain holes that occupy no disk space.
+    pub fn supports_sparse(&self) -> bool {
+        self.sparse
+    }
+
+    /// Returns `true` if files can carry extended attributes (alternate data
+    /// streams on Windows).
+    pub fn supports_xattr(&self) -> bool {
+        self.xattr
+    }
+}
+
+/// Reports the capabilities of the filesystem containing `path`.
+///
+/// `path` must exist; it may be a file or a directory.
+///
+/// # Arguments
+///
+/// * `path` - Any existing path on the filesystem of interest
+/// * `priority` - The priority for this operation
+///
+/// # Errors
+///
+/// Returns an error if `path` does not exist or the filesystem cannot be
+/// queried. On WASM, where files are served over HTTP, this always fails.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{probe, Priority};
+///
+/// let caps = probe("/tmp", Priority::unit_test()).await?;
+/// if !caps.case_sensitive() {
+///     println!("watch out for README vs readme");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn probe(path: impl AsRef<Path>, priority: Priority) -> Result<FsCapabilities, Error> {
+    sys::probe(path, priority).await.map_err(Error)
+}
@@ -0,0 +1,77 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Feeding a file into a bounded channel.
+//!
+//! A producer that reads as fast as the disk allows will happily buffer a whole
+//! file in memory when its consumer (a network upload, a decoder) is slower.
+//! [`stream_to_channel`] only reads the next chunk once the channel has room
+//! for it, so memory use is bounded by the channel's capacity.
+
+use crate::{Data, Error, File, Priority};
+
+/// Reads `file` from its current position to the end, sending it to `tx` in
+/// chunks of at most `chunk` bytes.
+///
+/// Each chunk is read only after the previous one has been accepted by the
+/// channel, so with a bounded channel at most its capacity plus one chunk is
+/// held in memory at once. `tx` is dropped on return; if it was the last
+/// sender, receivers then see the end of the stream.
+///
+/// If every receiver is dropped, reading stops early and the bytes delivered
+/// so far are returned.
+///
+/// # Returns
+///
+/// The number of bytes sent.
+///
+/// # Errors
+///
+/// Returns an error if a read fails. Chunks sent before the failure have
+/// already been delivered.
+///
+/// # Panics
+///
+/// Panics if `chunk` is 0.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{stream_to_channel, File, Priority};
+///
+/// let file = File::open("upload.bin", Priority::unit_test()).await?;
+/// let (tx, rx) = async_channel::bounded(4);
+/// let consumer = async move {
+///     while let Ok(chunk) = rx.recv().await {
+///         // upload `chunk`...
+///     }
+/// };
+/// // run `consumer` on your executor, then:
+/// let sent = stream_to_channel(&file, 64 * 1024, tx, Priority::unit_test()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn stream_to_channel(
+    file: &File,
+    chunk: usize,
+    tx: async_channel::Sender<Data>,
+    priority: Priority,
+) -> Result<u64, Error> {
+    assert!(chunk > 0, "chunk size must be nonzero");
+    let mut sent = 0;
+    loop {
+        // Don't read a chunk nobody will receive.
+        if tx.is_closed() {
+            return Ok(sent);
+        }
+        let data = file.read(chunk, priority).await?;
+        if data.is_empty() {
+            return Ok(sent);
+        }
+        let len = data.len() as u64;
+        if tx.send(data).await.is_err() {
+            return Ok(sent);
+        }
+        sent += len;
+    }
+}
@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Read coalescing across [`File`](crate::File)s that refer to the same path.
+//!
+//! Shared assets are often opened by many tasks at once, each reading the same
+//! header or the same whole file. When coalescing is enabled, a read that exactly
+//! matches one already in flight (same path, offset and length) waits for that
+//! read instead of issuing its own, and receives a copy of its result.
+//!
+//! Coalescing is off by default; see [`set_read_coalescing`], and
+//! [`OpenOptions::coalesce`](crate::OpenOptions::coalesce) to choose for one
+//! file.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::task::{Poll, Waker};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static IN_FLIGHT: LazyLock<Mutex<HashMap<Key, Arc<Slot>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Enables or disables read coalescing for all files, except those opened
+/// with [`OpenOptions::coalesce`](crate::OpenOptions::coalesce).
+///
+/// When enabled, concurrent reads of the same range of the same path share a
+/// single physical read. The first read performs the I/O; the others wait for
+/// it and receive a copy of the bytes, with their own file position advanced
+/// as if they had performed the read themselves.
+///
+/// # Matching
+///
+/// Reads are matched on the path passed to [`File::open`](crate::File::open),
+/// the file position, and the requested length. Paths are compared as given,
+/// so `assets/a.png` and `./assets/a.png` do not coalesce.
+///
+/// # Failure
+///
+/// If the shared read fails or is cancelled, waiting reads fall back to
+/// performing their own I/O, so errors are never shared between files.
+///
+/// # Examples
+///
+/// ```
+/// use async_file::set_read_coalescing;
+///
+/// // Many tasks are about to load the same assets
+/// set_read_coalescing(true);
+/// ```
+pub fn set_read_coalescing(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns whether read coalescing is enabled.
+pub fn read_coalescing() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Key {
+    path: PathBuf,
+    offset: u64,
+    len: usize,
+}
+
+/// The shared state for one in-flight physical read.
+#[derive(Debug, Default)]
+pub(crate) struct Slot {
+    state: Mutex<SlotState>,
+}
+
+#[derive(Debug, Default)]
+struct SlotState {
+    /// `Some(None)` once the leader failed or was dropped.
+    outcome: Option<Option<Arc<[u8]>>>,
+    wakers: Vec<Waker>,
+}
+
+/// How a read should proceed.
+pub(crate) enum Role {
+    /// Perform the physical read and publish it through the guard.
+    Leader(LeaderGuard),
+    /// Wait on the slot for another file's read.
+    Follower(Arc<Slot>),
+}
+
+/// Registers a read of `len` bytes at `offset` in `path`.
+pub(crate) fn join(path: &Path, offset: u64, len: usize) -> Role {
+    let key = Key {
+        path: path.to_owned(),
+        offset,
+        len,
+    };
+    let mut in_flight = IN_FLIGHT.lock().unwrap();
+    if let Some(slot) = in_flight.get(&key) {
+        return Role::Follower(slot.clone());
+    }
+    let slot = Arc::new(Slot::default());
+    in_flight.insert(key.clone(), slot.clone());
+    Role::Leader(LeaderGuard {
+        key: Some(key),
+        slot,
+    })
+}
+
+/// Publishes the leader's result. Dropping the guard without calling
+/// [`finish`](LeaderGuard::finish) releases any followers to read on their own.
+pub(crate) struct LeaderGuard {
+    key: Option<Key>,
+    slot: Arc<Slot>,
+}
+
+impl LeaderGuard {
+    /// Publishes `bytes` (or failure, if `None`) to any waiting followers.
+    pub(crate) fn finish(mut self, bytes: Option<&[u8]>) {
+        self.publish(bytes);
+    }
+
+    fn publish(&mut self, bytes: Option<&[u8]>) {
+        let Some(key) = self.key.take() else {
+            return;
+        };
+        IN_FLIGHT.lock().unwrap().remove(&key);
+        // Only pay for the copy if somebody is actually waiting.
+        let shared = if Arc::strong_count(&self.slot) > 1 {
+            bytes.map(Arc::from)
+        } else {
+            None
+        };
+        let wakers = {
+            let mut state = self.slot.state.lock().unwrap();
+            state.outcome = Some(shared);
+            std::mem::take(&mut state.wakers)
+        };
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+impl Drop for LeaderGuard {
+    fn drop(&mut self) {
+        self.publish(None);
+    }
+}
+
+/// Waits for the leader of `slot`. Returns `None` if the leader failed.
+pub(crate) async fn wait(slot: &Slot) -> Option<Arc<[u8]>> {
+    std::future::poll_fn(|cx| {
+        let mut state = slot.state.lock().unwrap();
+        match &state.outcome {
+            Some(outcome) => Poll::Ready(outcome.clone()),
+            None => {
+                if !state.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                    state.wakers.push(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+        }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Role, join, wait};
+    use std::path::Path;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_follower_receives_leader_bytes() {
+        let path = Path::new("coalesce/test_follower_receives_leader_bytes");
+        let Role::Leader(leader) = join(path, 0, 4) else {
+            panic!("first read should lead");
+        };
+        let Role::Follower(slot) = join(path, 0, 4) else {
+            panic!("matching read should follow");
+        };
+        assert!(matches!(join(path, 4, 4), Role::Leader(_)));
+        leader.finish(Some(&[1, 2, 3, 4]));
+        let bytes = test_executors::spin_on(wait(&slot));
+        assert_eq!(bytes.as_deref(), Some(&[1, 2, 3, 4][..]));
+        // the key was released once the leader finished
+        assert!(matches!(join(path, 0, 4), Role::Leader(_)));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_dropped_leader_releases_followers() {
+        let path = Path::new("coalesce/test_dropped_leader_releases_followers");
+        let Role::Leader(leader) = join(path, 0, 4) else {
+            panic!("first read should lead");
+        };
+        let Role::Follower(slot) = join(path, 0, 4) else {
+            panic!("matching read should follow");
+        };
+        drop(leader);
+        assert_eq!(test_executors::spin_on(wait(&slot)), None);
+    }
+}
@@ -0,0 +1,171 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Limits on how many operations run at once, and how queued ones age.
+
+use crate::{BANDS, Priority, rank, sys};
+use std::time::Duration;
+
+/// How many native filesystem calls may run at once, overall and for each
+/// priority, applied with [`set_config`].
+///
+/// [`max_in_flight`](Config::max_in_flight) is the worker thread limit also
+/// set by [`set_worker_threads`](crate::set_worker_threads).
+/// [`max_in_flight_at`](Config::max_in_flight_at) caps the calls of one
+/// priority: while that many are running, the rest wait in the queue even if
+/// threads are free, so a batch job issuing thousands of background reads
+/// keeps to a few at a time. [`Priority::Utility`] and [`Priority::Unknown`]
+/// share one limit, as they share a place in the queue.
+///
+/// The calls run on worker threads of this crate's own, not a pool shared with
+/// other crates, so their blocking work can't hold up file reads.
+/// [`max_queue`](Config::max_queue) bounds how many calls may wait for a
+/// thread; callers beyond it wait for room before their calls are queued.
+///
+/// With [`aging`](Config::aging), a queued call moves up one priority for
+/// every step it waits, so a steady stream of urgent calls can't starve less
+/// urgent ones. [`max_queue_wait`] shows how long each priority has waited.
+///
+/// On WASM, where operations are HTTP requests, the config has no effect.
+///
+/// # Examples
+///
+/// ```
+/// use async_file::{Config, Priority, set_config};
+///
+/// // At most 2 background reads at once, out of 16 overall.
+/// set_config(
+///     Config::new()
+///         .max_in_flight(16)
+///         .max_in_flight_at(Priority::Background, 2),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    max_in_flight: usize,
+    /// Indexed by rank.
+    bands: [Option<usize>; BANDS],
+    max_queue: Option<usize>,
+    aging: Option<Duration>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            max_in_flight: 32,
+            bands: [None; BANDS],
+            max_queue: None,
+            aging: None,
+        }
+    }
+}
+
+impl Config {
+    /// Creates a config with the defaults: 32 calls at once, no limit for any
+    /// one priority, an unbounded queue, and no aging.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how many calls may run at once overall. Values below 1 are
+    /// treated as 1.
+    pub fn max_in_flight(mut self, max: usize) -> Self {
+        self.max_in_flight = max.max(1);
+        self
+    }
+
+    /// Sets how many calls at `priority` may run at once. Values below 1 are
+    /// treated as 1.
+    pub fn max_in_flight_at(mut self, priority: Priority, max: usize) -> Self {
+        self.bands[rank(priority) as usize] = Some(max.max(1));
+        self
+    }
+
+    /// Sets how many calls may wait in the queue at once. Values below 1 are
+    /// treated as 1.
+    ///
+    /// Once the queue is full, a new call waits until a queued one starts.
+    /// Calls waiting for room are not ordered by priority; only once queued
+    /// do they take their place.
+    pub fn max_queue(mut self, max: usize) -> Self {
+        self.max_queue = Some(max.max(1));
+        self
+    }
+
+    /// Returns how many calls may wait in the queue at once, if bounded.
+    pub fn get_max_queue(&self) -> Option<usize> {
+        self.max_queue
+    }
+
+    /// Moves a queued call up one priority for every `step` it waits, until it
+    /// reaches [`Priority::UserInteractive`]. A zero step turns aging off.
+    ///
+    /// An aged call runs ahead of newer calls of the priority it has reached,
+    /// but still counts against its own priority's
+    /// [`max_in_flight_at`](Config::max_in_flight_at) limit.
+    pub fn aging(mut self, step: Duration) -> Self {
+        self.aging = Some(step).filter(|step| !step.is_zero());
+        self
+    }
+
+    /// Returns how long a queued call waits for each priority it moves up,
+    /// if aging is on.
+    pub fn get_aging(&self) -> Option<Duration> {
+        self.aging
+    }
+
+    /// Returns how many calls may run at once overall.
+    pub fn get_max_in_flight(&self) -> usize {
+        self.max_in_flight
+    }
+
+    /// Returns how many calls at `priority` may run at once, if limited.
+    pub fn get_max_in_flight_at(&self, priority: Priority) -> Option<usize> {
+        self.bands[rank(priority) as usize]
+    }
+}
+
+/// Applies `config` to the worker pool.
+///
+/// Calls already running are not interrupted when a limit is lowered; new
+/// calls wait until the running ones are back under it.
+pub fn set_config(config: Config) {
+    sys::set_worker_threads(config.max_in_flight);
+    sys::set_band_limits(config.bands);
+    sys::set_max_queue(config.max_queue);
+    sys::set_aging(config.aging);
+}
+
+/// Returns the limits in effect, including any set with
+/// [`set_worker_threads`](crate::set_worker_threads).
+///
+/// On WASM this is always the default config.
+pub fn config() -> Config {
+    let mut config = Config::default();
+    let threads = sys::worker_threads();
+    if threads > 0 {
+        config.max_in_flight = threads;
+    }
+    config.bands = sys::band_limits();
+    config.max_queue = sys::max_queue();
+    config.aging = sys::aging();
+    config
+}
+
+/// Returns the longest any native filesystem call at `priority` has waited in
+/// the queue before starting, since the program started.
+///
+/// [`Priority::Utility`] and [`Priority::Unknown`] share one figure, as they
+/// share a place in the queue. A call is counted under its own priority, not
+/// one [`aging`](Config::aging) moved it up to. On WASM this is always zero.
+///
+/// # Examples
+///
+/// ```
+/// use async_file::{Priority, max_queue_wait};
+///
+/// let waited = max_queue_wait(Priority::Background);
+/// println!("background calls have waited up to {waited:?}");
+/// ```
+pub fn max_queue_wait(priority: Priority) -> Duration {
+    sys::max_queue_waits()[rank(priority) as usize]
+}
@@ -0,0 +1,478 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! A conformance suite for file backends.
+//!
+//! The crate documents specific semantics for opening, reading, seeking and
+//! metadata: reads at end of file return empty data rather than failing,
+//! seeking before the start is an error, and so on. Code that provides the same
+//! operations some other way (an in-memory backend for tests, a backend over
+//! an archive or an object store) can implement [`Backend`] and call [`run`]
+//! to check that it behaves the same.
+//!
+//! The crate's own [`File`] is checked through [`DefaultBackend`].
+//!
+//! This module requires the `conformance` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! # async fn example() {
+//! use async_file::conformance::{run, DefaultBackend, Fixture};
+//!
+//! let fixture = Fixture::new("testdata/sample.bin", std::fs::read("testdata/sample.bin").unwrap())
+//!     .missing("testdata/does-not-exist");
+//! let report = run(&DefaultBackend, &fixture).await;
+//! report.assert_passed();
+//! # }
+//! ```
+
+use crate::{Data, Error, File, Priority};
+use std::fmt;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+
+/// The operations checked by the conformance suite.
+///
+/// Implementations should follow the semantics documented on [`File`]; [`run`]
+/// checks them.
+#[allow(async_fn_in_trait)]
+pub trait Backend {
+    /// An open file.
+    type File;
+    /// The bytes returned by a read.
+    type Data: AsRef<[u8]>;
+    /// The error type for failed operations.
+    type Error: fmt::Debug;
+
+    /// Opens the file at `path` for reading.
+    async fn open(&self, path: &Path, priority: Priority) -> Result<Self::File, Self::Error>;
+
+    /// Reads up to `len` bytes from the current position, advancing it.
+    async fn read(
+        &self,
+        file: &mut Self::File,
+        len: usize,
+        priority: Priority,
+    ) -> Result<Self::Data, Self::Error>;
+
+    /// Moves the current position, returning the new position.
+    async fn seek(
+        &self,
+        file: &mut Self::File,
+        pos: SeekFrom,
+        priority: Priority,
+    ) -> Result<u64, Self::Error>;
+
+    /// Returns the length of the file in bytes.
+    async fn len(&self, file: &mut Self::File, priority: Priority) -> Result<u64, Self::Error>;
+}
+
+/// The crate's own [`File`], as a [`Backend`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultBackend;
+
+impl Backend for DefaultBackend {
+    type File = File;
+    type Data = Data;
+    type Error = Error;
+
+    async fn open(&self, path: &Path, priority: Priority) -> Result<File, Error> {
+        File::open(path, priority).await
+    }
+
+    async fn read(&self, file: &mut File, len: usize, priority: Priority) -> Result<Data, Error> {
+        file.read(len, priority).await
+    }
+
+    async fn seek(&self, file: &mut File, pos: SeekFrom, priority: Priority) -> Result<u64, Error> {
+        file.seek(pos, priority).await
+    }
+
+    async fn len(&self, file: &mut File, priority: Priority) -> Result<u64, Error> {
+        Ok(file.metadata(priority).await?.len())
+    }
+}
+
+/// The files the suite runs against.
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    path: PathBuf,
+    contents: Vec<u8>,
+    missing: Option<PathBuf>,
+}
+
+impl Fixture {
+    /// Describes a readable file at `path` whose contents are `contents`.
+    ///
+    /// The file should be at least a few bytes long; a few KiB exercises chunked
+    /// reads better.
+    pub fn new(path: impl Into<PathBuf>, contents: impl Into<Vec<u8>>) -> Self {
+        Fixture {
+            path: path.into(),
+            contents: contents.into(),
+            missing: None,
+        }
+    }
+
+    /// Adds a path that must not exist, enabling the open-failure check.
+    pub fn missing(mut self, path: impl Into<PathBuf>) -> Self {
+        self.missing = Some(path.into());
+        self
+    }
+}
+
+/// The outcome of one check.
+#[derive(Debug, Clone)]
+pub struct Check {
+    name: &'static str,
+    failure: Option<String>,
+}
+
+impl Check {
+    /// Returns the name of the check.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns why the check failed, or `None` if it passed.
+    pub fn failure(&self) -> Option<&str> {
+        self.failure.as_deref()
+    }
+}
+
+/// The outcome of a conformance run.
+#[derive(Debug, Clone)]
+pub struct Report {
+    checks: Vec<Check>,
+}
+
+impl Report {
+    /// Returns every check that ran, in order.
+    pub fn checks(&self) -> &[Check] {
+        &self.checks
+    }
+
+    /// Returns the checks that failed.
+    pub fn failures(&self) -> impl Iterator<Item = &Check> {
+        self.checks.iter().filter(|check| check.failure.is_some())
+    }
+
+    /// Returns `true` if every check passed.
+    pub fn passed(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Panics with a description of every failed check, if any failed.
+    #[track_caller]
+    pub fn assert_passed(&self) {
+        assert!(self.passed(), "backend does not conform:\n{self}");
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            match &check.failure {
+                None => writeln!(f, "ok   {}", check.name)?,
+                Some(why) => writeln!(f, "FAIL {}: {why}", check.name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+type CheckResult = Result<(), String>;
+
+macro_rules! ensure {
+    ($cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            return Err(format!($($arg)+));
+        }
+    };
+}
+
+/// Runs every check against `backend`, using the files described by `fixture`.
+///
+/// Checks are independent: each opens the fixture afresh, and a failing check
+/// does not stop the others.
+pub async fn run<B: Backend>(backend: &B, fixture: &Fixture) -> Report {
+    let priority = Priority::unit_test();
+    let mut checks = Vec::new();
+    let mut record = |name: &'static str, result: CheckResult| {
+        checks.push(Check {
+            name,
+            failure: result.err(),
+        });
+    };
+    if let Some(missing) = &fixture.missing {
+        record(
+            "open_missing_fails",
+            open_missing_fails(backend, missing, priority).await,
+        );
+    }
+    record("len_matches", len_matches(backend, fixture, priority).await);
+    record(
+        "read_sequential",
+        read_sequential(backend, fixture, priority).await,
+    );
+    record(
+        "read_zero_is_empty",
+        read_zero_is_empty(backend, fixture, priority).await,
+    );
+    record(
+        "read_at_eof_is_empty",
+        read_at_eof_is_empty(backend, fixture, priority).await,
+    );
+    record("seek_start", seek_start(backend, fixture, priority).await);
+    record(
+        "seek_current",
+        seek_current(backend, fixture, priority).await,
+    );
+    record("seek_end", seek_end(backend, fixture, priority).await);
+    record(
+        "seek_before_start_fails",
+        seek_before_start_fails(backend, fixture, priority).await,
+    );
+    Report { checks }
+}
+
+async fn open<B: Backend>(
+    backend: &B,
+    fixture: &Fixture,
+    priority: Priority,
+) -> Result<B::File, String> {
+    backend
+        .open(&fixture.path, priority)
+        .await
+        .map_err(|e| format!("open failed: {e:?}"))
+}
+
+/// Reads until a read returns no bytes.
+async fn read_to_end<B: Backend>(
+    backend: &B,
+    file: &mut B::File,
+    chunk: usize,
+    limit: usize,
+    priority: Priority,
+) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    loop {
+        let data = backend
+            .read(file, chunk, priority)
+            .await
+            .map_err(|e| format!("read failed after {} bytes: {e:?}", out.len()))?;
+        let data = data.as_ref();
+        ensure!(
+            data.len() <= chunk,
+            "read of {chunk} returned {} bytes",
+            data.len()
+        );
+        if data.is_empty() {
+            return Ok(out);
+        }
+        out.extend_from_slice(data);
+        ensure!(
+            out.len() <= limit,
+            "read more than the file's {limit} bytes"
+        );
+    }
+}
+
+async fn open_missing_fails<B: Backend>(
+    backend: &B,
+    missing: &Path,
+    priority: Priority,
+) -> CheckResult {
+    ensure!(
+        backend.open(missing, priority).await.is_err(),
+        "opening {} succeeded",
+        missing.display()
+    );
+    Ok(())
+}
+
+async fn len_matches<B: Backend>(
+    backend: &B,
+    fixture: &Fixture,
+    priority: Priority,
+) -> CheckResult {
+    let mut file = open(backend, fixture, priority).await?;
+    let len = backend
+        .len(&mut file, priority)
+        .await
+        .map_err(|e| format!("len failed: {e:?}"))?;
+    ensure!(
+        len == fixture.contents.len() as u64,
+        "len is {len}, expected {}",
+        fixture.contents.len()
+    );
+    Ok(())
+}
+
+async fn read_sequential<B: Backend>(
+    backend: &B,
+    fixture: &Fixture,
+    priority: Priority,
+) -> CheckResult {
+    // An odd chunk size, so reads straddle any internal page or chunk boundary.
+    for chunk in [7, fixture.contents.len() + 1] {
+        let mut file = open(backend, fixture, priority).await?;
+        let read = read_to_end(backend, &mut file, chunk, fixture.contents.len(), priority).await?;
+        ensure!(
+            read == fixture.contents,
+            "reading in chunks of {chunk} returned different contents"
+        );
+    }
+    Ok(())
+}
+
+async fn read_zero_is_empty<B: Backend>(
+    backend: &B,
+    fixture: &Fixture,
+    priority: Priority,
+) -> CheckResult {
+    let mut file = open(backend, fixture, priority).await?;
+    let data = backend
+        .read(&mut file, 0, priority)
+        .await
+        .map_err(|e| format!("read of 0 failed: {e:?}"))?;
+    ensure!(data.as_ref().is_empty(), "read of 0 returned bytes");
+    Ok(())
+}
+
+async fn read_at_eof_is_empty<B: Backend>(
+    backend: &B,
+    fixture: &Fixture,
+    priority: Priority,
+) -> CheckResult {
+    let mut file = open(backend, fixture, priority).await?;
+    read_to_end(backend, &mut file, 4096, fixture.contents.len(), priority).await?;
+    for attempt in 0..2 {
+        let data = backend
+            .read(&mut file, 16, priority)
+            .await
+            .map_err(|e| format!("read at end of file failed: {e:?}"))?;
+        ensure!(
+            data.as_ref().is_empty(),
+            "read #{attempt} at end of file returned bytes"
+        );
+    }
+    Ok(())
+}
+
+async fn seek<B: Backend>(
+    backend: &B,
+    file: &mut B::File,
+    pos: SeekFrom,
+    priority: Priority,
+) -> Result<u64, String> {
+    backend
+        .seek(file, pos, priority)
+        .await
+        .map_err(|e| format!("seek to {pos:?} failed: {e:?}"))
+}
+
+async fn seek_start<B: Backend>(backend: &B, fixture: &Fixture, priority: Priority) -> CheckResult {
+    let mut file = open(backend, fixture, priority).await?;
+    let len = fixture.contents.len();
+    let mid = len / 2;
+    ensure!(
+        seek(backend, &mut file, SeekFrom::Start(mid as u64), priority).await? == mid as u64,
+        "seek returned the wrong position"
+    );
+    let rest = read_to_end(backend, &mut file, 64, len, priority).await?;
+    ensure!(
+        rest == fixture.contents[mid..],
+        "read after seek returned the wrong bytes"
+    );
+    // back to the beginning, after reading to the end
+    ensure!(
+        seek(backend, &mut file, SeekFrom::Start(0), priority).await? == 0,
+        "seek returned the wrong position"
+    );
+    let all = read_to_end(backend, &mut file, 64, len, priority).await?;
+    ensure!(
+        all == fixture.contents,
+        "read after rewinding returned the wrong bytes"
+    );
+    Ok(())
+}
+
+async fn seek_current<B: Backend>(
+    backend: &B,
+    fixture: &Fixture,
+    priority: Priority,
+) -> CheckResult {
+    let mut file = open(backend, fixture, priority).await?;
+    let len = fixture.contents.len();
+    let first = backend
+        .read(&mut file, 2, priority)
+        .await
+        .map_err(|e| format!("read failed: {e:?}"))?;
+    let consumed = first.as_ref().len();
+    let here = seek(backend, &mut file, SeekFrom::Current(0), priority).await?;
+    ensure!(
+        here == consumed as u64,
+        "position after reading {consumed} bytes is {here}"
+    );
+    if consumed > 0 {
+        let back = seek(backend, &mut file, SeekFrom::Current(-1), priority).await?;
+        ensure!(
+            back == here - 1,
+            "seeking back by 1 from {here} went to {back}"
+        );
+        let rest = read_to_end(backend, &mut file, 64, len, priority).await?;
+        ensure!(
+            rest == fixture.contents[consumed - 1..],
+            "read after relative seek returned the wrong bytes"
+        );
+    }
+    Ok(())
+}
+
+async fn seek_end<B: Backend>(backend: &B, fixture: &Fixture, priority: Priority) -> CheckResult {
+    let mut file = open(backend, fixture, priority).await?;
+    let len = fixture.contents.len();
+    let end = seek(backend, &mut file, SeekFrom::End(0), priority).await?;
+    ensure!(end == len as u64, "end of file is at {end}, expected {len}");
+    let tail = len.min(3);
+    let pos = seek(backend, &mut file, SeekFrom::End(-(tail as i64)), priority).await?;
+    ensure!(pos == (len - tail) as u64, "seek from end went to {pos}");
+    let rest = read_to_end(backend, &mut file, 64, len, priority).await?;
+    ensure!(
+        rest == fixture.contents[len - tail..],
+        "read after seek from end returned the wrong bytes"
+    );
+    Ok(())
+}
+
+async fn seek_before_start_fails<B: Backend>(
+    backend: &B,
+    fixture: &Fixture,
+    priority: Priority,
+) -> CheckResult {
+    let mut file = open(backend, fixture, priority).await?;
+    ensure!(
+        backend
+            .seek(&mut file, SeekFrom::Current(-1), priority)
+            .await
+            .is_err(),
+        "seeking before the start of the file succeeded"
+    );
+    Ok(())
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::{DefaultBackend, Fixture, run};
+
+    #[test_executors::async_test]
+    async fn test_default_backend_conforms() {
+        logwise::context::Context::reset("test_default_backend_conforms".to_string());
+        let path = crate::tests::temp_path("conformance");
+        let contents: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&path, &contents).unwrap();
+        let fixture = Fixture::new(&*path, contents).missing(path.with_extension("missing"));
+        run(&DefaultBackend, &fixture).await.assert_passed();
+    }
+}
@@ -0,0 +1,424 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! A local HTTP server for testing the WASM backend.
+//!
+//! On WASM, files are fetched over HTTP, so exercising that backend needs a
+//! server with correct `HEAD` and `Range` behavior. [`DevServer`] serves a local
+//! directory with exactly those semantics, plus the CORS headers a browser
+//! needs, so integration tests don't depend on a third-party host being
+//! reachable.
+//!
+//! The server runs on native platforms only. Start it from a native test
+//! harness or the `devserver` example, and point WASM tests at
+//! [`DevServer::origin`].
+//!
+//! This module requires the `devserver` feature. It is a test fixture: it is
+//! small, single-purpose, and not hardened for use on a network.
+//!
+//! # Examples
+//!
+//! ```
+//! use async_file::devserver::DevServer;
+//!
+//! let server = DevServer::serve("tests/fixtures").unwrap();
+//! println!("serving on {}", server.origin());
+//! // The server stops when dropped.
+//! ```
+
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Serves a directory over HTTP with `Range` and `HEAD` support.
+///
+/// # Semantics
+///
+/// - `GET` returns the file, or `206 Partial Content` for a satisfiable
+///   single `Range: bytes=` request (`a-b`, `a-`, or `-n`). Multiple ranges are
+///   ignored and the whole file is returned.
+/// - A range starting at or beyond the end of the file gets
+///   `416 Range Not Satisfiable` with `Content-Range: bytes */len`.
+/// - `HEAD` returns the same headers as `GET`, without a body. Both include
+///   `Last-Modified`.
+/// - Missing files, directories, and paths escaping the root get `404`.
+/// - Every response allows any origin and exposes `Content-Length`,
+///   `Content-Range` and `Accept-Ranges` to scripts.
+///
+/// Each connection handles one request on its own thread.
+#[derive(Debug)]
+pub struct DevServer {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl DevServer {
+    /// Serves `root` on an ephemeral port on the loopback interface.
+    pub fn serve(root: impl Into<PathBuf>) -> io::Result<DevServer> {
+        DevServer::bind(root, (Ipv4Addr::LOCALHOST, 0))
+    }
+
+    /// Serves `root` on the given address.
+    pub fn bind(root: impl Into<PathBuf>, addr: impl ToSocketAddrs) -> io::Result<DevServer> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let root: PathBuf = root.into();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let thread = std::thread::Builder::new()
+            .name("async_file devserver".to_string())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    if thread_stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let Ok(stream) = stream else {
+                        continue;
+                    };
+                    let root = root.clone();
+                    std::thread::spawn(move || {
+                        // A client hanging up mid-response is not our problem.
+                        let _ = handle(stream, &root);
+                    });
+                }
+            })?;
+        Ok(DevServer {
+            addr,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Returns the address the server is listening on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the server's origin, such as `http://127.0.0.1:49152/`.
+    ///
+    /// [`set_default_origin`](crate::set_default_origin) takes a `&'static str`;
+    /// leak the string (`String::leak`) to pass it there from a test.
+    pub fn origin(&self) -> String {
+        format!("http://{}/", self.addr)
+    }
+}
+
+impl Drop for DevServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // Wake the accept loop so it sees the flag.
+        let mut wake = self.addr;
+        if wake.ip().is_unspecified() {
+            wake.set_ip(Ipv4Addr::LOCALHOST.into());
+        }
+        let _ = TcpStream::connect(wake);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn handle(stream: TcpStream, root: &Path) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return respond(&stream, "400 Bad Request", &[], None);
+    };
+    let mut range = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("range") {
+                range = Some(value.trim().to_string());
+            }
+        }
+    }
+
+    match method {
+        "GET" | "HEAD" => {}
+        "OPTIONS" => {
+            let headers = [
+                (
+                    "Access-Control-Allow-Methods",
+                    "GET, HEAD, OPTIONS".to_string(),
+                ),
+                ("Access-Control-Allow-Headers", "*".to_string()),
+            ];
+            return respond(&stream, "204 No Content", &headers, None);
+        }
+        _ => return respond(&stream, "405 Method Not Allowed", &[], None),
+    }
+    let Some(path) = resolve(root, target) else {
+        return respond(&stream, "404 Not Found", &[], None);
+    };
+    let Ok(mut file) = fs::File::open(&path) else {
+        return respond(&stream, "404 Not Found", &[], None);
+    };
+    let len = file.metadata()?.len();
+
+    let (status, start, end, content_range) = match range.as_deref().map(|r| parse_range(r, len)) {
+        None | Some(Range::Ignored) => ("200 OK", 0, len, None),
+        Some(Range::Unsatisfiable) => {
+            let headers = [("Content-Range", format!("bytes */{len}"))];
+            return respond(&stream, "416 Range Not Satisfiable", &headers, None);
+        }
+        Some(Range::Bytes(start, end)) => (
+            "206 Partial Content",
+            start,
+            end,
+            Some(format!("bytes {start}-{}/{len}", end - 1)),
+        ),
+    };
+    let mut headers = vec![
+        ("Content-Length", (end - start).to_string()),
+        ("Content-Type", "application/octet-stream".to_string()),
+    ];
+    if let Ok(modified) = file.metadata()?.modified() {
+        headers.push(("Last-Modified", http_date(modified)));
+    }
+    if let Some(content_range) = content_range {
+        headers.push(("Content-Range", content_range));
+    }
+    if method == "HEAD" {
+        return respond(&stream, status, &headers, None);
+    }
+    file.seek(SeekFrom::Start(start))?;
+    respond(&stream, status, &headers, Some(&mut file.take(end - start)))
+}
+
+fn respond(
+    mut stream: &TcpStream,
+    status: &str,
+    headers: &[(&str, String)],
+    body: Option<&mut dyn Read>,
+) -> io::Result<()> {
+    let mut head = format!(
+        "HTTP/1.1 {status}\r\n\
+         Connection: close\r\n\
+         Accept-Ranges: bytes\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Access-Control-Expose-Headers: Content-Length, Content-Range, Accept-Ranges\r\n"
+    );
+    let has_length = headers.iter().any(|(name, _)| *name == "Content-Length");
+    for (name, value) in headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    if !has_length {
+        head.push_str("Content-Length: 0\r\n");
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes())?;
+    if let Some(body) = body {
+        io::copy(body, &mut stream)?;
+    }
+    stream.flush()
+}
+
+/// Maps a request target to a file below `root`, refusing to leave it.
+fn resolve(root: &Path, target: &str) -> Option<PathBuf> {
+    let path = target.split(['?', '#']).next().unwrap_or_default();
+    let decoded = percent_decode(path)?;
+    let mut resolved = root.to_path_buf();
+    for component in Path::new(decoded.trim_start_matches('/')).components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    resolved.is_file().then_some(resolved)
+}
+
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+/// Formats a time as an HTTP date, such as `Sun, 06 Nov 1994 08:49:37 GMT`.
+fn http_date(time: SystemTime) -> String {
+    const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let (days, secs_of_day) = (secs / 86400, secs % 86400);
+    // Civil date from days since the epoch, after Howard Hinnant's `civil_from_days`.
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!(
+        "{}, {day:02} {} {year} {:02}:{:02}:{:02} GMT",
+        DAYS[(days % 7) as usize],
+        MONTHS[(month - 1) as usize],
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+    )
+}
+
+#[derive(Debug, PartialEq)]
+enum Range {
+    /// Serve the whole file.
+    Ignored,
+    Unsatisfiable,
+    /// A half-open byte range within the file.
+    Bytes(u64, u64),
+}
+
+fn parse_range(header: &str, len: u64) -> Range {
+    let Some(spec) = header.strip_prefix("bytes=") else {
+        return Range::Ignored;
+    };
+    if spec.contains(',') {
+        return Range::Ignored;
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return Range::Ignored;
+    };
+    let (start, end) = match (start.parse::<u64>(), end.parse::<u64>()) {
+        (Ok(start), Ok(end)) if start <= end => (start, end.saturating_add(1).min(len)),
+        (Ok(start), Err(_)) if end.is_empty() => (start, len),
+        (Err(_), Ok(suffix)) if start.is_empty() => {
+            if suffix == 0 {
+                return Range::Unsatisfiable;
+            }
+            (len.saturating_sub(suffix), len)
+        }
+        _ => return Range::Ignored,
+    };
+    if start >= len {
+        Range::Unsatisfiable
+    } else {
+        Range::Bytes(start, end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DevServer, Range, http_date, parse_range};
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    fn fixture(name: &str) -> DevServer {
+        let dir = std::env::temp_dir().join(format!(
+            "async_file_devserver_{}_{name}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(dir.join("sub dir")).unwrap();
+        std::fs::write(dir.join("hello.txt"), b"hello world").unwrap();
+        std::fs::write(dir.join("sub dir/nested.txt"), b"nested").unwrap();
+        DevServer::serve(dir).unwrap()
+    }
+
+    /// Sends a raw request, returning the status line, the headers and the body.
+    fn request(server: &DevServer, request: &str) -> (String, String, Vec<u8>) {
+        let mut stream = TcpStream::connect(server.addr()).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).unwrap();
+        let split = response.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+        let head = String::from_utf8(response[..split].to_vec()).unwrap();
+        let (status, headers) = head.split_once("\r\n").unwrap();
+        (
+            status.to_string(),
+            headers.to_string(),
+            response[split + 4..].to_vec(),
+        )
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-4", 11), Range::Bytes(0, 5));
+        assert_eq!(parse_range("bytes=6-", 11), Range::Bytes(6, 11));
+        assert_eq!(parse_range("bytes=-5", 11), Range::Bytes(6, 11));
+        assert_eq!(parse_range("bytes=6-100", 11), Range::Bytes(6, 11));
+        assert_eq!(parse_range("bytes=11-", 11), Range::Unsatisfiable);
+        assert_eq!(parse_range("bytes=0-1,4-5", 11), Range::Ignored);
+        assert_eq!(parse_range("items=0-1", 11), Range::Ignored);
+    }
+
+    #[test]
+    fn test_http_date() {
+        use std::time::{Duration, UNIX_EPOCH};
+        let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+        assert_eq!(http_date(at(0)), "Thu, 01 Jan 1970 00:00:00 GMT");
+        assert_eq!(http_date(at(784_111_777)), "Sun, 06 Nov 1994 08:49:37 GMT");
+        assert_eq!(http_date(at(951_782_400)), "Tue, 29 Feb 2000 00:00:00 GMT");
+    }
+
+    #[test]
+    fn test_get_head_and_range() {
+        let server = fixture("test_get_head_and_range");
+        let (status, _, body) = request(&server, "GET /hello.txt HTTP/1.1\r\n\r\n");
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(body, b"hello world");
+
+        let (status, headers, body) = request(
+            &server,
+            "GET /hello.txt HTTP/1.1\r\nRange: bytes=6-\r\n\r\n",
+        );
+        assert_eq!(status, "HTTP/1.1 206 Partial Content");
+        assert!(headers.contains("Content-Range: bytes 6-10/11"));
+        assert_eq!(body, b"world");
+
+        let (status, headers, body) = request(&server, "HEAD /hello.txt HTTP/1.1\r\n\r\n");
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert!(headers.contains("Content-Length: 11"));
+        assert!(headers.contains("Last-Modified: "));
+        assert!(body.is_empty());
+
+        let (status, headers, _) = request(
+            &server,
+            "GET /hello.txt HTTP/1.1\r\nRange: bytes=20-\r\n\r\n",
+        );
+        assert_eq!(status, "HTTP/1.1 416 Range Not Satisfiable");
+        assert!(headers.contains("Content-Range: bytes */11"));
+
+        let (_, _, body) = request(&server, "GET /sub%20dir/nested.txt?v=1 HTTP/1.1\r\n\r\n");
+        assert_eq!(body, b"nested");
+    }
+
+    #[test]
+    fn test_not_found() {
+        let server = fixture("test_not_found");
+        for target in [
+            "/missing.txt",
+            "/sub%20dir",
+            "/../hello.txt",
+            "/sub%20dir/../../x",
+        ] {
+            let (status, _, _) = request(&server, &format!("GET {target} HTTP/1.1\r\n\r\n"));
+            assert_eq!(status, "HTTP/1.1 404 Not Found", "{target}");
+        }
+    }
+}
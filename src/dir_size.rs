@@ -0,0 +1,143 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Totalling the size of a directory tree, like `du`.
+
+use crate::walk::Walker;
+use crate::{EntryKind, Error, Priority, SymlinkPolicy, WalkOptions, sys};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Which size [`dir_size_with`] adds up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum SizeKind {
+    /// The length of each file, as reported by [`Metadata::len`](crate::Metadata::len).
+    #[default]
+    Apparent,
+    /// The disk space allocated to each file, which is smaller for sparse or
+    /// compressed files and rounded up to whole blocks otherwise. This is what
+    /// `du` reports by default.
+    Allocated,
+}
+
+/// Options controlling [`dir_size_with`].
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{dir_size_with, DirSizeOptions, Priority, SizeKind};
+///
+/// let options = DirSizeOptions::new().size(SizeKind::Allocated);
+/// let used = dir_size_with("cache", &options, Priority::unit_test()).await?;
+/// println!("cache uses {used} bytes on disk");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DirSizeOptions {
+    symlinks: SymlinkPolicy,
+    size: SizeKind,
+}
+
+impl DirSizeOptions {
+    /// Creates options with default values: links are counted as links
+    /// without following them, and apparent sizes are added up.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how symlinks and junctions are treated.
+    ///
+    /// With [`SymlinkPolicy::NoFollow`] a link counts its own size (typically
+    /// the length of its target path). With [`SymlinkPolicy::Follow`] it counts
+    /// what it points to, and linked directories are walked.
+    pub fn symlinks(mut self, policy: SymlinkPolicy) -> Self {
+        self.symlinks = policy;
+        self
+    }
+
+    /// Sets which size to add up.
+    pub fn size(mut self, size: SizeKind) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Returns the configured symlink policy.
+    pub fn get_symlinks(&self) -> SymlinkPolicy {
+        self.symlinks
+    }
+
+    /// Returns the configured size kind.
+    pub fn get_size(&self) -> SizeKind {
+        self.size
+    }
+}
+
+/// Returns the total size, in bytes, of the files below `root`.
+///
+/// This is shorthand for [`dir_size_with`] with default options: apparent
+/// sizes, without following links.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{dir_size, Priority};
+///
+/// if dir_size("cache", Priority::unit_test()).await? > 512 * 1024 * 1024 {
+///     println!("time to evict");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn dir_size(root: impl AsRef<Path>, priority: Priority) -> Result<u64, Error> {
+    dir_size_with(root, &DirSizeOptions::new(), priority).await
+}
+
+/// Returns the total size, in bytes, of the files below `root`, with the given
+/// options.
+///
+/// Directories themselves are not counted. On Unix, a file reached by several
+/// paths (hard links, or links followed with [`SymlinkPolicy::Follow`]) is
+/// counted once. Entries removed while the walk is running are skipped, so a
+/// cache being evicted concurrently can still be measured.
+///
+/// # Errors
+///
+/// Returns the first error that stops part of the tree from being measured:
+/// `root` not being a readable directory, a subdirectory that cannot be listed,
+/// or a link cycle while following links. On WASM, where there are no directory
+/// listings, this always fails.
+pub async fn dir_size_with(
+    root: impl AsRef<Path>,
+    options: &DirSizeOptions,
+    priority: Priority,
+) -> Result<u64, Error> {
+    let follow = options.symlinks == SymlinkPolicy::Follow;
+    let walk = WalkOptions::new().symlinks(options.symlinks);
+    let mut walker = Walker::new(root.as_ref(), &walk, priority);
+    let mut seen = HashSet::new();
+    let mut total = 0u64;
+    while let Some(next) = walker.next().await {
+        let path = match next {
+            Ok((_, EntryKind::Dir)) => continue,
+            Ok((path, _)) => path,
+            Err((path, e)) if e.0.is_not_found() && path != root.as_ref() => continue,
+            Err((_, e)) => return Err(e),
+        };
+        let usage = match sys::usage(&path, follow, priority).await {
+            Ok(usage) => usage,
+            Err(e) if e.is_not_found() => continue,
+            Err(e) => return Err(Error(e)),
+        };
+        if usage.id.is_some_and(|id| !seen.insert(id)) {
+            continue;
+        }
+        total = total.saturating_add(match options.size {
+            SizeKind::Apparent => usage.len,
+            SizeKind::Allocated => usage.allocated,
+        });
+    }
+    Ok(total)
+}
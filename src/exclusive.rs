@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Exclusive access to a [`File`], checked by the borrow checker.
+//!
+//! [`File`]'s methods take `&self`, so two operations on one file can be
+//! started together; the second waits in the file's queue. [`Exclusive`]
+//! borrows the file mutably and takes `&mut self` for every operation, so
+//! starting a second one is a compile error instead.
+
+use crate::{Advice, Data, Error, File, Metadata, Priority, ReadOptions, Text, TextOptions};
+use std::io::SeekFrom;
+
+/// A [`File`] borrowed for one operation at a time, returned by
+/// [`File::exclusive`].
+///
+/// Each method takes `&mut self` and does what the [`File`] method of the same
+/// name does. While the returned future is alive the `Exclusive` stays
+/// borrowed, so no other operation can be started on it, and while the
+/// `Exclusive` is alive the file can't be used except through it. Operations
+/// through an `Exclusive` therefore never wait behind another.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{File, Priority};
+///
+/// let mut file = File::open("/dev/zero", Priority::unit_test()).await?;
+/// let mut exclusive = file.exclusive();
+/// let header = exclusive.read(16, Priority::unit_test()).await?;
+/// let metadata = exclusive.metadata(Priority::unit_test()).await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Starting a second operation while one is in flight doesn't compile:
+///
+/// ```compile_fail
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{File, Priority};
+///
+/// let mut file = File::open("/dev/zero", Priority::unit_test()).await?;
+/// let mut exclusive = file.exclusive();
+/// let read = exclusive.read(16, Priority::unit_test());
+/// let metadata = exclusive.metadata(Priority::unit_test());
+/// # let _ = (read, metadata);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Exclusive<'a> {
+    file: &'a mut File,
+}
+
+impl File {
+    /// Borrows the file so that only one operation at a time can be started
+    /// on it, as checked at compile time. See [`Exclusive`].
+    pub fn exclusive(&mut self) -> Exclusive<'_> {
+        Exclusive { file: self }
+    }
+}
+
+impl Exclusive<'_> {
+    /// Reads up to `buf_size` bytes, as [`File::read`] does.
+    pub async fn read(&mut self, buf_size: usize, priority: Priority) -> Result<Data, Error> {
+        self.file.read(buf_size, priority).await
+    }
+
+    /// Reads up to `buf_size` bytes into `buf`'s allocation where possible, as
+    /// [`File::read_into`] does.
+    pub async fn read_into(
+        &mut self,
+        buf: Data,
+        buf_size: usize,
+        priority: Priority,
+    ) -> Result<Data, Error> {
+        self.file.read_into(buf, buf_size, priority).await
+    }
+
+    /// Reads the rest of the file, as [`File::read_all`] does.
+    pub async fn read_all(&mut self, priority: Priority) -> Result<Data, Error> {
+        self.file.read_all(priority).await
+    }
+
+    /// Reads the rest of the file with the given options, as
+    /// [`File::read_all_with`] does.
+    pub async fn read_all_with(
+        &mut self,
+        options: &ReadOptions,
+        priority: Priority,
+    ) -> Result<Data, Error> {
+        self.file.read_all_with(options, priority).await
+    }
+
+    /// Seeks to a position in the file, as [`File::seek`] does.
+    pub async fn seek(&mut self, pos: SeekFrom, priority: Priority) -> Result<u64, Error> {
+        self.file.seek(pos, priority).await
+    }
+
+    /// Gives advice about how a range of the file will be read, as
+    /// [`File::advise`] does.
+    pub async fn advise(
+        &mut self,
+        advice: Advice,
+        range: impl std::ops::RangeBounds<u64>,
+        priority: Priority,
+    ) -> Result<(), Error> {
+        self.file.advise(advice, range, priority).await
+    }
+
+    /// Returns metadata about the file, as [`File::metadata`] does.
+    pub async fn metadata(&mut self, priority: Priority) -> Result<Metadata, Error> {
+        self.file.metadata(priority).await
+    }
+
+    /// Reads the rest of the file as text, as [`File::text`] does.
+    pub async fn text(&mut self, priority: Priority) -> Result<Text, Error> {
+        self.file.text(priority).await
+    }
+
+    /// Reads the rest of the file as text with the given options, as
+    /// [`File::text_with`] does.
+    pub async fn text_with(
+        &mut self,
+        options: &TextOptions,
+        priority: Priority,
+    ) -> Result<Text, Error> {
+        self.file.text_with(options, priority).await
+    }
+}
@@ -0,0 +1,564 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Options for the HTTP requests that read files on WASM.
+
+use std::io::ErrorKind;
+use std::time::Duration;
+
+/// Options for the `fetch` requests the WASM backend makes, set for all files
+/// with [`set_fetch_options`](crate::set_fetch_options) or for one file with
+/// [`OpenOptions::fetch`](crate::OpenOptions::fetch).
+///
+/// On native platforms, where files are read from disk, these options are
+/// accepted and ignored.
+///
+/// # Headers
+///
+/// Headers are sent with every request for a file: the HEAD request made at
+/// open, each ranged GET, and metadata requests. Use them for
+/// `Authorization` bearer tokens, API keys, or cache directives. Headers given
+/// for one file are added to the global ones, replacing a global header of
+/// the same name. The crate's own `Range` header always wins.
+///
+/// # Origin
+///
+/// Relative paths are fetched from the page's origin, or from the fallback
+/// given to [`set_default_origin`](crate::set_default_origin) where there is
+/// no page. [`origin`](FetchOptions::origin) sets the base URL to fetch them
+/// from instead, so files opened with different options can come from
+/// different servers. Paths that are already absolute `http://` or
+/// `https://` URLs are fetched as they are, whatever the origin.
+///
+/// A relative path names a file, not a URL: characters such as spaces, `#`,
+/// `?` and `%`, and non-ASCII letters, are percent-encoded, so
+/// `"levels/boss #2.bin"` fetches `levels/boss%20%232.bin`. Absolute URLs
+/// must already be encoded.
+///
+/// # Mode, Credentials, and Cache
+///
+/// [`mode`](FetchOptions::mode), [`credentials`](FetchOptions::credentials)
+/// and [`cache`](FetchOptions::cache) set the `RequestInit` fields of the same
+/// names. Left unset, the browser's defaults apply: CORS mode, credentials
+/// sent only to the same origin, and the default cache behavior. To send
+/// cookies to an asset server on another origin, use
+/// [`FetchCredentials::Include`]; the server must then answer with
+/// `Access-Control-Allow-Credentials: true` and a specific
+/// `Access-Control-Allow-Origin`.
+///
+/// # Retries
+///
+/// With a [`RetryPolicy`], requests that fail with a network error or a
+/// transient status are made again after a delay. Without one, the default,
+/// the first failure is reported.
+///
+/// # Cache Storage
+///
+/// With [`cache_storage`](FetchOptions::cache_storage), requests go through a
+/// cache in the browser's Cache Storage (`caches.open(name)`), the same caches
+/// a service worker uses, so files read once can be read again offline. See
+/// [`CachePolicy`] for when the cache is consulted and populated. Cache
+/// Storage exists only in secure contexts; elsewhere the option is ignored.
+///
+/// # Timeouts
+///
+/// With [`timeout`](FetchOptions::timeout), an operation whose requests have
+/// not finished in time is aborted and fails with an error for which
+/// [`Error::is_timed_out`](crate::Error::is_timed_out) is true. The limit
+/// covers each open, read, metadata or existence check as a whole, including
+/// any retries. Without one, the default, a request that never completes
+/// leaves its future pending until it is dropped.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{FetchCredentials, FetchOptions, File, OpenOptions, Priority, set_fetch_options};
+///
+/// // Every request carries the API key
+/// set_fetch_options(FetchOptions::new().header("X-Api-Key", "k-123"));
+///
+/// // This file also needs the user's token
+/// let options = OpenOptions::new()
+///     .fetch(FetchOptions::new().header("Authorization", "Bearer eyJhbGciOi..."));
+/// let file = File::open_with("private/report.bin", &options, Priority::unit_test()).await?;
+///
+/// // Files can come from another server, by origin or by absolute URL
+/// let options = OpenOptions::new().fetch(FetchOptions::new().origin("https://cdn-b.example.com"));
+/// let textures = File::open_with("textures.bin", &options, Priority::unit_test()).await?;
+/// let fonts = File::open("https://fonts.example.com/ui.woff2", Priority::unit_test()).await?;
+///
+/// // Send the session cookie to the asset server
+/// let options = OpenOptions::new().fetch(
+///     FetchOptions::new()
+///         .origin("https://assets.example.com")
+///         .credentials(FetchCredentials::Include),
+/// );
+/// let level = File::open_with("levels/1.bin", &options, Priority::unit_test()).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FetchOptions {
+    headers: Vec<(String, String)>,
+    origin: Option<String>,
+    mode: Option<FetchMode>,
+    credentials: Option<FetchCredentials>,
+    cache: Option<FetchCache>,
+    retry: Option<RetryPolicy>,
+    cache_storage: Option<(String, CachePolicy)>,
+    timeout: Option<Duration>,
+}
+
+impl FetchOptions {
+    /// Creates options with no extra headers, fetching relative paths from
+    /// the default origin.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a header to send with each request, replacing any earlier header
+    /// of the same name (compared case-insensitively).
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into();
+        self.headers
+            .retain(|(existing, _)| !existing.eq_ignore_ascii_case(&name));
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    /// Sets the base URL relative paths are fetched from, such as
+    /// `https://cdn.example.com/assets`.
+    pub fn origin(mut self, origin: impl Into<String>) -> Self {
+        self.origin = Some(origin.into());
+        self
+    }
+
+    /// Sets the request mode.
+    pub fn mode(mut self, mode: FetchMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Sets whether cookies and HTTP authentication are sent.
+    pub fn credentials(mut self, credentials: FetchCredentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Sets how requests use the browser's HTTP cache.
+    pub fn cache(mut self, cache: FetchCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Sets how failed requests are retried.
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Sets a Cache Storage cache to read through, by name, and when to use it.
+    pub fn cache_storage(mut self, name: impl Into<String>, policy: CachePolicy) -> Self {
+        self.cache_storage = Some((name.into(), policy));
+        self
+    }
+
+    /// Sets how long each operation may wait for its requests before it is
+    /// aborted.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the headers to send, in the order they were added.
+    pub fn get_headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Returns the configured origin, if any.
+    pub fn get_origin(&self) -> Option<&str> {
+        self.origin.as_deref()
+    }
+
+    /// Returns the configured mode, if any.
+    pub fn get_mode(&self) -> Option<FetchMode> {
+        self.mode
+    }
+
+    /// Returns the configured credentials setting, if any.
+    pub fn get_credentials(&self) -> Option<FetchCredentials> {
+        self.credentials
+    }
+
+    /// Returns the configured cache mode, if any.
+    pub fn get_cache(&self) -> Option<FetchCache> {
+        self.cache
+    }
+
+    /// Returns the configured retry policy, if any.
+    pub fn get_retry(&self) -> Option<&RetryPolicy> {
+        self.retry.as_ref()
+    }
+
+    /// Returns the configured Cache Storage cache name and policy, if any.
+    pub fn get_cache_storage(&self) -> Option<(&str, CachePolicy)> {
+        self.cache_storage
+            .as_ref()
+            .map(|(name, policy)| (name.as_str(), *policy))
+    }
+
+    /// Returns the configured timeout, if any.
+    pub fn get_timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Returns these options with `overrides` applied on top.
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    pub(crate) fn merged_with(&self, overrides: &FetchOptions) -> FetchOptions {
+        let mut merged = self.clone();
+        for (name, value) in &overrides.headers {
+            merged = merged.header(name.clone(), value.clone());
+        }
+        if let Some(origin) = &overrides.origin {
+            merged.origin = Some(origin.clone());
+        }
+        merged.mode = overrides.mode.or(merged.mode);
+        merged.credentials = overrides.credentials.or(merged.credentials);
+        merged.cache = overrides.cache.or(merged.cache);
+        if let Some(retry) = &overrides.retry {
+            merged.retry = Some(retry.clone());
+        }
+        if let Some(cache_storage) = &overrides.cache_storage {
+            merged.cache_storage = Some(cache_storage.clone());
+        }
+        merged.timeout = overrides.timeout.or(merged.timeout);
+        merged
+    }
+}
+
+/// How failed operations are retried: WASM requests, set with
+/// [`FetchOptions::retry`], and native I/O, set with
+/// [`OpenOptions::retry`](crate::OpenOptions::retry) or
+/// [`ReadOptions::retry`](crate::ReadOptions::retry).
+///
+/// A request is retried if it fails with a network error (when
+/// [`network_errors`](RetryPolicy::network_errors) is on), or if the server
+/// answers with one of the [`statuses`](RetryPolicy::statuses). A native
+/// operation is retried if it fails with an I/O error of one of the
+/// [`error_kinds`](RetryPolicy::error_kinds), such as the `EAGAIN` and `EBUSY`
+/// that network filesystems report while a server is briefly unavailable. The
+/// delay before each retry doubles from [`backoff`](RetryPolicy::backoff), up
+/// to [`max_backoff`](RetryPolicy::max_backoff). Operations abandoned because
+/// their future was dropped are never retried.
+///
+/// # Examples
+///
+/// ```
+/// use async_file::{FetchOptions, RetryPolicy, set_fetch_options};
+/// use std::time::Duration;
+///
+/// // Up to 5 tries, waiting 0.5s, 1s, 2s and 4s in between
+/// let retry = RetryPolicy::new()
+///     .attempts(5)
+///     .backoff(Duration::from_millis(500));
+/// set_fetch_options(FetchOptions::new().retry(retry));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    attempts: u32,
+    backoff: Duration,
+    max_backoff: Duration,
+    statuses: Vec<u16>,
+    network_errors: bool,
+    error_kinds: Vec<ErrorKind>,
+}
+
+impl RetryPolicy {
+    /// Creates a policy of 3 attempts, waiting 250 ms then 500 ms, on
+    /// network errors, statuses 408, 429, 500, 502, 503 and 504, and I/O
+    /// errors of kind [`Interrupted`](ErrorKind::Interrupted),
+    /// [`WouldBlock`](ErrorKind::WouldBlock),
+    /// [`ResourceBusy`](ErrorKind::ResourceBusy) and
+    /// [`TimedOut`](ErrorKind::TimedOut). Delays are capped at 10 s.
+    pub fn new() -> Self {
+        RetryPolicy {
+            attempts: 3,
+            backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(10),
+            statuses: vec![408, 429, 500, 502, 503, 504],
+            network_errors: true,
+            error_kinds: vec![
+                ErrorKind::Interrupted,
+                ErrorKind::WouldBlock,
+                ErrorKind::ResourceBusy,
+                ErrorKind::TimedOut,
+            ],
+        }
+    }
+
+    /// Sets the total number of tries, including the first. 1 or less means
+    /// no retries.
+    pub fn attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    /// Sets the delay before the first retry.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Sets the longest delay between tries.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Sets the response statuses that are retried.
+    pub fn statuses(mut self, statuses: impl Into<Vec<u16>>) -> Self {
+        self.statuses = statuses.into();
+        self
+    }
+
+    /// Sets whether network errors, where no response arrived, are retried.
+    pub fn network_errors(mut self, network_errors: bool) -> Self {
+        self.network_errors = network_errors;
+        self
+    }
+
+    /// Sets the kinds of native I/O error that are retried.
+    pub fn error_kinds(mut self, error_kinds: impl Into<Vec<ErrorKind>>) -> Self {
+        self.error_kinds = error_kinds.into();
+        self
+    }
+
+    /// Returns the total number of tries.
+    pub fn get_attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Returns the delay before the first retry.
+    pub fn get_backoff(&self) -> Duration {
+        self.backoff
+    }
+
+    /// Returns the longest delay between tries.
+    pub fn get_max_backoff(&self) -> Duration {
+        self.max_backoff
+    }
+
+    /// Returns the response statuses that are retried.
+    pub fn get_statuses(&self) -> &[u16] {
+        &self.statuses
+    }
+
+    /// Returns whether network errors are retried.
+    pub fn get_network_errors(&self) -> bool {
+        self.network_errors
+    }
+
+    /// Returns the kinds of native I/O error that are retried.
+    pub fn get_error_kinds(&self) -> &[ErrorKind] {
+        &self.error_kinds
+    }
+
+    /// Returns the delay before try number `attempt`, counting the first as
+    /// 0, or `None` if there are no tries left.
+    pub(crate) fn delay_before(&self, attempt: u32) -> Option<Duration> {
+        if attempt == 0 || attempt >= self.attempts {
+            return None;
+        }
+        let factor = 2u32.saturating_pow(attempt - 1);
+        Some(self.backoff.saturating_mul(factor).min(self.max_backoff))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// When requests use a Cache Storage cache, set with
+/// [`FetchOptions::cache_storage`].
+///
+/// The Cache API stores whole files only, so filling the cache means
+/// downloading the whole file, even for a read of a few bytes; reads are then
+/// answered from the stored copy.
+///
+/// # Examples
+///
+/// ```
+/// use async_file::{CachePolicy, FetchOptions, set_fetch_options};
+///
+/// // Game assets never change within a version: download each once
+/// set_fetch_options(FetchOptions::new().cache_storage("assets-v3", CachePolicy::CacheFirst));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CachePolicy {
+    /// Answer from the cache whenever it holds the file. Otherwise, fetch the
+    /// whole file, store it, and answer from it. HEAD requests for files not
+    /// yet cached go to the network without storing anything. Suits files
+    /// that never change at a given URL.
+    CacheFirst,
+    /// Send requests to the network, storing any whole-file response, and
+    /// answer from the cache only if the network fails. Ranged reads, which
+    /// servers answer in part, don't fill the cache; pair this with a service
+    /// worker or [`CacheFirst`](CachePolicy::CacheFirst) reads that do.
+    NetworkFirst,
+}
+
+/// The mode of a request, set with [`FetchOptions::mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FetchMode {
+    /// Cross-origin requests are allowed if the server permits them with CORS
+    /// headers; the browser's default.
+    Cors,
+    /// Cross-origin requests are made without CORS, but their responses are
+    /// opaque: status and body can't be read, so reads fail.
+    NoCors,
+    /// Cross-origin requests fail.
+    SameOrigin,
+}
+
+/// Whether a request carries cookies and HTTP authentication, set with
+/// [`FetchOptions::credentials`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FetchCredentials {
+    /// Never send them.
+    Omit,
+    /// Send them only to the same origin; the browser's default.
+    SameOrigin,
+    /// Send them to any origin, including cross-origin asset servers.
+    Include,
+}
+
+/// How a request uses the browser's HTTP cache, set with
+/// [`FetchOptions::cache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FetchCache {
+    /// Use the cache as HTTP caching rules say; the browser's default.
+    Default,
+    /// Bypass the cache entirely, neither reading nor storing.
+    NoStore,
+    /// Fetch from the server, then store the response.
+    Reload,
+    /// Revalidate cached responses with the server before using them.
+    NoCache,
+    /// Use any cached response, however stale, and fetch only on a miss.
+    ForceCache,
+    /// Use only cached responses, failing on a miss. Requires
+    /// [`FetchMode::SameOrigin`].
+    OnlyIfCached,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CachePolicy, FetchCache, FetchCredentials, FetchMode, FetchOptions, RetryPolicy};
+    use std::time::Duration;
+
+    #[test]
+    fn test_header_replaces_and_merges() {
+        let global = FetchOptions::new()
+            .header("X-Api-Key", "old")
+            .header("x-api-key", "k-123")
+            .header("Accept", "application/json");
+        assert_eq!(
+            global.get_headers(),
+            [
+                ("x-api-key".to_string(), "k-123".to_string()),
+                ("Accept".to_string(), "application/json".to_string()),
+            ]
+        );
+        let merged = global.merged_with(&FetchOptions::new().header("ACCEPT", "text/csv"));
+        assert_eq!(
+            merged.get_headers(),
+            [
+                ("x-api-key".to_string(), "k-123".to_string()),
+                ("ACCEPT".to_string(), "text/csv".to_string()),
+            ]
+        );
+        assert_eq!(merged.get_origin(), None);
+    }
+
+    #[test]
+    fn test_origin_overrides() {
+        let global = FetchOptions::new().origin("https://cdn-a.example.com");
+        let kept = global.merged_with(&FetchOptions::new().header("Accept", "*/*"));
+        assert_eq!(kept.get_origin(), Some("https://cdn-a.example.com"));
+        let replaced = global.merged_with(&FetchOptions::new().origin("https://cdn-b.example.com"));
+        assert_eq!(replaced.get_origin(), Some("https://cdn-b.example.com"));
+    }
+
+    #[test]
+    fn test_modes_override() {
+        let global = FetchOptions::new()
+            .credentials(FetchCredentials::Include)
+            .cache(FetchCache::NoStore);
+        let merged = global.merged_with(
+            &FetchOptions::new()
+                .mode(FetchMode::SameOrigin)
+                .cache(FetchCache::ForceCache),
+        );
+        assert_eq!(merged.get_mode(), Some(FetchMode::SameOrigin));
+        assert_eq!(merged.get_credentials(), Some(FetchCredentials::Include));
+        assert_eq!(merged.get_cache(), Some(FetchCache::ForceCache));
+        assert_eq!(FetchOptions::new().get_mode(), None);
+    }
+
+    #[test]
+    fn test_cache_storage_overrides() {
+        let global = FetchOptions::new().cache_storage("assets", CachePolicy::CacheFirst);
+        assert_eq!(
+            global.merged_with(&FetchOptions::new()).get_cache_storage(),
+            Some(("assets", CachePolicy::CacheFirst))
+        );
+        let merged = global
+            .merged_with(&FetchOptions::new().cache_storage("docs", CachePolicy::NetworkFirst));
+        assert_eq!(
+            merged.get_cache_storage(),
+            Some(("docs", CachePolicy::NetworkFirst))
+        );
+    }
+
+    #[test]
+    fn test_timeout_overrides() {
+        let global = FetchOptions::new().timeout(Duration::from_secs(30));
+        assert_eq!(
+            global.merged_with(&FetchOptions::new()).get_timeout(),
+            Some(Duration::from_secs(30))
+        );
+        let merged = global.merged_with(&FetchOptions::new().timeout(Duration::from_secs(5)));
+        assert_eq!(merged.get_timeout(), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_retry_delays() {
+        let retry = RetryPolicy::new()
+            .attempts(5)
+            .backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_millis(300));
+        let delays: Vec<_> = (0..6).map(|attempt| retry.delay_before(attempt)).collect();
+        assert_eq!(
+            delays,
+            [
+                None,
+                Some(Duration::from_millis(100)),
+                Some(Duration::from_millis(200)),
+                Some(Duration::from_millis(300)),
+                Some(Duration::from_millis(300)),
+                None,
+            ]
+        );
+        assert_eq!(RetryPolicy::new().attempts(1).delay_before(1), None);
+        assert_eq!(
+            RetryPolicy::new().attempts(100).delay_before(99),
+            Some(Duration::from_secs(10))
+        );
+    }
+}
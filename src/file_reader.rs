@@ -0,0 +1,367 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! The `futures-io` and `tokio` I/O traits over [`File`].
+//!
+//! Async decompressors, parsers, codecs and copy helpers throughout the
+//! ecosystem read through `AsyncRead`, `AsyncBufRead` and `AsyncSeek`, which
+//! lend the reader a buffer to fill. [`File`] instead returns the
+//! [`Data`](crate::Data) it read, so [`FileReader`] reads into a [`BufFile`]
+//! and copies out of it.
+
+use crate::{BufFile, Error, File, Priority};
+use std::future::Future;
+use std::io::SeekFrom;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+type FillFuture = Pin<Box<dyn Future<Output = (BufFile, Result<(), Error>)> + Send>>;
+type SeekFuture = Pin<Box<dyn Future<Output = (BufFile, Result<u64, Error>)> + Send>>;
+
+/// A [`File`] implementing `AsyncRead`, `AsyncBufRead` and `AsyncSeek`:
+/// those of `futures-io` with the `futures-io` feature, and those of
+/// `tokio::io` with the `tokio` feature.
+///
+/// Reads go through a [`BufFile`], so callers that read a few bytes at a time
+/// don't each reach the backend. Every backend read and seek uses the priority
+/// given at construction. Errors are converted to [`std::io::Error`] by its
+/// `From<Error>` implementation, so the original [`Error`] can be recovered
+/// with [`get_ref`](std::io::Error::get_ref).
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "futures-io")]
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{File, FileReader, Priority};
+/// use futures_io::AsyncRead;
+///
+/// let file = File::open("archive.tar.gz", Priority::unit_test()).await?;
+/// let reader = FileReader::new(file, Priority::unit_test());
+/// // Hand `reader` to any consumer of futures::io::AsyncRead
+/// fn decompress(_input: impl AsyncRead + Unpin) {}
+/// decompress(reader);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Under tokio, the same reader works with `tokio::io` consumers:
+///
+/// ```
+/// # #[cfg(feature = "tokio")]
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{File, FileReader, Priority};
+/// use tokio::io::AsyncBufRead;
+///
+/// let file = File::open("records.csv", Priority::unit_test()).await?;
+/// let reader = FileReader::new(file, Priority::unit_test());
+/// fn parse(_input: impl AsyncBufRead + Unpin) {}
+/// parse(reader);
+/// # Ok(())
+/// # }
+/// ```
+pub struct FileReader {
+    /// `None` while an operation holds the reader.
+    reader: Option<BufFile>,
+    pending: Option<Pending>,
+    priority: Priority,
+    /// Where `tokio::io::AsyncSeek::start_seek` asked to seek to.
+    #[cfg(feature = "tokio")]
+    requested_seek: Option<SeekFrom>,
+}
+
+enum Pending {
+    Fill(FillFuture),
+    Seek(SeekFuture),
+}
+
+impl FileReader {
+    /// Wraps `file`, buffering with the default [`BufFile`] capacity.
+    pub fn new(file: File, priority: Priority) -> Self {
+        Self::from_buf_file(BufFile::new(file), priority)
+    }
+
+    /// Wraps `file`, buffering `capacity` bytes at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn with_capacity(capacity: usize, file: File, priority: Priority) -> Self {
+        Self::from_buf_file(BufFile::with_capacity(capacity, file), priority)
+    }
+
+    /// Wraps a [`BufFile`], keeping any bytes it has already buffered.
+    pub fn from_buf_file(reader: BufFile, priority: Priority) -> Self {
+        FileReader {
+            reader: Some(reader),
+            pending: None,
+            priority,
+            #[cfg(feature = "tokio")]
+            requested_seek: None,
+        }
+    }
+
+    /// Finishes the operation in progress, if any, returning its result.
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<u64, Error>>> {
+        let (reader, result) = match &mut self.pending {
+            None => return Poll::Ready(None),
+            Some(Pending::Fill(fut)) => {
+                let (reader, result) = ready!(fut.as_mut().poll(cx));
+                (reader, result.map(|()| 0))
+            }
+            Some(Pending::Seek(fut)) => ready!(fut.as_mut().poll(cx)),
+        };
+        self.reader = Some(reader);
+        self.pending = None;
+        Poll::Ready(Some(result))
+    }
+
+    fn take_reader(&mut self) -> BufFile {
+        self.reader
+            .take()
+            .expect("FileReader used after an operation panicked")
+    }
+
+    fn poll_fill(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        loop {
+            let filling = matches!(self.pending, Some(Pending::Fill(_)));
+            match ready!(self.poll_pending(cx)) {
+                Some(Err(e)) => return Poll::Ready(Err(e.into())),
+                Some(Ok(_)) if filling => break,
+                // A seek finished first; the buffer is now empty.
+                Some(Ok(_)) => {}
+                None if self.reader.as_ref().is_some_and(|r| !r.buffer().is_empty()) => break,
+                None => {
+                    let mut reader = self.take_reader();
+                    let priority = self.priority;
+                    self.pending = Some(Pending::Fill(Box::pin(async move {
+                        let result = reader.fill_buf(priority).await.map(|_| ());
+                        (reader, result)
+                    })));
+                }
+            }
+        }
+        Poll::Ready(Ok(self.reader.as_ref().map_or(&[], BufFile::buffer)))
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if let Some(reader) = &mut self.reader {
+            reader.consume(amt);
+        }
+    }
+
+    /// Seeks to `pos`, unless a seek is already in progress, which is
+    /// finished instead.
+    fn poll_seek_to(&mut self, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<std::io::Result<u64>> {
+        loop {
+            let seeking = matches!(self.pending, Some(Pending::Seek(_)));
+            match ready!(self.poll_pending(cx)) {
+                Some(result) if seeking => return Poll::Ready(result.map_err(Into::into)),
+                // A fill finished first. Its bytes are discarded by the seek,
+                // and so is any error.
+                Some(_) => {}
+                None => {
+                    let mut reader = self.take_reader();
+                    let priority = self.priority;
+                    self.pending = Some(Pending::Seek(Box::pin(async move {
+                        let result = reader.seek(pos, priority).await;
+                        (reader, result)
+                    })));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl futures_io::AsyncRead for FileReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        let available = ready!(this.poll_fill(cx))?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        this.consume(n);
+        Poll::Ready(Ok(n))
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl futures_io::AsyncBufRead for FileReader {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        self.get_mut().poll_fill(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().consume(amt);
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl futures_io::AsyncSeek for FileReader {
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<std::io::Result<u64>> {
+        self.get_mut().poll_seek_to(cx, pos)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncRead for FileReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        let available = ready!(this.poll_fill(cx))?;
+        let n = available.len().min(buf.remaining());
+        buf.put_slice(&available[..n]);
+        this.consume(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncBufRead for FileReader {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        self.get_mut().poll_fill(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().consume(amt);
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncSeek for FileReader {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        let this = self.get_mut();
+        if this.requested_seek.is_some() {
+            return Err(std::io::Error::other(
+                "a seek is already in progress; call poll_complete first",
+            ));
+        }
+        this.requested_seek = Some(position);
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        let this = self.get_mut();
+        // Without a seek to finish, tokio asks for the current position.
+        let pos = this.requested_seek.unwrap_or(SeekFrom::Current(0));
+        let result = ready!(this.poll_seek_to(cx, pos));
+        this.requested_seek = None;
+        Poll::Ready(result)
+    }
+}
+
+impl std::fmt::Debug for FileReader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileReader")
+            .field("reader", &self.reader)
+            .field("priority", &self.priority)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::FileReader;
+    use crate::{File, Priority};
+    use std::future::poll_fn;
+    use std::io::SeekFrom;
+    use std::pin::Pin;
+
+    #[cfg(feature = "futures-io")]
+    #[test_executors::async_test]
+    async fn test_read_and_seek() {
+        use futures_io::{AsyncBufRead, AsyncRead, AsyncSeek};
+        logwise::context::Context::reset("test_read_and_seek".to_string());
+        let path = crate::tests::temp_path("futures_io");
+        let contents: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        std::fs::write(&path, &contents).unwrap();
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        let mut reader = FileReader::with_capacity(64, file, Priority::unit_test());
+
+        let mut buf = [0; 10];
+        let n = poll_fn(|cx| Pin::new(&mut reader).poll_read(cx, &mut buf))
+            .await
+            .unwrap();
+        assert_eq!(&buf[..n], &contents[..n]);
+
+        let pos = poll_fn(|cx| Pin::new(&mut reader).poll_seek(cx, SeekFrom::Current(5)))
+            .await
+            .unwrap();
+        assert_eq!(pos, n as u64 + 5);
+        let available = poll_fn(|cx| {
+            Pin::new(&mut reader)
+                .poll_fill_buf(cx)
+                .map_ok(<[u8]>::to_vec)
+        })
+        .await
+        .unwrap();
+        assert_eq!(available[0], contents[pos as usize]);
+        Pin::new(&mut reader).consume(available.len());
+
+        let mut rest = Vec::new();
+        let mut buf = [0; 100];
+        loop {
+            let n = poll_fn(|cx| Pin::new(&mut reader).poll_read(cx, &mut buf))
+                .await
+                .unwrap();
+            if n == 0 {
+                break;
+            }
+            rest.extend_from_slice(&buf[..n]);
+        }
+        assert_eq!(rest, &contents[pos as usize + available.len()..]);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test_executors::async_test]
+    async fn test_tokio_read_and_seek() {
+        use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
+        logwise::context::Context::reset("test_tokio_read_and_seek".to_string());
+        let path = crate::tests::temp_path("tokio_io");
+        let contents: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        std::fs::write(&path, &contents).unwrap();
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        let mut reader = FileReader::with_capacity(64, file, Priority::unit_test());
+
+        let mut storage = [0; 10];
+        let mut buf = ReadBuf::new(&mut storage);
+        poll_fn(|cx| Pin::new(&mut reader).poll_read(cx, &mut buf))
+            .await
+            .unwrap();
+        let n = buf.filled().len();
+        assert_eq!(buf.filled(), &contents[..n]);
+
+        Pin::new(&mut reader)
+            .start_seek(SeekFrom::Start(500))
+            .unwrap();
+        assert!(
+            Pin::new(&mut reader)
+                .start_seek(SeekFrom::Start(0))
+                .is_err()
+        );
+        let pos = poll_fn(|cx| Pin::new(&mut reader).poll_complete(cx))
+            .await
+            .unwrap();
+        assert_eq!(pos, 500);
+        let pos = poll_fn(|cx| Pin::new(&mut reader).poll_complete(cx))
+            .await
+            .unwrap();
+        assert_eq!(pos, 500);
+
+        let mut buf = ReadBuf::new(&mut storage);
+        poll_fn(|cx| Pin::new(&mut reader).poll_read(cx, &mut buf))
+            .await
+            .unwrap();
+        assert_eq!(buf.filled(), &contents[500..510]);
+    }
+}
@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Space on the filesystem containing a path.
+
+use crate::{Error, Priority, sys};
+use std::path::Path;
+
+/// Space on the filesystem containing a path, as reported by [`fs_stats`].
+///
+/// All sizes are in bytes. The figures are a snapshot: other processes may
+/// use or free space at any time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FsStats {
+    pub(crate) total: u64,
+    pub(crate) free: u64,
+    pub(crate) available: u64,
+}
+
+impl FsStats {
+    /// Returns the size of the filesystem.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Returns the unused space, including space reserved for privileged users.
+    pub fn free(&self) -> u64 {
+        self.free
+    }
+
+    /// Returns the unused space this process can write to.
+    ///
+    /// This is the figure to check before a large write. It can be less than
+    /// [`free`](FsStats::free) because of blocks reserved for the superuser on
+    /// Unix, or per-user quotas on Windows.
+    pub fn available(&self) -> u64 {
+        self.available
+    }
+}
+
+/// Reports space on the filesystem containing `path`.
+///
+/// `path` must exist; it may be a file or a directory.
+///
+/// # Arguments
+///
+/// * `path` - Any existing path on the filesystem of interest
+/// * `priority` - The priority for this operation
+///
+/// # Errors
+///
+/// Returns an error if `path` does not exist or the filesystem cannot be
+/// queried. On WASM, where files are served over HTTP, this always fails.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{fs_stats, Priority};
+///
+/// let download_len = 4 * 1024 * 1024 * 1024;
+/// let stats = fs_stats("downloads", Priority::unit_test()).await?;
+/// if stats.available() < download_len {
+///     println!("not enough space for this download");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn fs_stats(path: impl AsRef<Path>, priority: Priority) -> Result<FsStats, Error> {
+    sys::fs_stats(path, priority).await.map_err(Error)
+}
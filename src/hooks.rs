@@ -0,0 +1,348 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Callbacks run as file operations start and finish, for telemetry.
+//!
+//! With the `tracing` feature, the same operations are also each run in a
+//! `tracing` span.
+
+use crate::{Error, Priority, sys};
+use std::fmt;
+use std::future::Future;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+type HookFn = dyn Fn(&OperationEvent<'_>) + Send + Sync;
+
+static GLOBAL: RwLock<Option<Hooks>> = RwLock::new(None);
+
+/// A kind of operation reported to [`Hooks`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Operation {
+    /// [`File::open`](crate::File::open) or
+    /// [`File::open_with`](crate::File::open_with).
+    Open,
+    /// [`File::read`](crate::File::read), [`File::read_into`](crate::File::read_into),
+    /// [`File::read_all`](crate::File::read_all) or
+    /// [`File::read_all_with`](crate::File::read_all_with).
+    Read,
+    /// [`File::seek`](crate::File::seek).
+    Seek,
+    /// [`File::metadata`](crate::File::metadata).
+    Metadata,
+}
+
+/// An operation starting or finishing, passed to [`Hooks`] callbacks.
+///
+/// When an operation starts, [`bytes`](OperationEvent::bytes) and
+/// [`duration`](OperationEvent::duration) are zero and there is no error.
+#[derive(Debug, Clone, Copy)]
+pub struct OperationEvent<'a> {
+    path: &'a Path,
+    operation: Operation,
+    priority: Priority,
+    bytes: u64,
+    duration: Duration,
+    error: Option<&'a Error>,
+    cancelled: bool,
+}
+
+impl OperationEvent<'_> {
+    /// Returns the path the file was opened with, which is empty for files
+    /// not opened by path, such as those wrapped with `File::from_std`.
+    pub fn path(&self) -> &Path {
+        self.path
+    }
+
+    /// Returns the kind of operation.
+    pub fn operation(&self) -> Operation {
+        self.operation
+    }
+
+    /// Returns the priority the operation was made at.
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    /// Returns the bytes the operation read, or 0 for operations that read
+    /// none.
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Returns how long the operation took, including time spent queued.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns the error the operation failed with, if it did.
+    pub fn error(&self) -> Option<&Error> {
+        self.error
+    }
+
+    /// Returns whether the operation's future was dropped before it finished.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+}
+
+/// Callbacks run as file operations start and finish, installed with
+/// [`set_hooks`].
+///
+/// Every operation that starts also finishes: when its future is dropped
+/// part way, [`on_complete`](Hooks::on_complete) is still called, with
+/// [`is_cancelled`](OperationEvent::is_cancelled) set. Callbacks run on the
+/// task making the operation, so they should be quick; send the event
+/// elsewhere for anything slow.
+///
+/// # Examples
+///
+/// ```
+/// use async_file::{Hooks, set_hooks};
+///
+/// set_hooks(Some(Hooks::new().on_complete(|event| {
+///     println!(
+///         "{:?} of {} took {:?}",
+///         event.operation(),
+///         event.path().display(),
+///         event.duration()
+///     );
+/// })));
+/// ```
+#[derive(Clone, Default)]
+pub struct Hooks {
+    start: Option<Arc<HookFn>>,
+    complete: Option<Arc<HookFn>>,
+}
+
+impl Hooks {
+    /// Creates hooks with no callbacks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a callback invoked when an operation starts.
+    pub fn on_start(mut self, hook: impl Fn(&OperationEvent<'_>) + Send + Sync + 'static) -> Self {
+        self.start = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sets a callback invoked when an operation finishes, fails or is
+    /// cancelled.
+    pub fn on_complete(
+        mut self,
+        hook: impl Fn(&OperationEvent<'_>) + Send + Sync + 'static,
+    ) -> Self {
+        self.complete = Some(Arc::new(hook));
+        self
+    }
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hooks")
+            .field("start", &self.start.is_some())
+            .field("complete", &self.complete.is_some())
+            .finish()
+    }
+}
+
+/// Installs `hooks` for every later operation, replacing any installed
+/// before, or removes them with `None`.
+///
+/// Operations already started report to the hooks in place when they
+/// started.
+pub fn set_hooks(hooks: Option<Hooks>) {
+    *GLOBAL.write().unwrap() = hooks;
+}
+
+/// Reports `operation` on the file at `path` to the installed hooks, and with
+/// the `tracing` feature runs `fut` in a span, with the bytes `bytes` counts
+/// in its output.
+pub(crate) async fn observe<T>(
+    path: &Path,
+    operation: Operation,
+    priority: Priority,
+    bytes: impl FnOnce(&T) -> u64,
+    fut: impl Future<Output = Result<T, Error>>,
+) -> Result<T, Error> {
+    #[cfg(feature = "tracing")]
+    let span = span(path, operation, priority);
+    #[cfg(feature = "tracing")]
+    let fut = tracing::Instrument::instrument(fut, span.clone());
+    let mut observed = GLOBAL.read().unwrap().clone().map(|hooks| Observed {
+        hooks,
+        event: OperationEvent {
+            path,
+            operation,
+            priority,
+            bytes: 0,
+            duration: Duration::ZERO,
+            error: None,
+            cancelled: true,
+        },
+        start: sys::Instant::now(),
+        finished: false,
+    });
+    if let Some(Observed {
+        hooks: Hooks {
+            start: Some(hook), ..
+        },
+        event,
+        ..
+    }) = &observed
+    {
+        hook(&OperationEvent {
+            cancelled: false,
+            ..*event
+        });
+    }
+    let result = fut.await;
+    let (bytes, error) = match &result {
+        Ok(value) => (bytes(value), None),
+        Err(error) => (0, Some(error)),
+    };
+    #[cfg(feature = "tracing")]
+    {
+        span.record("bytes", bytes);
+        if let Some(error) = error {
+            span.record("error", tracing::field::display(error));
+        }
+    }
+    if let Some(observed) = &mut observed {
+        observed.finished = true;
+        if let Some(hook) = &observed.hooks.complete {
+            hook(&OperationEvent {
+                bytes,
+                duration: observed.start.elapsed(),
+                error,
+                cancelled: false,
+                ..observed.event
+            });
+        }
+    }
+    result
+}
+
+/// Returns the span `operation` runs in, named for the operation, with the
+/// bytes and error left to record once it finishes.
+#[cfg(feature = "tracing")]
+fn span(path: &Path, operation: Operation, priority: Priority) -> tracing::Span {
+    macro_rules! span {
+        ($name:literal) => {
+            tracing::info_span!(
+                $name,
+                path = %path.display(),
+                ?priority,
+                bytes = tracing::field::Empty,
+                error = tracing::field::Empty,
+            )
+        };
+    }
+    match operation {
+        Operation::Open => span!("open"),
+        Operation::Read => span!("read"),
+        Operation::Seek => span!("seek"),
+        Operation::Metadata => span!("metadata"),
+    }
+}
+
+/// Reports an operation as cancelled if it is dropped before finishing.
+struct Observed<'a> {
+    hooks: Hooks,
+    event: OperationEvent<'a>,
+    start: sys::Instant,
+    finished: bool,
+}
+
+impl Drop for Observed<'_> {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        if let Some(hook) = &self.hooks.complete {
+            hook(&OperationEvent {
+                duration: self.start.elapsed(),
+                ..self.event
+            });
+        }
+    }
+}
+
+#[cfg(all(test, feature = "tracing", not(target_arch = "wasm32")))]
+mod tests {
+    use crate::{File, Priority};
+    use std::sync::Mutex;
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Records each span's name and the bytes recorded on it.
+    #[derive(Default)]
+    struct Spans(Mutex<Vec<(&'static str, Option<u64>)>>);
+
+    struct Bytes<'a>(&'a mut Option<u64>);
+
+    impl Visit for Bytes<'_> {
+        fn record_u64(&mut self, field: &Field, value: u64) {
+            if field.name() == "bytes" {
+                *self.0 = Some(value);
+            }
+        }
+
+        fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+    }
+
+    impl Subscriber for Spans {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut spans = self.0.lock().unwrap();
+            spans.push((span.metadata().name(), None));
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            let mut spans = self.0.lock().unwrap();
+            let bytes = &mut spans[span.into_u64() as usize - 1].1;
+            values.record(&mut Bytes(bytes));
+        }
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, _event: &Event<'_>) {}
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    #[test]
+    fn test_spans() {
+        let spans = std::sync::Arc::new(Spans::default());
+        tracing::subscriber::with_default(spans.clone(), || {
+            test_executors::spin_on(async {
+                let mut file = File::open("README.md", Priority::unit_test())
+                    .await
+                    .unwrap();
+                file.read(4, Priority::unit_test()).await.unwrap();
+                file.seek(std::io::SeekFrom::Start(0), Priority::unit_test())
+                    .await
+                    .unwrap();
+                file.metadata(Priority::unit_test()).await.unwrap();
+            })
+        });
+        assert_eq!(
+            *spans.0.lock().unwrap(),
+            [
+                ("open", Some(0)),
+                ("read", Some(4)),
+                ("seek", Some(0)),
+                ("metadata", Some(0)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Copying between open files.
+
+use crate::{COPY_CHUNK_SIZE, Error, File, Priority, throttle};
+use std::io::SeekFrom;
+use std::sync::atomic::Ordering;
+
+/// Copies the rest of `src` into `dst`, returning the number of bytes copied.
+///
+/// Reads start from `src`'s position and writes from `dst`'s, in chunks of
+/// the same size [`copy`](crate::copy) uses, and both positions end up past
+/// the bytes copied. `dst` must have been opened for writing, for example
+/// with [`File::from_std`]; files opened with [`File::open`] are read-only.
+/// A pipe or other stream can be either end.
+///
+/// Like `std::io::copy`, this stops at the end of `src` and returns what was
+/// copied; a failed read or write returns the error, and whatever was
+/// already written stays written.
+///
+/// # Errors
+///
+/// Returns an error if `src` can't be read or `dst` can't be written. On
+/// WASM, where files are read-only, this always fails.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{File, Priority, io_copy};
+///
+/// let src = File::open("assets/logo.png", Priority::unit_test()).await?;
+/// let mut dst = File::from_std(std::fs::File::create("install/logo.png").unwrap());
+/// let bytes = io_copy(&src, &mut dst, Priority::unit_test()).await?;
+/// println!("Copied {bytes} bytes");
+/// # Ok(())
+/// # }
+/// ```
+pub async fn io_copy(src: &File, dst: &mut File, priority: Priority) -> Result<u64, Error> {
+    // Write where dst's next read would start, not past what it read ahead.
+    dst.seek(SeekFrom::Current(0), priority).await?;
+    let mut copied = 0;
+    loop {
+        let chunk = src.read(COPY_CHUNK_SIZE, priority).await?;
+        if chunk.is_empty() {
+            return Ok(copied);
+        }
+        let len = chunk.len() as u64;
+        throttle::admit(priority).await;
+        dst.inner
+            .write_all(chunk.into_sys(), priority)
+            .await
+            .map_err(Error)?;
+        throttle::charge(priority, len);
+        dst.pos.fetch_add(len, Ordering::Relaxed);
+        copied += len;
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::io_copy;
+    use crate::{File, Priority};
+    use std::io::SeekFrom;
+
+    #[test_executors::async_test]
+    async fn test_io_copy() {
+        logwise::context::Context::reset("test_io_copy".to_string());
+        let dir = crate::tests::temp_path("io_copy");
+        std::fs::create_dir_all(&dir).unwrap();
+        let contents: Vec<u8> = (0..=255).cycle().take(600 * 1024).collect();
+        std::fs::write(dir.join("src"), &contents).unwrap();
+        std::fs::write(dir.join("dst"), b"0123456789").unwrap();
+
+        let src = File::open(dir.join("src"), Priority::unit_test())
+            .await
+            .unwrap();
+        src.read(100, Priority::unit_test()).await.unwrap();
+        let dst = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(dir.join("dst"))
+            .unwrap();
+        let mut dst = File::from_std(dst);
+        // Reading ahead doesn't move where the copy is written.
+        dst.read(4, Priority::unit_test()).await.unwrap();
+        let copied = io_copy(&src, &mut dst, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(copied, contents.len() as u64 - 100);
+        assert_eq!(
+            dst.seek(SeekFrom::Current(0), Priority::unit_test())
+                .await
+                .unwrap(),
+            4 + copied
+        );
+        let written = std::fs::read(dir.join("dst")).unwrap();
+        assert_eq!(&written[..4], b"0123");
+        assert_eq!(&written[4..], &contents[100..]);
+
+        // A file opened with File::open is read-only.
+        let src = File::open(dir.join("src"), Priority::unit_test())
+            .await
+            .unwrap();
+        let mut read_only = File::open(dir.join("dst"), Priority::unit_test())
+            .await
+            .unwrap();
+        assert!(
+            io_copy(&src, &mut read_only, Priority::unit_test())
+                .await
+                .is_err()
+        );
+    }
+}
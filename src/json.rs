@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Deserializing whole JSON documents from files.
+
+use crate::{Data, Error, File, Priority, sys};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+impl Data {
+    /// Deserializes the bytes as a JSON document.
+    ///
+    /// The result may borrow from the buffer, so `&str` and `&[u8]` fields
+    /// are read without copying (for strings, as long as they contain no
+    /// escapes).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a valid JSON `T`. The error names
+    /// the line it was found on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, Priority};
+    ///
+    /// // A list of asset names, such as ["hero.png", "theme.ogg"]
+    /// let file = File::open("assets.json", Priority::unit_test()).await?;
+    /// let data = file.read_all(Priority::unit_test()).await?;
+    /// let assets: Vec<&str> = data.deserialize_json()?;
+    /// println!("{} assets", assets.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn deserialize_json<'de, T: Deserialize<'de>>(&'de self) -> Result<T, Error> {
+        serde_json::from_slice(self).map_err(json_error)
+    }
+}
+
+impl File {
+    /// Reads the rest of the file and deserializes it as a JSON document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails, or if the contents are not a valid
+    /// JSON `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, Priority};
+    /// use std::collections::HashMap;
+    ///
+    /// let file = File::open("ports.json", Priority::unit_test()).await?;
+    /// let ports: HashMap<String, u16> = file.read_json(Priority::unit_test()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_json<T: DeserializeOwned>(&self, priority: Priority) -> Result<T, Error> {
+        self.read_all(priority).await?.deserialize_json()
+    }
+}
+
+fn json_error(source: serde_json::Error) -> Error {
+    Error(sys::Error::Json {
+        line: source.line() as u64,
+        source,
+    })
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use crate::{Data, File, Priority};
+
+    #[test]
+    fn test_deserialize_json_borrows() {
+        let data = Data::from_boxed_slice(Box::from(&br#"{"name": "atlas"}"#[..]));
+        let parsed: std::collections::HashMap<&str, &str> = data.deserialize_json().unwrap();
+        let name = parsed["name"];
+        assert_eq!(name, "atlas");
+        assert!(data.as_ptr_range().contains(&name.as_ptr()));
+    }
+
+    #[test_executors::async_test]
+    async fn test_read_json() {
+        logwise::context::Context::reset("test_read_json".to_string());
+        let path = crate::tests::temp_path("read_json.json");
+        std::fs::write(&path, "[1, 2,\n 3]").unwrap();
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        let numbers: Vec<u32> = file.read_json(Priority::unit_test()).await.unwrap();
+        assert_eq!(numbers, [1, 2, 3]);
+
+        std::fs::write(&path, "[1, 2,\n oops]").unwrap();
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        let error = file
+            .read_json::<Vec<u32>>(Priority::unit_test())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("line 2"), "{error}");
+    }
+}
@@ -54,7 +54,15 @@ This constraint:
 - Avoids many classes of concurrency bugs
 - Matches typical file I/O patterns
 
-Attempting concurrent operations on the same file handle will result in undefined behavior.
+Operations are serialized rather than refused: one started while another is
+in flight on the same file handle waits in a per-file queue and runs when the
+file is free. Waiting operations run in priority order, and in the order they
+were started within a priority, so several tasks holding `&File` can submit
+reads without coordinating. Dropping a waiting operation's future takes it
+out of the queue. To have the borrow checker rule out waiting altogether,
+use the file through [`File::exclusive`], whose operations take `&mut self`.
+To read one file from several tasks in parallel, give each its own handle
+with [`File::try_clone`].
 
 ## Memory Management Strategy
 
@@ -78,9 +86,11 @@ use async_file::{File, Priority};
 let file = File::open("config.txt", Priority::highest_async()).await?;
 let contents = file.read_all(Priority::highest_async()).await?;
 
-// Convert to String if needed
-let text = String::from_utf8(contents.into_boxed_slice().into_vec())
-    .expect("Invalid UTF-8");
+// Convert to String if needed, without copying
+match contents.into_string() {
+    Ok(text) => println!("{text}"),
+    Err((bytes, error)) => eprintln!("{} bytes, not UTF-8: {error}", bytes.len()),
+}
 # Ok(())
 # }
 ```
@@ -106,6 +116,25 @@ let data = file.read(4096, Priority::unit_test()).await?;
 # }
 ```
 
+## Buffered Reading
+
+Each [`File::read`] is a round trip to the backend. For parsers that issue many
+small reads, wrap the file in a [`BufFile`]:
+
+```
+# async fn example() -> Result<(), async_file::Error> {
+use async_file::{BufFile, File, Priority};
+
+let file = File::open("/dev/zero", Priority::unit_test()).await?;
+let mut reader = BufFile::new(file);
+
+// Served from one buffered backend read
+let tag = reader.read(2, Priority::unit_test()).await?;
+let len = reader.read(4, Priority::unit_test()).await?;
+# Ok(())
+# }
+```
+
 ## Checking File Existence Before Opening
 
 ```
@@ -150,13 +179,13 @@ let test_file = File::open("test_fixture.txt",
 
 # Platform Support
 
-- **Unix/Linux/macOS**: Uses `blocking` crate to run `std::fs` operations in a thread pool
-- **WASM**: Uses web fetch API for remote file access (requires `set_default_origin`)
-- **Windows**: Same as Unix implementation using `blocking` crate
+- **Unix/Linux/macOS**: Runs `std::fs` operations on a priority-ordered thread pool
+- **WASM**: Uses web fetch API for remote file access (requires `set_default_origin`), and the `web` module for local files the user picks. With the `node` feature, reads local files under Node.js
+- **Windows**: Same as Unix implementation
 
 # Design Philosophy
 
-This library enforces that only one operation may be in-flight at a time per file handle.
+This library runs only one operation at a time per file handle, queueing the rest.
 This constraint simplifies the implementation and prevents many classes of concurrency bugs.
 
 The library uses opaque types (`File`, `Data`, `Metadata`) that wrap platform-specific
@@ -203,13 +232,182 @@ pub fn set_default_origin(origin: &'static str) {
     sys::set_default_origin(origin);
 }
 
+/// Sets the [`FetchOptions`] used for every WASM request.
+///
+/// Options given to [`File::open_with`] with [`OpenOptions::fetch`] are
+/// applied on top of these for that file. Files already open keep the options
+/// they were opened with; other requests use the options current when they
+/// are made.
+///
+/// # Platform Behavior
+///
+/// - **WASM**: Configures the `fetch` requests that read files
+/// - **Other platforms**: No-op, provided for cross-platform compatibility
+///
+/// # Examples
+///
+/// ```
+/// use async_file::{FetchOptions, set_fetch_options};
+///
+/// set_fetch_options(FetchOptions::new().header("Authorization", "Bearer eyJhbGciOi..."));
+/// ```
+pub fn set_fetch_options(options: FetchOptions) {
+    sys::set_fetch_options(options);
+}
+
+/// Sets the maximum number of threads that run blocking filesystem calls on
+/// native platforms.
+///
+/// Filesystem calls are queued for a pool of worker threads, and run in
+/// [`Priority`] order when more are queued than there are threads: a
+/// [`Priority::highest_async`] read starts before background reads queued
+/// ahead of it. Calls of equal priority run in the order they were made.
+///
+/// On Linux and Android, each worker thread also takes on a kernel I/O
+/// priority (`ioprio`) matching the call it is running, so
+/// [`Priority::Background`] calls use the idle class and yield disk time to
+/// other I/O system-wide. Whether the kernel honors this depends on the disk's
+/// I/O scheduler. On macOS and iOS, worker threads likewise take on a QoS
+/// class and disk I/O policy, with [`Priority::Background`] calls throttled
+/// behind other I/O. On Windows, reads and writes through an open [`File`]
+/// carry an I/O priority hint, very low for [`Priority::Background`] and low
+/// for [`Priority::Utility`].
+///
+/// More threads let more calls overlap, which helps on disks and network
+/// filesystems that serve requests in parallel; fewer threads queue more
+/// calls, so priorities take effect sooner. Threads are started as needed and
+/// exit after a period of idleness. The default is 32. Values below 1 are
+/// treated as 1.
+///
+/// Calls already running are not interrupted when the limit is lowered. To
+/// also limit the calls of each priority, use [`set_config`].
+///
+/// On WASM, where operations are HTTP requests, this does nothing.
+///
+/// # Examples
+///
+/// ```
+/// use async_file::{set_worker_threads, worker_threads};
+///
+/// // A spinning disk serves one request at a time; keep the queue in our hands
+/// set_worker_threads(2);
+/// # #[cfg(not(target_arch = "wasm32"))]
+/// assert_eq!(worker_threads(), 2);
+/// ```
+pub fn set_worker_threads(threads: usize) {
+    sys::set_worker_threads(threads);
+}
+
+/// Returns the maximum number of worker threads set by
+/// [`set_worker_threads`], or 0 on WASM, which has none.
+pub fn worker_threads() -> usize {
+    sys::worker_threads()
+}
+
+mod advice;
+mod aggregate;
+mod ambient;
+#[cfg(not(target_arch = "wasm32"))]
+mod blocking;
+mod buf_file;
+mod bulk;
+#[cfg(feature = "bytes")]
+mod bytes_impl;
+mod cache_dir;
+mod capabilities;
+mod channel;
+mod coalesce;
+mod config;
+#[cfg(feature = "conformance")]
+pub mod conformance;
+#[cfg(all(feature = "devserver", not(target_arch = "wasm32")))]
+pub mod devserver;
+mod dir_size;
+mod exclusive;
+mod fetch_options;
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+mod file_reader;
+mod fs_stats;
+mod hooks;
+mod io_copy;
+#[cfg(feature = "serde")]
+mod json;
+mod memory;
+#[cfg(feature = "serde")]
+mod ndjson;
+mod op_queue;
+mod open_options;
+mod priority_scope;
+mod ranged;
+mod read_once;
+mod read_options;
+mod remove_dir;
+mod retry;
+mod stats;
 #[cfg(not(target_arch = "wasm32"))]
 mod std_impl;
+mod text;
+mod throttle;
+mod timeout;
+mod times;
+#[cfg(feature = "toml")]
+mod toml;
+#[cfg(all(unix, not(target_arch = "wasm32")))]
+pub mod unix;
+mod walk;
 #[cfg(target_arch = "wasm32")]
 mod wasm_impl;
+#[cfg(target_arch = "wasm32")]
+pub mod web;
+#[cfg(all(windows, not(target_arch = "wasm32")))]
+pub mod windows;
+mod xattr;
+mod yield_now;
+
+pub use advice::Advice;
+pub use ambient::{OperationHandle, WithHandle, WithPriority, current_priority, with_priority};
+#[cfg(not(target_arch = "wasm32"))]
+pub use blocking::BlockingReader;
+pub use buf_file::{BufFile, Lines};
+pub use bulk::{BulkResult, CopyDirOptions, copy_dir_all, copy_dir_all_with, read_many};
+pub use cache_dir::{CacheDir, CacheDirOptions};
+pub use capabilities::{FsCapabilities, probe};
+pub use channel::stream_to_channel;
+pub use coalesce::{read_coalescing, set_read_coalescing};
+pub use config::{Config, config, max_queue_wait, set_config};
+pub use dir_size::{DirSizeOptions, SizeKind, dir_size, dir_size_with};
+pub use exclusive::Exclusive;
+pub use fetch_options::{
+    CachePolicy, FetchCache, FetchCredentials, FetchMode, FetchOptions, RetryPolicy,
+};
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+pub use file_reader::FileReader;
+pub use fs_stats::{FsStats, fs_stats};
+pub use hooks::{Hooks, Operation, OperationEvent, set_hooks};
+pub use io_copy::io_copy;
+pub use memory::{memory_budget, memory_in_use, set_memory_budget};
+#[cfg(feature = "serde")]
+pub use ndjson::{NdjsonRecords, read_ndjson};
+pub use open_options::OpenOptions;
+pub use priority_scope::PriorityScope;
+pub use ranged::{PathStore, RangedFuture, RangedRead};
+pub use read_once::read_once;
+pub use read_options::ReadOptions;
+pub use remove_dir::{RemoveDirOptions, remove_dir_all, remove_dir_all_with};
+pub use stats::{SchedulerStats, stats};
+pub use text::{Text, TextEncoding, TextFallback, TextOptions};
+pub use throttle::{Throttle, set_throttle, throttle};
+pub use timeout::{Timeout, TimeoutExt};
+pub use times::{FileTimes, set_modified, set_times};
+pub use walk::{EntryKind, SymlinkPolicy, WalkOptions, walk_dir};
+pub use xattr::{get_xattr, list_xattrs, remove_xattr, set_xattr};
 
+use read_options::Reporter;
 use std::hash::Hash;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 
 #[cfg(not(target_arch = "wasm32"))]
 use std_impl as sys;
@@ -223,8 +421,24 @@ use wasm_impl as sys;
 ///
 /// # Constraints
 ///
-/// Only one operation may be in-flight at a time per file handle. This means
-/// you cannot start a new operation until the previous one completes.
+/// Only one operation may be in-flight at a time per file handle. One started
+/// while another is in flight waits for it, in a per-file queue ordered by
+/// priority and then by arrival, so operations can be started from several
+/// tasks sharing `&File`. [`File::exclusive`] makes the borrow checker rule
+/// out waiting. [`File::try_clone`] opens another handle for reading in
+/// parallel.
+///
+/// # Cancellation
+///
+/// Dropping an operation's future cancels it. A cancelled read leaves the
+/// file position where it was before the read, on every platform: the next
+/// read starts from the same place, as if the cancelled one had never been
+/// made. This holds for reads of several chunks, such as
+/// [`read_all`](File::read_all), as well. On native platforms a blocking call
+/// that has already started runs to completion on its worker thread and its
+/// bytes are discarded; one still queued never runs. On WASM the fetch is
+/// aborted. Reads of pipes and other streams, which have no positions, are
+/// the exception: bytes a cancelled read took from the stream are lost.
 ///
 /// # Examples
 ///
@@ -239,7 +453,24 @@ use wasm_impl as sys;
 /// # }
 /// ```
 #[derive(Debug)]
-pub struct File(sys::File);
+pub struct File {
+    inner: sys::File,
+    /// The path this file was opened with, used to match coalesced reads.
+    path: PathBuf,
+    /// The physical file position, mirrored from the backend.
+    pos: AtomicU64,
+    /// Bytes read ahead for small-read aggregation.
+    page: Mutex<aggregate::PageBuffer>,
+    /// Serializes operations, which wait here while another is in flight.
+    queue: op_queue::OpQueue,
+    /// How native operations are retried, from [`OpenOptions::retry`].
+    retry: Option<Arc<RetryPolicy>>,
+    /// A policy given to the operation in flight, used in place of `retry`.
+    retry_override: Mutex<Option<Arc<RetryPolicy>>>,
+    /// Whether reads are coalesced, from [`OpenOptions::coalesce`], in place
+    /// of the process-wide setting.
+    coalesce: Option<bool>,
+}
 
 /// A priority value for scheduling file operations.
 ///
@@ -247,6 +478,20 @@ pub struct File(sys::File);
 /// the scheduling priority of your file operations.
 pub type Priority = priority::Priority;
 
+/// The number of priority bands, one for each value of [`rank`].
+pub(crate) const BANDS: usize = 4;
+
+/// Ranks `priority` by urgency; lower is more urgent.
+fn rank(priority: Priority) -> u8 {
+    match priority {
+        Priority::UserInteractive => 0,
+        Priority::UserInitiated => 1,
+        Priority::Background => 3,
+        // Utility, Unknown, and anything added later.
+        _ => 2,
+    }
+}
+
 /// An opaque buffer type that holds data read from files.
 ///
 /// `Data` represents memory that may be allocated and managed by the OS. It provides
@@ -264,6 +509,17 @@ pub type Priority = priority::Priority;
 /// You can access the underlying bytes through several methods:
 /// - `as_ref()` or `deref()` to get a `&[u8]` slice
 /// - `into_boxed_slice()` to convert to a `Box<[u8]>` (may require copying)
+/// - `slice()` to carve out part of the buffer without copying
+/// - `clone()` to hand the same bytes to another consumer without copying
+///
+/// # Alignment
+///
+/// No alignment is guaranteed: like a `Box<[u8]>`, a `Data` may start at any
+/// address. Reads of files opened with [`OpenOptions::direct`] at 4096-byte
+/// aligned positions happen to return 4096-byte aligned buffers on native
+/// platforms, but code that casts the bytes to wider types, or passes them to
+/// APIs with alignment requirements, should use
+/// [`with_alignment`](Data::with_alignment).
 ///
 /// # Examples
 ///
@@ -283,18 +539,46 @@ pub type Priority = priority::Priority;
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Debug)]
-pub struct Data(sys::Data);
+pub struct Data {
+    buffer: Arc<Buffer>,
+    range: std::ops::Range<usize>,
+}
+
+/// The allocation behind one or more [`Data`] views.
+struct Buffer(
+    sys::Data,
+    // Held only to be released on drop.
+    #[allow(dead_code)] memory::Lease,
+);
+
+impl Clone for Data {
+    /// Returns a second handle to the same bytes, without copying them.
+    ///
+    /// The buffer is freed (and released from the
+    /// [memory budget](set_memory_budget)) when the last handle is dropped.
+    fn clone(&self) -> Self {
+        Data {
+            buffer: self.buffer.clone(),
+            range: self.range.clone(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Data {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Data").field(&&self[..]).finish()
+    }
+}
 
 impl AsRef<[u8]> for Data {
     fn as_ref(&self) -> &[u8] {
-        self.0.as_ref()
+        self
     }
 }
 impl std::ops::Deref for Data {
     type Target = [u8];
     fn deref(&self) -> &[u8] {
-        self.0.deref()
+        &self.buffer.0[self.range.clone()]
     }
 }
 
@@ -305,7 +589,8 @@ impl Data {
     ///
     /// - On platforms where the underlying memory layout is compatible, this is
     ///   a zero-cost operation
-    /// - On other platforms, this may require copying the data
+    /// - On other platforms, or when the buffer is shared with a clone or a
+    ///   [`slice`](Data::slice), this copies the data
     ///
     /// # When to Use
     ///
@@ -334,7 +619,208 @@ impl Data {
     /// # }
     /// ```
     pub fn into_boxed_slice(self) -> Box<[u8]> {
-        self.0.into_boxed_slice()
+        self.into_sys().into_boxed_slice()
+    }
+
+    /// Converts this `Data` into a `String`, if it is valid UTF-8.
+    ///
+    /// The bytes are validated in place. When the buffer can be handed over
+    /// as is (see [`into_boxed_slice`](Data::into_boxed_slice)), the string
+    /// reuses it without copying.
+    ///
+    /// # Errors
+    ///
+    /// If the bytes are not valid UTF-8, returns them unchanged along with
+    /// the [`Utf8Error`](std::str::Utf8Error) describing where validation
+    /// failed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, Priority};
+    ///
+    /// let file = File::open("upload.txt", Priority::unit_test()).await?;
+    /// let contents = file.read_all(Priority::unit_test()).await?;
+    /// match contents.into_string() {
+    ///     Ok(text) => println!("{text}"),
+    ///     Err((bytes, error)) => {
+    ///         // The bytes are still available, for example to store as binary
+    ///         let valid = &bytes[..error.valid_up_to()];
+    ///         eprintln!("rejected upload after {} valid bytes: {error}", valid.len());
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_string(self) -> Result<String, (Data, std::str::Utf8Error)> {
+        if let Err(error) = std::str::from_utf8(&self) {
+            return Err((self, error));
+        }
+        let bytes = self.into_sys().into_vec();
+        // SAFETY: these are the bytes just validated, moved or copied.
+        Ok(unsafe { String::from_utf8_unchecked(bytes) })
+    }
+
+    /// Returns a view of `range` of this buffer, sharing its memory.
+    ///
+    /// No bytes are copied: the view and this `Data` refer to the same
+    /// allocation, which is freed (and released from the
+    /// [memory budget](set_memory_budget)) when the last view is dropped. A
+    /// small slice therefore keeps the whole buffer alive; copy it out with
+    /// [`into_boxed_slice`](Data::into_boxed_slice) if that matters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or its start is after its end, as
+    /// slice indexing does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, Priority};
+    ///
+    /// let file = File::open("records.bin", Priority::unit_test()).await?;
+    /// let data = file.read_all(Priority::unit_test()).await?;
+    ///
+    /// // Carve fixed-size records out of the file without copying them
+    /// let records: Vec<_> = (0..data.len() / 64)
+    ///     .map(|i| data.slice(i * 64..(i + 1) * 64))
+    ///     .collect();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn slice(&self, range: impl std::ops::RangeBounds<usize>) -> Data {
+        use std::ops::Bound;
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.checked_add(1).expect("range start overflows"),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.checked_add(1).expect("range end overflows"),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len(),
+        };
+        assert!(
+            start <= end,
+            "slice index starts at {start} but ends at {end}"
+        );
+        assert!(
+            end <= self.len(),
+            "range end index {end} out of range for Data of length {}",
+            self.len()
+        );
+        Data {
+            buffer: self.buffer.clone(),
+            range: self.range.start + start..self.range.start + end,
+        }
+    }
+
+    /// Returns the same bytes starting at an address that is a multiple of
+    /// `align`.
+    ///
+    /// If the bytes are already aligned, this returns `self` unchanged.
+    /// Otherwise they are copied once into a buffer allocated with room to
+    /// place them at an aligned address.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, Priority};
+    ///
+    /// let file = File::open("weights.f32", Priority::unit_test()).await?;
+    /// let data = file.read_all(Priority::unit_test()).await?.with_alignment(16);
+    /// assert_eq!(data.as_ptr() as usize % 16, 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_alignment(self, align: usize) -> Data {
+        assert!(align.is_power_of_two(), "alignment must be a power of two");
+        if self.as_ptr() as usize % align == 0 {
+            return self;
+        }
+        let mut buffer = vec![0; self.len() + align - 1].into_boxed_slice();
+        let start = buffer.as_ptr().align_offset(align);
+        buffer[start..start + self.len()].copy_from_slice(&self);
+        let len = self.len();
+        drop(self);
+        Data::from_boxed_slice(buffer).slice(start..start + len)
+    }
+
+    /// Joins buffers end to end into one.
+    ///
+    /// The result is allocated once, sized from the summed lengths. A single
+    /// buffer is returned as is, without copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{Data, File, Priority};
+    ///
+    /// let file = File::open("upload.bin", Priority::unit_test()).await?;
+    /// let mut chunks = Vec::new();
+    /// loop {
+    ///     let chunk = file.read(64 * 1024, Priority::unit_test()).await?;
+    ///     if chunk.is_empty() {
+    ///         break;
+    ///     }
+    ///     chunks.push(chunk);
+    /// }
+    /// let whole = Data::concat(chunks);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn concat(parts: impl IntoIterator<Item = Data>) -> Data {
+        let mut parts: Vec<Data> = parts.into_iter().collect();
+        if parts.len() == 1 {
+            return parts.pop().unwrap();
+        }
+        let len = parts.iter().map(|part| part.len()).sum();
+        let mut joined = Vec::with_capacity(len);
+        for part in &parts {
+            joined.extend_from_slice(part);
+        }
+        Data::from_boxed_slice(joined.into_boxed_slice())
+    }
+
+    pub(crate) fn from_boxed_slice(slice: Box<[u8]>) -> Self {
+        let lease = memory::Lease::new(slice.len());
+        Data::from_sys(sys::Data::from_boxed_slice(slice), lease)
+    }
+
+    /// Unwraps the backend buffer, if no other view shares it.
+    pub(crate) fn try_into_sys(self) -> Option<sys::Data> {
+        Arc::try_unwrap(self.buffer)
+            .ok()
+            .map(|Buffer(data, _)| data)
+    }
+
+    pub(crate) fn from_sys(data: sys::Data, lease: memory::Lease) -> Self {
+        Data {
+            range: 0..data.len(),
+            buffer: Arc::new(Buffer(data, lease)),
+        }
+    }
+
+    /// Unwraps the backend buffer, copying if it is shared or this is a
+    /// slice of it.
+    pub(crate) fn into_sys(self) -> sys::Data {
+        if self.range == (0..self.buffer.0.len()) {
+            match Arc::try_unwrap(self.buffer) {
+                Ok(Buffer(data, _)) => data,
+                Err(buffer) => sys::Data::from_boxed_slice(Box::from(&*buffer.0)),
+            }
+        } else {
+            sys::Data::from_boxed_slice(Box::from(&self.buffer.0[self.range]))
+        }
     }
 }
 
@@ -362,6 +848,9 @@ impl File {
     /// - Permissions are insufficient
     /// - Other I/O errors occur
     ///
+    /// On Unix, opening a directory succeeds and the error only surfaces on the
+    /// first read. Use [`Metadata::is_dir`] to tell what was opened.
+    ///
     /// # Examples
     ///
     /// ```
@@ -377,11 +866,168 @@ impl File {
     /// # }
     /// ```
     pub async fn open(path: impl AsRef<Path>, priority: Priority) -> Result<Self, Error> {
-        sys::File::open(path, priority)
+        let path = path.as_ref().to_owned();
+        let open = async { sys::File::open(&path, priority).await.map_err(Error) };
+        let inner = hooks::observe(&path, Operation::Open, priority, |_| 0, open).await?;
+        Ok(File::from_inner(inner, path))
+    }
+
+    /// Opens a file for reading, with the given options.
+    ///
+    /// # Errors
+    ///
+    /// As for [`open`](File::open). With [`OpenOptions::direct`], opening also
+    /// fails if the platform or filesystem does not support direct I/O.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, OpenOptions, Priority};
+    ///
+    /// // Scan a large log without evicting everything else from the page cache
+    /// let options = OpenOptions::new().direct(true);
+    /// let file = File::open_with("huge.log", &options, Priority::unit_test()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn open_with(
+        path: impl AsRef<Path>,
+        options: &OpenOptions,
+        priority: Priority,
+    ) -> Result<Self, Error> {
+        let path = path.as_ref().to_owned();
+        let open = async {
+            retry::retrying(options.get_retry(), || {
+                sys::File::open_with(&path, options, priority)
+            })
             .await
-            .map(File)
             .map_err(Error)
+        };
+        let inner = hooks::observe(&path, Operation::Open, priority, |_| 0, open).await?;
+        let mut file = File::from_inner(inner, path);
+        file.retry = options.get_retry().cloned().map(Arc::new);
+        file.coalesce = options.get_coalesce();
+        Ok(file)
+    }
+
+    fn from_inner(inner: sys::File, path: PathBuf) -> Self {
+        File {
+            inner,
+            path,
+            pos: AtomicU64::new(0),
+            page: Mutex::default(),
+            queue: op_queue::OpQueue::default(),
+            retry: None,
+            retry_override: Mutex::new(None),
+            coalesce: None,
+        }
+    }
+
+    /// Wraps a file opened by other means, such as a descriptor inherited
+    /// from a service manager or returned by another library.
+    ///
+    /// Reads start from the file's current position. The file has no path, so
+    /// its reads are never [coalesced](set_read_coalescing) with others, and
+    /// on Windows [`set_times`](File::set_times) fails. It is never treated
+    /// as opened for direct I/O, whatever flags it was opened with.
+    ///
+    /// Not available on WASM.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, Priority};
+    ///
+    /// let std_file = std::fs::File::open("/dev/zero").unwrap();
+    /// let file = File::from_std(std_file);
+    /// let data = file.read(16, Priority::unit_test()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_std(file: std::fs::File) -> Self {
+        let inner = sys::File::from_std(file);
+        let pos = inner.position();
+        let file = File::from_inner(inner, PathBuf::new());
+        file.pos.store(pos, Ordering::Relaxed);
+        file
+    }
+
+    /// Converts into a `std::fs::File`, its cursor at the position the next
+    /// read would have started from.
+    ///
+    /// If an operation whose future was dropped is still running on a worker
+    /// thread, it holds the file until it finishes, and the descriptor is
+    /// duplicated instead; the two then share a cursor.
+    ///
+    /// Not available on WASM.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cursor can't be moved, or if the descriptor
+    /// can't be duplicated.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn into_std(self) -> Result<std::fs::File, Error> {
+        let buffered = self.page.lock().unwrap().remaining() as u64;
+        let pos = self.pos.load(Ordering::Relaxed) - buffered;
+        self.inner
+            .into_std(pos)
+            .map_err(|e| Error(sys::Error::Io(e)))
+    }
+
+    /// Opens a second handle to the same file, with its own position, starting
+    /// where this one's next read would.
+    ///
+    /// Only one operation runs at a time on a handle, so this is how to read
+    /// one file from several tasks in parallel. On native platforms the
+    /// descriptor or handle is duplicated; on WASM the clone reads from the
+    /// same URL, local file or Node path as this one. The clone keeps the
+    /// [`OpenOptions::retry`] policy and [`OpenOptions::coalesce`] setting
+    /// this file was opened with.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the descriptor can't be duplicated, or on WASM if a
+    /// Node file can no longer be opened or a local file is used from another
+    /// thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, Priority};
+    /// use std::io::SeekFrom;
+    ///
+    /// let file = File::open("/dev/zero", Priority::unit_test()).await?;
+    /// let mut other = file.try_clone(Priority::unit_test()).await?;
+    /// other.seek(SeekFrom::Start(4096), Priority::unit_test()).await?;
+    /// // Each handle can have a read in flight; `file` still reads from 0.
+    /// let head = file.read(16, Priority::unit_test());
+    /// let tail = other.read(16, Priority::unit_test());
+    /// # let _ = (head.await?, tail.await?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn try_clone(&self, priority: Priority) -> Result<File, Error> {
+        let _in_flight = self.queue.turn(priority).await;
+        let buffered = self.page.lock().unwrap().remaining() as u64;
+        let pos = self.pos.load(Ordering::Relaxed) - buffered;
+        let inner = self.inner.try_clone(pos, priority).await.map_err(Error)?;
+        let mut file = File::from_inner(inner, self.path.clone());
+        file.pos.store(pos, Ordering::Relaxed);
+        file.retry = self.retry.clone();
+        file.coalesce = self.coalesce;
+        Ok(file)
+    }
+
+    /// Returns the retry policy for the operation in flight.
+    fn retry_policy(&self) -> Option<Arc<RetryPolicy>> {
+        let retry_override = self.retry_override.lock().unwrap().clone();
+        retry_override.or_else(|| self.retry.clone())
     }
+
     /// Reads up to `buf_size` bytes from the file.
     ///
     /// This method is similar to `std::fs::File::read` but with key differences:
@@ -402,7 +1048,18 @@ impl File {
     ///
     /// # Constraints
     ///
-    /// Only one operation may be in-flight at a time per file handle.
+    /// Only one operation may be in-flight at a time per file handle; this one
+    /// waits for any started before it.
+    ///
+    /// # Coalescing
+    ///
+    /// When [`set_read_coalescing`] is enabled, a read matching one already in
+    /// flight on another `File` for the same path shares its result.
+    ///
+    /// # Small Reads
+    ///
+    /// When [`set_small_read_aggregation`](File::set_small_read_aggregation) is
+    /// enabled, small reads are served from a page read ahead of time.
     ///
     /// # Examples
     ///
@@ -422,11 +1079,178 @@ impl File {
     /// # }
     /// ```
     pub async fn read(&self, buf_size: usize, priority: Priority) -> Result<Data, Error> {
-        self.0
-            .read(buf_size, priority)
-            .await
-            .map(Data)
-            .map_err(Error)
+        let read = async {
+            let _in_flight = self.queue.turn(priority).await;
+            self.read_inner(buf_size, priority).await
+        };
+        hooks::observe(&self.path, Operation::Read, priority, read_len, read).await
+    }
+
+    /// Reads as [`read`](File::read) does, within an operation already begun.
+    async fn read_inner(&self, buf_size: usize, priority: Priority) -> Result<Data, Error> {
+        if buf_size == 0 {
+            return Ok(Data::from_boxed_slice(Box::new([])));
+        }
+        let aggregate = {
+            let mut page = self.page.lock().unwrap();
+            if page.remaining() > 0 {
+                return Ok(page.take(buf_size));
+            }
+            page.enabled && buf_size < aggregate::PAGE_SIZE
+        };
+        if aggregate {
+            let data = self.read_shared(aggregate::PAGE_SIZE, priority).await?;
+            let mut page = self.page.lock().unwrap();
+            page.fill(data);
+            return Ok(page.take(buf_size));
+        }
+        self.read_shared(buf_size, priority).await
+    }
+
+    /// Reads up to `buf_size` bytes, reusing the allocation of a buffer returned
+    /// by an earlier read.
+    ///
+    /// This behaves like [`read`](File::read), but on native platforms the
+    /// bytes are read into `buf`'s allocation when it has room for `buf_size`
+    /// bytes and no [`slice`](Data::slice) of it is still alive. Loops that
+    /// read and discard many chunks can hand each chunk back to avoid an
+    /// allocation per read.
+    ///
+    /// Small buffers are also recycled automatically when dropped, so plain
+    /// `read` already avoids most allocations for reads up to 64 KiB. This
+    /// method matters most for larger reads. Reads served by
+    /// [coalescing](set_read_coalescing) or
+    /// [small-read aggregation](File::set_small_read_aggregation), and reads
+    /// of files opened for direct I/O, use their own buffers, and `buf` is
+    /// dropped. On WASM, `buf` is always dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, Priority};
+    ///
+    /// let file = File::open("video.mp4", Priority::unit_test()).await?;
+    /// let mut chunk = file.read(1024 * 1024, Priority::unit_test()).await?;
+    /// let mut checksum = 0u64;
+    /// while !chunk.is_empty() {
+    ///     checksum = chunk.iter().fold(checksum, |sum, &b| sum.wrapping_add(b as u64));
+    ///     chunk = file
+    ///         .read_into(chunk, 1024 * 1024, Priority::unit_test())
+    ///         .await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_into(
+        &self,
+        buf: Data,
+        buf_size: usize,
+        priority: Priority,
+    ) -> Result<Data, Error> {
+        let read = async {
+            let _in_flight = self.queue.turn(priority).await;
+            let buffered = buf_size == 0 || self.coalescing() || {
+                let page = self.page.lock().unwrap();
+                page.remaining() > 0 || (page.enabled && buf_size < aggregate::PAGE_SIZE)
+            };
+            if buffered {
+                drop(buf);
+                return self.read_inner(buf_size, priority).await;
+            }
+            self.read_physical_into(Some(buf), buf_size, priority).await
+        };
+        hooks::observe(&self.path, Operation::Read, priority, read_len, read).await
+    }
+
+    /// Enables or disables small-read aggregation for this file.
+    ///
+    /// When enabled, reads smaller than 4 KiB are satisfied from an internally
+    /// read 4 KiB page, so a parser reading 16 bytes at a time issues one
+    /// physical read per page instead of one per call. Reads of 4 KiB or more
+    /// are unaffected. Unlike [`BufFile`], this requires no change to the code
+    /// issuing the reads.
+    ///
+    /// While bytes remain in the page, any read (of any size) is served from the
+    /// page and may return fewer bytes than requested. Disabling aggregation
+    /// keeps already buffered bytes until they are read or a seek discards them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, Priority};
+    ///
+    /// let mut file = File::open("/dev/zero", Priority::unit_test()).await?;
+    /// file.set_small_read_aggregation(true);
+    ///
+    /// // One physical read serves all of these
+    /// for _ in 0..256 {
+    ///     file.read(16, Priority::unit_test()).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_small_read_aggregation(&mut self, enabled: bool) {
+        self.page.get_mut().unwrap().enabled = enabled;
+    }
+
+    /// Returns whether this file's reads are coalesced.
+    fn coalescing(&self) -> bool {
+        self.coalesce.unwrap_or_else(coalesce::read_coalescing)
+    }
+
+    /// Reads from the backend, sharing the read with other files if coalescing is enabled.
+    async fn read_shared(&self, buf_size: usize, priority: Priority) -> Result<Data, Error> {
+        // Files without a path, from `from_std`, can't be matched to others.
+        if self.coalescing() && !self.path.as_os_str().is_empty() {
+            let offset = self.pos.load(Ordering::Relaxed);
+            match coalesce::join(&self.path, offset, buf_size) {
+                coalesce::Role::Leader(guard) => {
+                    let result = self.read_physical(buf_size, priority).await;
+                    guard.finish(result.as_ref().ok().map(|data| &data[..]));
+                    return result;
+                }
+                coalesce::Role::Follower(slot) => {
+                    if let Some(bytes) = coalesce::wait(&slot).await {
+                        let end = offset + bytes.len() as u64;
+                        self.inner
+                            .seek(std::io::SeekFrom::Start(end), priority)
+                            .await
+                            .map_err(Error)?;
+                        self.pos.store(end, Ordering::Relaxed);
+                        return Ok(Data::from_boxed_slice(bytes.as_ref().into()));
+                    }
+                    // the shared read failed; try on our own
+                }
+            }
+        }
+        self.read_physical(buf_size, priority).await
+    }
+
+    async fn read_physical(&self, buf_size: usize, priority: Priority) -> Result<Data, Error> {
+        self.read_physical_into(None, buf_size, priority).await
+    }
+
+    async fn read_physical_into(
+        &self,
+        recycled: Option<Data>,
+        buf_size: usize,
+        priority: Priority,
+    ) -> Result<Data, Error> {
+        let mut recycled = recycled.and_then(Data::try_into_sys);
+        let lease = memory::Lease::admit(buf_size).await;
+        throttle::admit(priority).await;
+        let data = retry::retrying(self.retry_policy().as_deref(), || {
+            self.inner.read_into(recycled.take(), buf_size, priority)
+        })
+        .await
+        .map_err(Error)?;
+        throttle::charge(priority, data.len() as u64);
+        let lease = lease.resize(data.len());
+        let data = Data::from_sys(data, lease);
+        self.pos.fetch_add(data.len() as u64, Ordering::Relaxed);
+        Ok(data)
     }
 
     /// Seeks to a position in the file.
@@ -445,7 +1269,8 @@ impl File {
     ///
     /// # Constraints
     ///
-    /// Only one operation may be in-flight at a time per file handle.
+    /// Only one operation may be in-flight at a time per file handle; this one
+    /// waits for any started before it.
     ///
     /// # Examples
     ///
@@ -467,39 +1292,132 @@ impl File {
     /// # }
     /// ```
     pub async fn seek(&mut self, pos: std::io::SeekFrom, priority: Priority) -> Result<u64, Error> {
-        self.0.seek(pos, priority).await.map_err(Error)
+        let File {
+            inner,
+            path,
+            pos: file_pos,
+            page,
+            retry,
+            ..
+        } = self;
+        let seek = async {
+            let page = page.get_mut().unwrap();
+            let pos = match pos {
+                // the backend is ahead of us by whatever is still buffered
+                std::io::SeekFrom::Current(offset) => {
+                    std::io::SeekFrom::Current(offset - page.remaining() as i64)
+                }
+                other => other,
+            };
+            page.clear();
+            let pos = retry::retrying(retry.as_deref(), || inner.seek(pos, priority))
+                .await
+                .map_err(Error)?;
+            file_pos.store(pos, Ordering::Relaxed);
+            Ok(pos)
+        };
+        hooks::observe(path, Operation::Seek, priority, |_| 0, seek).await
     }
 
-    /// Returns metadata about the file.
+    /// Tells the operating system how `range` of the file is about to be read.
     ///
-    /// This method retrieves information about the file such as its size.
-    /// It behaves like `std::fs::File::metadata`.
+    /// This is `posix_fadvise` on Linux and Android. See [`Advice`] for how each
+    /// hint maps onto other platforms; where a hint has no equivalent, this does
+    /// nothing and succeeds. Advice never changes what reads return.
     ///
     /// # Arguments
     ///
+    /// * `advice` - The expected access pattern
+    /// * `range` - The byte range the advice applies to; `..` means the whole
+    ///   file, however long it grows
     /// * `priority` - The priority for this operation
     ///
+    /// # Errors
+    ///
+    /// Returns an error if the operating system rejects the advice, for example
+    /// because the range starts beyond what the platform can address.
+    ///
     /// # Examples
     ///
     /// ```
     /// # async fn example() -> Result<(), async_file::Error> {
-    /// use async_file::{File, Priority};
-    ///
-    /// let file = File::open("/dev/zero", Priority::unit_test()).await?;
-    /// let metadata = file.metadata(Priority::unit_test()).await?;
+    /// use async_file::{Advice, File, Priority};
     ///
-    /// println!("File size: {} bytes", metadata.len());
+    /// let file = File::open("index.bin", Priority::unit_test()).await?;
+    /// // Lookups jump around; don't read ahead
+    /// file.advise(Advice::Random, .., Priority::unit_test()).await?;
+    /// // But the header will be needed shortly
+    /// file.advise(Advice::WillNeed, 0..4096, Priority::unit_test()).await?;
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn metadata(&self, priority: Priority) -> Result<Metadata, Error> {
-        self.0.metadata(priority).await.map(Metadata).map_err(Error)
+    pub async fn advise(
+        &self,
+        advice: Advice,
+        range: impl std::ops::RangeBounds<u64>,
+        priority: Priority,
+    ) -> Result<(), Error> {
+        use std::ops::Bound;
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let len = match range.end_bound() {
+            Bound::Included(&end) => Some(end.saturating_add(1).saturating_sub(start)),
+            Bound::Excluded(&end) => Some(end.saturating_sub(start)),
+            Bound::Unbounded => None,
+        };
+        if len == Some(0) {
+            return Ok(());
+        }
+        let _in_flight = self.queue.turn(priority).await;
+        retry::retrying(self.retry.as_deref(), || {
+            self.inner.advise(advice, start, len, priority)
+        })
+        .await
+        .map_err(Error)
     }
 
-    /// Reads the entire contents of the file.
+    /// Returns metadata about the file.
+    ///
+    /// This method retrieves information about the file such as its size.
+    /// It behaves like `std::fs::File::metadata`.
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - The priority for this operation
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, Priority};
+    ///
+    /// let file = File::open("/dev/zero", Priority::unit_test()).await?;
+    /// let metadata = file.metadata(Priority::unit_test()).await?;
+    ///
+    /// println!("File size: {} bytes", metadata.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn metadata(&self, priority: Priority) -> Result<Metadata, Error> {
+        let metadata = async {
+            let _in_flight = self.queue.turn(priority).await;
+            retry::retrying(self.retry.as_deref(), || self.inner.metadata(priority))
+                .await
+                .map(Metadata)
+                .map_err(Error)
+        };
+        hooks::observe(&self.path, Operation::Metadata, priority, |_| 0, metadata).await
+    }
+
+    /// Reads the entire contents of the file.
     ///
     /// This is a convenience method that first retrieves the file's metadata
-    /// to determine its size, then reads that many bytes.
+    /// to determine its size, then reads that many bytes from the current
+    /// position. Large files are read in chunks with default [`ReadOptions`];
+    /// use [`read_all_with`](File::read_all_with) to tune the chunk size.
     ///
     /// # Arguments
     ///
@@ -510,7 +1428,8 @@ impl File {
     /// Returns an error if:
     /// - The metadata operation fails
     /// - The read operation fails
-    /// - The file is too large to fit in memory
+    /// - The file is too large to fit in memory, for example a file over 4 GiB
+    ///   on a 32-bit target
     ///
     /// # Examples
     ///
@@ -526,12 +1445,382 @@ impl File {
     /// # }
     /// ```
     pub async fn read_all(&self, priority: Priority) -> Result<Data, Error> {
-        let metadata = self.0.metadata(priority).await.map(Metadata)?;
-        let len = metadata.len();
-        self.read(len.try_into().unwrap(), priority).await
+        self.read_all_with(&ReadOptions::default(), priority).await
+    }
+
+    /// Reads the entire contents of the file with the given options.
+    ///
+    /// The file is read in chunks of at most [`ReadOptions::chunk_size`] bytes,
+    /// yielding to the executor between chunks. Dropping the returned future
+    /// between chunks abandons the read. With a
+    /// [`ReadOptions::map_threshold`], large files are memory-mapped instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, Priority, ReadOptions};
+    ///
+    /// let file = File::open("movie.mp4", Priority::unit_test()).await?;
+    /// let options = ReadOptions::new().chunk_size(16 * 1024 * 1024);
+    /// let contents = file.read_all_with(&options, Priority::unit_test()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_all_with(
+        &self,
+        options: &ReadOptions,
+        priority: Priority,
+    ) -> Result<Data, Error> {
+        let read = ambient::with_deadline(options.get_deadline(), async {
+            let _in_flight = self.queue.turn(priority).await;
+            *self.retry_override.lock().unwrap() = options.get_retry().cloned().map(Arc::new);
+            let _clear = ClearRetry(&self.retry_override);
+            let retry = self.retry_policy();
+            let metadata = retry::retrying(retry.as_deref(), || self.inner.metadata(priority))
+                .await
+                .map(Metadata)?;
+            let len = in_memory_len(metadata.len())?;
+            let map = options
+                .get_map_threshold()
+                .is_some_and(|threshold| metadata.is_file() && metadata.len() >= threshold);
+            let total = metadata
+                .len()
+                .saturating_sub(self.pos.load(Ordering::Relaxed));
+            let reporter = options.reporter(Some(total));
+            // Bytes buffered for small-read aggregation come before the mapped ones.
+            if map && self.page.lock().unwrap().remaining() == 0 {
+                let offset = self.pos.load(Ordering::Relaxed);
+                let mapped = retry::retrying(retry.as_deref(), || self.inner.map(offset, priority))
+                    .await
+                    .map_err(Error)?;
+                if let Some(data) = mapped {
+                    self.pos
+                        .store(offset + data.len() as u64, Ordering::Relaxed);
+                    if let Some(reporter) = &reporter {
+                        reporter.report(data.len() as u64);
+                    }
+                    // Mapped pages belong to the page cache, not the heap.
+                    return Ok(Data::from_sys(data, memory::Lease::new(0)));
+                }
+            }
+            self.read_up_to(len, options.get_chunk_size(), reporter.as_ref(), priority)
+                .await
+        });
+        hooks::observe(&self.path, Operation::Read, priority, read_len, read).await
+    }
+
+    /// Reads until `len` bytes have been read or the file ends, in chunks of
+    /// at most `chunk_size`, passing progress to `reporter`.
+    async fn read_up_to(
+        &self,
+        len: usize,
+        chunk_size: usize,
+        reporter: Option<&Arc<Reporter>>,
+        priority: Priority,
+    ) -> Result<Data, Error> {
+        // Empty files (and the marker files that fill some trees) need no read at all.
+        if len == 0 {
+            return Ok(Data::from_boxed_slice(Box::new([])));
+        }
+        let mut restore = RestorePosition::new(self);
+        let result = self.read_chunks(len, chunk_size, reporter, priority).await;
+        restore.0 = None;
+        result
+    }
+
+    /// Reads for [`read_up_to`](File::read_up_to), moving the position after
+    /// each chunk.
+    async fn read_chunks(
+        &self,
+        len: usize,
+        chunk_size: usize,
+        reporter: Option<&Arc<Reporter>>,
+        priority: Priority,
+    ) -> Result<Data, Error> {
+        let first = self
+            .read_reporting(len.min(chunk_size), 0, reporter, priority)
+            .await?;
+        if first.len() == len || first.is_empty() {
+            return Ok(first);
+        }
+        let mut contents = Vec::new();
+        contents
+            .try_reserve_exact(len)
+            .map_err(|_| Error(sys::Error::TooLarge(len as u64)))?;
+        contents.extend_from_slice(&first);
+        drop(first);
+        while contents.len() < len {
+            yield_now::yield_now().await;
+            let chunk = self
+                .read_reporting(
+                    (len - contents.len()).min(chunk_size),
+                    contents.len() as u64,
+                    reporter,
+                    priority,
+                )
+                .await?;
+            if chunk.is_empty() {
+                break;
+            }
+            contents.extend_from_slice(&chunk);
+        }
+        Ok(Data::from_boxed_slice(contents.into_boxed_slice()))
+    }
+
+    /// Reads as [`read_inner`](File::read_inner) does, reporting `before` plus the bytes
+    /// read to `reporter`: as they stream in, where the backend can, and once
+    /// the read completes.
+    async fn read_reporting(
+        &self,
+        buf_size: usize,
+        before: u64,
+        reporter: Option<&Arc<Reporter>>,
+        priority: Priority,
+    ) -> Result<Data, Error> {
+        let Some(reporter) = reporter else {
+            return self.read_inner(buf_size, priority).await;
+        };
+        let streamed = Arc::clone(reporter);
+        self.inner
+            .set_progress(Some(Arc::new(move |read| streamed.report(before + read))));
+        let _clear = ClearProgress(&self.inner);
+        let data = self.read_inner(buf_size, priority).await?;
+        reporter.report(before + data.len() as u64);
+        Ok(data)
+    }
+
+    /// Sets the access and modification times of the file.
+    ///
+    /// Times not set in `times` are left unchanged. On Unix this applies to
+    /// the open handle (`futimens`). On Windows, where a handle opened for
+    /// reading cannot change attributes, the path the file was opened with is
+    /// reopened for the change.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the caller may not change the file's times. On
+    /// WASM, where files are served over HTTP, this always fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, FileTimes, Priority};
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// let file = File::open("cache/index.bin", Priority::unit_test()).await?;
+    /// let yesterday = SystemTime::now() - Duration::from_secs(24 * 60 * 60);
+    /// file.set_times(FileTimes::new().set_modified(yesterday), Priority::unit_test())
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_times(&self, times: FileTimes, priority: Priority) -> Result<(), Error> {
+        let _in_flight = self.queue.turn(priority).await;
+        self.inner
+            .set_times(&self.path, times, priority)
+            .await
+            .map_err(Error)
+    }
+
+    /// Sets the modification time of the file.
+    ///
+    /// This is shorthand for [`set_times`](File::set_times) with only a
+    /// modification time.
+    pub async fn set_modified(
+        &self,
+        modified: SystemTime,
+        priority: Priority,
+    ) -> Result<(), Error> {
+        self.set_times(FileTimes::new().set_modified(modified), priority)
+            .await
+    }
+
+    /// Lists the names of the file's extended attributes.
+    ///
+    /// See [`list_xattrs`] for platform support. On unix this uses the open
+    /// handle, so it keeps working if the file is renamed.
+    pub async fn list_xattrs(&self, priority: Priority) -> Result<Vec<std::ffi::OsString>, Error> {
+        let _in_flight = self.queue.turn(priority).await;
+        self.inner
+            .list_xattrs(&self.path, priority)
+            .await
+            .map_err(Error)
+    }
+
+    /// Reads one of the file's extended attributes, or returns `None` if it is
+    /// not set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, Priority};
+    ///
+    /// let file = File::open("photo.jpg", Priority::unit_test()).await?;
+    /// let hash = file.get_xattr("user.sha256", Priority::unit_test()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_xattr(
+        &self,
+        name: impl AsRef<std::ffi::OsStr>,
+        priority: Priority,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let _in_flight = self.queue.turn(priority).await;
+        self.inner
+            .get_xattr(&self.path, name.as_ref(), priority)
+            .await
+            .map_err(Error)
+    }
+
+    /// Sets one of the file's extended attributes, creating it or replacing
+    /// its value.
+    ///
+    /// See [`set_xattr`] for errors.
+    pub async fn set_xattr(
+        &self,
+        name: impl AsRef<std::ffi::OsStr>,
+        value: impl AsRef<[u8]>,
+        priority: Priority,
+    ) -> Result<(), Error> {
+        let _in_flight = self.queue.turn(priority).await;
+        self.inner
+            .set_xattr(&self.path, name.as_ref(), value.as_ref(), priority)
+            .await
+            .map_err(Error)
+    }
+
+    /// Removes one of the file's extended attributes.
+    ///
+    /// See [`remove_xattr`] for errors.
+    pub async fn remove_xattr(
+        &self,
+        name: impl AsRef<std::ffi::OsStr>,
+        priority: Priority,
+    ) -> Result<(), Error> {
+        let _in_flight = self.queue.turn(priority).await;
+        self.inner
+            .remove_xattr(&self.path, name.as_ref(), priority)
+            .await
+            .map_err(Error)
+    }
+
+    /// Reads the rest of the file as text, for display.
+    ///
+    /// This never fails on the file's contents: invalid UTF-8 is replaced with
+    /// U+FFFD, and UTF-16 files with a byte-order mark are decoded. Use
+    /// [`text_with`](File::text_with) to limit how much is read or to choose
+    /// another fallback for invalid UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, or is too large to fit in
+    /// memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, Priority};
+    ///
+    /// let file = File::open("notes.txt", Priority::unit_test()).await?;
+    /// let text = file.text(Priority::unit_test()).await?;
+    /// println!("{text}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn text(&self, priority: Priority) -> Result<Text, Error> {
+        self.text_with(&TextOptions::default(), priority).await
+    }
+
+    /// Reads the rest of the file as text, with the given options.
+    ///
+    /// With a [`max_len`](TextOptions::max_len), at most that many bytes are
+    /// read, and a character cut in half by the limit is dropped rather than
+    /// treated as invalid.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, or if it contains invalid
+    /// UTF-8 and the fallback is [`TextFallback::Strict`].
+    pub async fn text_with(
+        &self,
+        options: &TextOptions,
+        priority: Priority,
+    ) -> Result<Text, Error> {
+        let _in_flight = self.queue.turn(priority).await;
+        let len = retry::retrying(self.retry.as_deref(), || self.inner.metadata(priority))
+            .await
+            .map(Metadata)?
+            .len();
+        let limit = options.get_max_len().unwrap_or(u64::MAX);
+        let want = in_memory_len(len.min(limit))?;
+        let data = self
+            .read_up_to(want, ReadOptions::DEFAULT_CHUNK_SIZE, None, priority)
+            .await?;
+        text::decode(&data, len > limit, options.get_fallback())
+    }
+}
+
+/// Counts the bytes of a read for [`Hooks`].
+fn read_len(data: &Data) -> u64 {
+    data.len() as u64
+}
+
+/// Called by a backend with the bytes a read has received so far.
+type ProgressHook = Arc<dyn Fn(u64) + Send + Sync>;
+
+/// Removes a per-operation retry policy when the operation finishes or is
+/// cancelled.
+struct ClearRetry<'a>(&'a Mutex<Option<Arc<RetryPolicy>>>);
+
+impl Drop for ClearRetry<'_> {
+    fn drop(&mut self) {
+        *self.0.lock().unwrap() = None;
+    }
+}
+
+/// Removes a backend progress hook when a read finishes or is cancelled.
+struct ClearProgress<'a>(&'a sys::File);
+
+impl Drop for ClearProgress<'_> {
+    fn drop(&mut self) {
+        self.0.set_progress(None);
+    }
+}
+
+/// Moves a file back to where a read of several chunks started, and drops any
+/// bytes buffered since, if the read is cancelled between chunks.
+struct RestorePosition<'a>(Option<(&'a File, u64)>);
+
+impl<'a> RestorePosition<'a> {
+    fn new(file: &'a File) -> Self {
+        let buffered = file.page.lock().unwrap().remaining() as u64;
+        RestorePosition(Some((file, file.pos.load(Ordering::Relaxed) - buffered)))
+    }
+}
+
+impl Drop for RestorePosition<'_> {
+    fn drop(&mut self) {
+        if let Some((file, pos)) = self.0 {
+            file.page.lock().unwrap().clear();
+            file.pos.store(pos, Ordering::Relaxed);
+            file.inner.set_position(pos);
+        }
     }
 }
 
+/// Converts a file length to a buffer length, failing if no buffer that large
+/// can exist on this target.
+fn in_memory_len(len: u64) -> Result<usize, Error> {
+    usize::try_from(len)
+        .ok()
+        .filter(|&len| len <= isize::MAX as usize)
+        .ok_or(Error(sys::Error::TooLarge(len)))
+}
+
 /// Tests if a file or directory exists at the given path.
 ///
 /// This function checks whether a file or directory exists without opening it.
@@ -610,98 +1899,643 @@ pub async fn exists(path: impl AsRef<Path>, priority: Priority) -> bool {
     sys::exists(path, priority).await
 }
 
-/// An error that can occur during file operations.
+/// Renames a file or directory, replacing the destination if it exists.
 ///
-/// This is a wrapper around platform-specific error types. It implements
-/// `std::error::Error` and provides automatic conversion from the underlying
-/// platform errors.
+/// This behaves like `std::fs::rename`, executed on the worker pool. When
+/// `from` and `to` are on the same filesystem the rename is atomic, which makes
+/// it the final step of the write-then-rename pattern for replacing a file.
 ///
-/// # Error Sources
+/// # Arguments
 ///
-/// Errors can originate from:
-/// - File not found
-/// - Permission denied
-/// - I/O errors during read/write/seek operations
-/// - Invalid file paths
-/// - File system errors (disk full, etc.)
+/// * `from` - The current path
+/// * `to` - The new path
+/// * `priority` - The priority for this operation
 ///
-/// # Platform Differences
+/// # Errors
 ///
-/// - **Standard platforms**: Wraps `std::io::Error`
-/// - **WASM**: Wraps fetch API errors
+/// Returns an error if `from` does not exist, the paths are on different
+/// filesystems, or permissions are insufficient. On WASM, where files are served
+/// over HTTP, this always fails.
 ///
-/// # Error Handling
+/// # Examples
 ///
-/// The error type implements `Display` and `Debug` for convenient error reporting.
-/// It also implements `std::error::Error` for compatibility with error handling libraries.
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{rename, Priority};
 ///
-/// # Examples
+/// // Publish a fully written file in one step
+/// rename("config.json.tmp", "config.json", Priority::unit_test()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn rename(
+    from: impl AsRef<Path>,
+    to: impl AsRef<Path>,
+    priority: Priority,
+) -> Result<(), Error> {
+    sys::rename(from, to, priority).await.map_err(Error)
+}
+
+/// Creates a hard link: a new name `link` for the existing file `original`.
 ///
-/// ## Basic Error Handling
+/// This behaves like `std::fs::hard_link`, executed on the worker pool. Both
+/// names refer to the same file afterwards; removing one leaves the other.
+///
+/// # Arguments
+///
+/// * `original` - An existing file
+/// * `link` - The new name, which must not exist
+/// * `priority` - The priority for this operation
+///
+/// # Errors
+///
+/// Returns an error if `original` does not exist, `link` exists, the paths are
+/// on different filesystems, or the filesystem does not support hard links.
+/// On WASM, where files are served over HTTP, this always fails.
+///
+/// # Examples
 ///
 /// ```
-/// # async fn example() {
-/// use async_file::{File, Priority};
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{hard_link, Priority};
 ///
-/// match File::open("nonexistent.txt", Priority::unit_test()).await {
-///     Ok(file) => println!("File opened successfully"),
-///     Err(e) => eprintln!("Failed to open file: {}", e),
-/// }
+/// // Install a package from the shared store without copying it
+/// hard_link("store/pkg-1.0/lib.so", "env/lib/lib.so", Priority::unit_test()).await?;
+/// # Ok(())
 /// # }
 /// ```
+pub async fn hard_link(
+    original: impl AsRef<Path>,
+    link: impl AsRef<Path>,
+    priority: Priority,
+) -> Result<(), Error> {
+    sys::hard_link(original, link, priority)
+        .await
+        .map_err(Error)
+}
+
+/// Creates a symbolic link at `link` pointing to `original`.
 ///
-/// ## Using the ? Operator
+/// This behaves like `std::os::unix::fs::symlink`, executed on the blocking
+/// pool. `original` is stored as given; a relative target is resolved relative
+/// to the directory containing `link`, and need not exist.
+///
+/// Windows distinguishes links to files from links to directories; see
+/// `symlink_file` and `symlink_dir` there.
+///
+/// # Errors
+///
+/// Returns an error if `link` already exists or permissions are insufficient.
+///
+/// # Examples
 ///
 /// ```
 /// # async fn example() -> Result<(), async_file::Error> {
-/// use async_file::{File, Priority};
-///
-/// // The ? operator works with async_file::Error
-/// let file = File::open("data.txt", Priority::unit_test()).await?;
-/// let contents = file.read_all(Priority::unit_test()).await?;
+/// use async_file::{symlink, Priority};
 ///
-/// println!("Read {} bytes", contents.len());
+/// symlink("pkg-1.0", "store/pkg-current", Priority::unit_test()).await?;
 /// # Ok(())
 /// # }
 /// ```
+#[cfg(unix)]
+pub async fn symlink(
+    original: impl AsRef<Path>,
+    link: impl AsRef<Path>,
+    priority: Priority,
+) -> Result<(), Error> {
+    sys::symlink(original, link, priority).await.map_err(Error)
+}
+
+/// Creates a symbolic link to a file at `link` pointing to `original`.
 ///
-/// ## Converting to `Box<dyn Error>`
+/// This behaves like `std::os::windows::fs::symlink_file`, executed on the
+/// worker pool. Creating symlinks on Windows requires either administrator
+/// rights or Developer Mode.
 ///
-/// ```
-/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
-/// use async_file::{File, Priority};
+/// # Errors
 ///
-/// // async_file::Error can be converted to Box<dyn Error>
-/// let file = File::open("config.json", Priority::highest_async()).await?;
-/// let data = file.read_all(Priority::highest_async()).await?;
+/// Returns an error if `link` already exists or the process may not create
+/// symlinks.
+#[cfg(windows)]
+pub async fn symlink_file(
+    original: impl AsRef<Path>,
+    link: impl AsRef<Path>,
+    priority: Priority,
+) -> Result<(), Error> {
+    sys::symlink_file(original, link, priority)
+        .await
+        .map_err(Error)
+}
+
+/// Creates a symbolic link to a directory at `link` pointing to `original`.
+///
+/// This behaves like `std::os::windows::fs::symlink_dir`, executed on the
+/// worker pool. Creating symlinks on Windows requires either administrator
+/// rights or Developer Mode.
+///
+/// # Errors
 ///
+/// Returns an error if `link` already exists or the process may not create
+/// symlinks.
+#[cfg(windows)]
+pub async fn symlink_dir(
+    original: impl AsRef<Path>,
+    link: impl AsRef<Path>,
+    priority: Priority,
+) -> Result<(), Error> {
+    sys::symlink_dir(original, link, priority)
+        .await
+        .map_err(Error)
+}
+
+/// Reads the target of a symlink.
+///
+/// This behaves like `std::fs::read_link`, executed on the worker pool. The
+/// target is returned as stored in the link, so it may be relative.
+///
+/// # Arguments
+///
+/// * `path` - The symlink to read
+/// * `priority` - The priority for this operation
+///
+/// # Errors
+///
+/// Returns an error if `path` does not exist or is not a symlink. On WASM,
+/// where files are served over HTTP, this always fails.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{read_link, Priority};
+///
+/// let target = read_link("store/pkg-current", Priority::unit_test()).await?;
+/// println!("current version: {}", target.display());
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Debug, thiserror::Error)]
-#[error("afile error {0}")]
-pub struct Error(#[from] sys::Error);
+pub async fn read_link(path: impl AsRef<Path>, priority: Priority) -> Result<PathBuf, Error> {
+    sys::read_link(path, priority).await.map_err(Error)
+}
 
-/// Metadata information about a file.
+/// Retrieves metadata for a path, following symlinks.
 ///
-/// This structure contains file metadata such as size. It's returned by
-/// the `File::metadata` method.
+/// This behaves like `std::fs::metadata`, executed on the worker pool. No
+/// file handle is opened, so it is cheaper than [`File::open`] followed by
+/// [`File::metadata`] when only the metadata is needed. On WASM it issues a
+/// single HTTP HEAD request.
+///
+/// # Arguments
+///
+/// * `path` - The file or directory to query
+/// * `priority` - The priority for this operation
+///
+/// # Errors
+///
+/// Returns an error if `path` does not exist or cannot be queried.
 ///
 /// # Examples
 ///
 /// ```
 /// # async fn example() -> Result<(), async_file::Error> {
-/// use async_file::{File, Priority};
+/// use async_file::{metadata, Priority};
 ///
-/// let file = File::open("/dev/zero", Priority::unit_test()).await?;
-/// let metadata = file.metadata(Priority::unit_test()).await?;
+/// let len = metadata("assets/intro.mp4", Priority::unit_test()).await?.len();
+/// println!("{len} bytes to download");
+/// # Ok(())
+/// # }
+/// ```
+pub async fn metadata(path: impl AsRef<Path>, priority: Priority) -> Result<Metadata, Error> {
+    sys::metadata(path, priority)
+        .await
+        .map(Metadata)
+        .map_err(Error)
+}
+
+/// Retrieves metadata for a path without following symlinks.
 ///
-/// println!("File size: {} bytes", metadata.len());
+/// This behaves like `std::fs::symlink_metadata`, executed on the blocking
+/// pool. If `path` is a symlink, the metadata describes the link itself and
+/// [`Metadata::is_symlink`] returns `true`; otherwise it describes the file.
+///
+/// On WASM, where HTTP has no symlinks, this returns the metadata reported for
+/// the URL.
+///
+/// # Errors
+///
+/// Returns an error if `path` does not exist or cannot be queried.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{symlink_metadata, Priority};
+///
+/// let metadata = symlink_metadata("store/pkg-current", Priority::unit_test()).await?;
+/// assert!(metadata.is_symlink());
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Debug, Clone)]
-pub struct Metadata(sys::Metadata);
+pub async fn symlink_metadata(
+    path: impl AsRef<Path>,
+    priority: Priority,
+) -> Result<Metadata, Error> {
+    sys::symlink_metadata(path, priority)
+        .await
+        .map(Metadata)
+        .map_err(Error)
+}
+
+/// Returns the canonical, absolute form of a path, with all intermediate
+/// components normalized and symlinks resolved.
+///
+/// This behaves like `std::fs::canonicalize`, executed on the worker pool, so
+/// resolving paths on a slow network filesystem doesn't stall the executor.
+/// Canonical paths make good deduplication keys: two paths that lead to the
+/// same file canonicalize to the same result.
+///
+/// On Windows the result uses the extended-length `\\?\` form.
+///
+/// # Arguments
+///
+/// * `path` - The path to resolve
+/// * `priority` - The priority for this operation
+///
+/// # Errors
+///
+/// Returns an error if `path` does not exist or a component cannot be
+/// resolved. On WASM, where files are served over HTTP, this always fails.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{canonicalize, Priority};
+///
+/// let key = canonicalize("./assets/../assets/logo.png", Priority::unit_test()).await?;
+/// println!("cache key: {}", key.display());
+/// # Ok(())
+/// # }
+/// ```
+pub async fn canonicalize(path: impl AsRef<Path>, priority: Priority) -> Result<PathBuf, Error> {
+    sys::canonicalize(path, priority).await.map_err(Error)
+}
+
+/// Changes the permissions of a file or directory.
+///
+/// This behaves like `std::fs::set_permissions`, executed on the worker pool.
+/// Symlinks are followed. Start from the current [`Metadata::permissions`] to
+/// change one attribute while keeping the rest.
+///
+/// # Arguments
+///
+/// * `path` - The file or directory to change
+/// * `permissions` - The permissions to apply
+/// * `priority` - The priority for this operation
+///
+/// # Errors
+///
+/// Returns an error if `path` does not exist or the caller may not change its
+/// permissions. On WASM, where files are served over HTTP, this always fails.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{set_permissions, symlink_metadata, Priority};
+///
+/// let mut permissions = symlink_metadata("out/report.pdf", Priority::unit_test())
+///     .await?
+///     .permissions();
+/// permissions.set_readonly(true);
+/// set_permissions("out/report.pdf", permissions, Priority::unit_test()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn set_permissions(
+    path: impl AsRef<Path>,
+    permissions: Permissions,
+    priority: Priority,
+) -> Result<(), Error> {
+    sys::set_permissions(path, permissions.0, priority)
+        .await
+        .map_err(Error)
+}
+
+/// Removes a file.
+///
+/// This behaves like `std::fs::remove_file`, executed on the worker pool.
+/// Directories are not removed; use [`remove_dir`] for those.
+///
+/// # Arguments
+///
+/// * `path` - The file to remove
+/// * `priority` - The priority for this operation
+///
+/// # Errors
+///
+/// Returns an error if `path` does not exist, is a directory, or permissions
+/// are insufficient. On WASM, where files are served over HTTP, this always fails.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{remove_file, Priority};
+///
+/// // Clean up a scratch file once it's no longer needed
+/// remove_file("render.tmp", Priority::unit_test()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn remove_file(path: impl AsRef<Path>, priority: Priority) -> Result<(), Error> {
+    sys::remove_file(path, priority).await.map_err(Error)
+}
+
+/// Removes an empty directory.
+///
+/// This behaves like `std::fs::remove_dir`, executed on the worker pool.
+///
+/// # Arguments
+///
+/// * `path` - The directory to remove
+/// * `priority` - The priority for this operation
+///
+/// # Errors
+///
+/// Returns an error if `path` does not exist, is not a directory, is not
+/// empty, or permissions are insufficient. On WASM this always fails.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{remove_dir, remove_file, Priority};
+///
+/// remove_file("scratch/output.bin", Priority::unit_test()).await?;
+/// remove_dir("scratch", Priority::unit_test()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn remove_dir(path: impl AsRef<Path>, priority: Priority) -> Result<(), Error> {
+    sys::remove_dir(path, priority).await.map_err(Error)
+}
+
+/// The size of each chunk read and written by [`copy`].
+const COPY_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Copies the contents of one file to another.
+///
+/// Unlike `std::fs::copy`, the data is streamed in chunks through the crate's
+/// own read and write operations, so every chunk is issued at `priority` and
+/// the copy can be cancelled between chunks by dropping the future.
+///
+/// The destination is created if it does not exist and truncated if it does.
+/// Only the contents are copied; permissions and timestamps are not.
+///
+/// # Arguments
+///
+/// * `from` - The path of the file to copy
+/// * `to` - The path to copy to
+/// * `priority` - The priority for every read and write
+///
+/// # Returns
+///
+/// The number of bytes copied.
+///
+/// # Errors
+///
+/// Returns an error if the source cannot be read or the destination cannot be
+/// written. On WASM, where files are served over HTTP, this always fails.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{copy, Priority};
+///
+/// let bytes = copy("assets/logo.png", "install/logo.png", Priority::unit_test()).await?;
+/// println!("Copied {bytes} bytes");
+/// # Ok(())
+/// # }
+/// ```
+pub async fn copy(
+    from: impl AsRef<Path>,
+    to: impl AsRef<Path>,
+    priority: Priority,
+) -> Result<u64, Error> {
+    let source = File::open(from, priority).await?;
+    let dest = sys::WriteFile::create(to, priority).await?;
+    let mut copied = 0;
+    loop {
+        let chunk = source.read(COPY_CHUNK_SIZE, priority).await?;
+        if chunk.is_empty() {
+            return Ok(copied);
+        }
+        let len = chunk.len() as u64;
+        copied += len;
+        throttle::admit(priority).await;
+        dest.write_all(chunk.into_sys(), priority).await?;
+        throttle::charge(priority, len);
+    }
+}
+
+/// An error that can occur during file operations.
+///
+/// This is a wrapper around platform-specific error types. It implements
+/// `std::error::Error` and provides automatic conversion from the underlying
+/// platform errors.
+///
+/// # Error Sources
+///
+/// Errors can originate from:
+/// - File not found
+/// - Permission denied
+/// - I/O errors during read/write/seek operations
+/// - Invalid file paths
+/// - File system errors (disk full, etc.)
+///
+/// # Platform Differences
+///
+/// - **Standard platforms**: Wraps `std::io::Error`
+/// - **WASM**: Wraps fetch API errors
+///
+/// # Error Handling
+///
+/// The error type implements `Display` and `Debug` for convenient error reporting.
+/// It also implements `std::error::Error` for compatibility with error handling libraries.
+///
+/// # Examples
+///
+/// ## Basic Error Handling
+///
+/// ```
+/// # async fn example() {
+/// use async_file::{File, Priority};
+///
+/// match File::open("nonexistent.txt", Priority::unit_test()).await {
+///     Ok(file) => println!("File opened successfully"),
+///     Err(e) => eprintln!("Failed to open file: {}", e),
+/// }
+/// # }
+/// ```
+///
+/// ## Using the ? Operator
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{File, Priority};
+///
+/// // The ? operator works with async_file::Error
+/// let file = File::open("data.txt", Priority::unit_test()).await?;
+/// let contents = file.read_all(Priority::unit_test()).await?;
+///
+/// println!("Read {} bytes", contents.len());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ## Converting to `Box<dyn Error>`
+///
+/// ```
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use async_file::{File, Priority};
+///
+/// // async_file::Error can be converted to Box<dyn Error>
+/// let file = File::open("config.json", Priority::highest_async()).await?;
+/// let data = file.read_all(Priority::highest_async()).await?;
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, thiserror::Error)]
+#[error("afile error {0}")]
+pub struct Error(#[from] sys::Error);
+
+impl Error {
+    /// Whether the operation timed out.
+    ///
+    /// This is the error reported when an operation outlasts
+    /// [`TimeoutExt::timeout`], and on WASM when requests outlast the
+    /// [`FetchOptions::timeout`]. On native platforms it is an I/O error of
+    /// kind [`TimedOut`](std::io::ErrorKind::TimedOut).
+    pub fn is_timed_out(&self) -> bool {
+        self.0.is_timed_out()
+    }
+
+    /// Whether the file changed between two reads of it.
+    ///
+    /// On WASM, reads of a file served over HTTP check that the server still
+    /// has the version first read (by its ETag or Last-Modified date), and
+    /// fail this way rather than return bytes from two versions. Reopen the
+    /// file to read the new one. On native platforms this is always false.
+    pub fn is_changed(&self) -> bool {
+        self.0.is_changed()
+    }
+
+    /// Classifies the error, for deciding how to handle it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{ErrorKind, File, Priority};
+    ///
+    /// match File::open("settings.toml", Priority::unit_test()).await {
+    ///     Ok(file) => { /* read the settings */ }
+    ///     Err(e) if e.kind() == ErrorKind::NotFound => { /* use the defaults */ }
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn kind(&self) -> ErrorKind {
+        self.0.kind()
+    }
+}
+
+/// Converts to an I/O error, so that this crate's errors can be returned from
+/// functions and trait methods that report `std::io::Result`.
+///
+/// On native platforms an error that came from the operating system converts
+/// back to that `std::io::Error`, with its kind and OS error code. Other
+/// errors become an `std::io::Error` of the closest kind, such as
+/// [`InvalidData`](std::io::ErrorKind::InvalidData) for invalid UTF-8 or
+/// [`NotFound`](std::io::ErrorKind::NotFound) for a 404 on WASM, wrapping the
+/// original [`Error`], which [`get_ref`](std::io::Error::get_ref) and
+/// [`into_inner`](std::io::Error::into_inner) return.
+///
+/// # Examples
+///
+/// ```
+/// use async_file::{File, Priority};
+///
+/// async fn header(path: &str) -> std::io::Result<Vec<u8>> {
+///     let file = File::open(path, Priority::unit_test()).await?;
+///     Ok(file.read(16, Priority::unit_test()).await?.to_vec())
+/// }
+/// ```
+/// Wraps the file as [`File::from_std`] does.
+#[cfg(not(target_arch = "wasm32"))]
+impl From<std::fs::File> for File {
+    fn from(file: std::fs::File) -> Self {
+        File::from_std(file)
+    }
+}
+
+impl From<Error> for std::io::Error {
+    fn from(error: Error) -> Self {
+        match error.0.into_io() {
+            Ok(io) => io,
+            Err(other) => std::io::Error::new(other.closest_io_kind(), Error(other)),
+        }
+    }
+}
+
+/// The broad category of an [`Error`], returned by [`Error::kind`].
+///
+/// On native platforms the kind comes from the
+/// [`std::io::ErrorKind`] of the underlying I/O error. On WASM it comes from
+/// the response: 404 and 410 are [`NotFound`](ErrorKind::NotFound), 401 and
+/// 403 are [`PermissionDenied`](ErrorKind::PermissionDenied), and other
+/// failing statuses are [`HttpStatus`](ErrorKind::HttpStatus).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The file or directory does not exist.
+    NotFound,
+    /// The caller may not access the file.
+    PermissionDenied,
+    /// The operation did not finish in time; see [`Error::is_timed_out`].
+    TimedOut,
+    /// The operation was interrupted, and may succeed if tried again.
+    Interrupted,
+    /// The server answered a WASM request with this failing status.
+    HttpStatus(u16),
+    /// Any other error.
+    Other,
+}
+
+/// Metadata information about a file.
+///
+/// This structure contains file metadata such as size. It's returned by
+/// the `File::metadata` method.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{File, Priority};
+///
+/// let file = File::open("/dev/zero", Priority::unit_test()).await?;
+/// let metadata = file.metadata(Priority::unit_test()).await?;
+///
+/// println!("File size: {} bytes", metadata.len());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Metadata(sys::Metadata);
 impl Metadata {
     /// Returns the size of the file in bytes.
     ///
@@ -745,16 +2579,207 @@ impl Metadata {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
-}
-
-logwise::declare_logging_domain!();
 
-/*
-Boilerplate section, for types in order of appearance in the main section.
- */
+    /// Returns the time the file was last modified.
+    ///
+    /// On WASM this comes from the `Last-Modified` response header.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the platform or filesystem does not record
+    /// modification times, or, on WASM, if the server sent no `Last-Modified`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, Priority};
+    ///
+    /// let file = File::open("assets/logo.png", Priority::unit_test()).await?;
+    /// let modified = file.metadata(Priority::unit_test()).await?.modified()?;
+    /// // rebuild derived files older than `modified`
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn modified(&self) -> Result<SystemTime, Error> {
+        self.0.modified().map_err(Error)
+    }
 
-/*
-File
+    /// Returns the time the file was last accessed.
+    ///
+    /// Many filesystems update access times lazily or not at all, so treat this
+    /// as approximate.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the platform or filesystem does not record access
+    /// times. Always fails on WASM, where HTTP does not report them.
+    pub fn accessed(&self) -> Result<SystemTime, Error> {
+        self.0.accessed().map_err(Error)
+    }
+
+    /// Returns the time the file was created.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the platform or filesystem does not record creation
+    /// times, which includes some Linux filesystems. Always fails on WASM, where
+    /// HTTP does not report them.
+    pub fn created(&self) -> Result<SystemTime, Error> {
+        self.0.created().map_err(Error)
+    }
+
+    /// Returns `true` if this metadata describes a symlink itself.
+    ///
+    /// Only metadata from [`symlink_metadata`] can describe a symlink; other
+    /// operations follow links to their targets. On Windows, junctions are
+    /// also reported as symlinks. On WASM this is always `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{symlink_metadata, Priority};
+    ///
+    /// let metadata = symlink_metadata("current", Priority::unit_test()).await?;
+    /// if metadata.is_symlink() {
+    ///     println!("current is a link");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_symlink(&self) -> bool {
+        self.0.file_type() == FileType::Symlink
+    }
+
+    /// Returns the type of the file this metadata describes.
+    ///
+    /// On WASM, everything served over HTTP is a [`FileType::File`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, FileType, Priority};
+    ///
+    /// let file = File::open("assets", Priority::unit_test()).await?;
+    /// match file.metadata(Priority::unit_test()).await?.file_type() {
+    ///     FileType::Dir => println!("that's a directory, not an asset"),
+    ///     _ => {}
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn file_type(&self) -> FileType {
+        self.0.file_type()
+    }
+
+    /// Returns the permissions of the file.
+    ///
+    /// On WASM, files are always reported read-only.
+    pub fn permissions(&self) -> Permissions {
+        Permissions(self.0.permissions())
+    }
+
+    /// Returns `true` if this metadata describes a regular file.
+    pub fn is_file(&self) -> bool {
+        self.file_type() == FileType::File
+    }
+
+    /// Returns `true` if this metadata describes a directory.
+    pub fn is_dir(&self) -> bool {
+        self.file_type() == FileType::Dir
+    }
+}
+
+/// The permissions of a file, as reported by [`Metadata::permissions`] and
+/// applied by [`set_permissions`].
+///
+/// Every platform has a read-only flag. On Unix, the full mode bits are
+/// available too.
+///
+/// # Examples
+///
+/// Marking a generated script executable:
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{set_permissions, symlink_metadata, Priority};
+///
+/// let mut permissions = symlink_metadata("build/run.sh", Priority::unit_test())
+///     .await?
+///     .permissions();
+/// #[cfg(unix)]
+/// permissions.set_mode(permissions.mode() | 0o111);
+/// set_permissions("build/run.sh", permissions, Priority::unit_test()).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Permissions(sys::Permissions);
+
+impl Permissions {
+    /// Returns `true` if the file cannot be written.
+    ///
+    /// On Unix this means no write bit is set for anyone, which is not the
+    /// same as "the current user cannot write it".
+    pub fn readonly(&self) -> bool {
+        self.0.readonly()
+    }
+
+    /// Sets or clears the read-only flag.
+    ///
+    /// On Unix, clearing it makes the file writable by everyone. Prefer
+    /// [`set_mode`](Permissions::set_mode) there for anything finer.
+    ///
+    /// This only changes this value; apply it with [`set_permissions`].
+    pub fn set_readonly(&mut self, readonly: bool) {
+        self.0.set_readonly(readonly)
+    }
+
+    /// Returns the Unix mode bits, such as `0o755`.
+    #[cfg(unix)]
+    pub fn mode(&self) -> u32 {
+        self.0.mode()
+    }
+
+    /// Sets the Unix mode bits.
+    ///
+    /// This only changes this value; apply it with [`set_permissions`].
+    #[cfg(unix)]
+    pub fn set_mode(&mut self, mode: u32) {
+        self.0.set_mode(mode)
+    }
+
+    /// Creates permissions from Unix mode bits.
+    #[cfg(unix)]
+    pub fn from_mode(mode: u32) -> Self {
+        Permissions(sys::Permissions::from_mode(mode))
+    }
+}
+
+/// The type of a file, as reported by [`Metadata::file_type`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FileType {
+    /// A regular file.
+    File,
+    /// A directory.
+    Dir,
+    /// A symlink, or on Windows a junction. Only [`symlink_metadata`] reports these.
+    Symlink,
+    /// Anything else, such as a device, FIFO or socket.
+    Other,
+}
+
+logwise::declare_logging_domain!();
+
+/*
+Boilerplate section, for types in order of appearance in the main section.
+ */
+
+/*
+File
 
 Clone: Not implemented. The underlying std::fs::File doesn't support Clone as it
 represents an exclusive handle to an open file descriptor.
@@ -766,188 +2791,1992 @@ doesn't make semantic sense.
 
 Hash: Not implemented without Eq.
 
-Default: Not implemented. There's no meaningful default file.
+Default: Not implemented. There's no meaningful default file.
+
+Display: Not implemented. File handles are not typically displayed to users.
+
+AsRef/AsMut: Not implemented. We want to control the API surface and not expose
+the underlying file handle directly.
+
+Send/Sync: Automatically derived and safe. Files can be sent between threads
+and accessed from multiple threads (though our API enforces single operation
+at a time).
+
+Unpin: Automatically derived and safe since there are no self-references.
+ */
+
+/*
+Data
+
+Clone: Implemented. The buffer is refcounted internally, so a clone is a second
+handle to the same bytes, as cheap as cloning an Arc. This lets one read be fanned
+out to several consumers without wrapping it in Arc<Data>.
+
+Copy: Not implemented. Data represents potentially large buffers that shouldn't
+be copied implicitly.
+
+PartialEq/Eq: Implemented. Comparison via the underlying byte slice is meaningful
+and useful for testing and validation.
+
+Ord: Not implemented. Lexicographic ordering of byte data rarely makes sense
+in file I/O contexts.
+
+Hash: Implemented. Hashing byte data is useful for caching and deduplication
+scenarios.
+
+Default: Not implemented. There's no meaningful default data buffer.
+
+Display: Not implemented. Binary data is not typically displayed as text.
+
+Send/Sync: Automatically derived and safe since the data is immutable.
+
+Unpin: Safe to unpin even if there are internal pointers, as the data is
+immutable after creation.
+ */
+
+impl PartialEq for Data {
+    fn eq(&self, other: &Self) -> bool {
+        self[..] == other[..]
+    }
+}
+impl Eq for Data {}
+
+impl Hash for Data {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self[..].hash(state)
+    }
+}
+
+/*
+Metadata
+
+Clone: Implemented via derive. std::fs::Metadata supports Clone and it makes sense
+for metadata to be cloneable as it's just informational data that users may want
+to store or pass around.
+
+Copy: Not implemented. std::fs::Metadata doesn't support Copy as it's not a trivial
+type - it contains platform-specific metadata that may include complex structures.
+
+PartialEq/Eq: Not implemented. std::fs::Metadata doesn't support equality comparison,
+likely because metadata can include timestamps and other volatile information that
+makes equality semantics unclear.
+
+Hash: Not implemented. std::fs::Metadata doesn't support hashing, and without Eq
+it wouldn't make sense anyway.
+
+Default: Not implemented. There's no meaningful default metadata - metadata must
+come from an actual file.
+
+Display: Not implemented. Metadata is not typically formatted for end-user display.
+
+From/Into: Not obvious conversions exist. We don't want users converting between
+our metadata and std metadata directly.
+
+AsRef/AsMut: Not implemented. We want to control the API surface and not expose
+the underlying std::fs::Metadata directly.
+
+Send/Sync: Automatically derived since std::fs::Metadata is Send + Sync.
+Unpin: Automatically derived and safe since there are no self-references.
+ */
+
+/*
+Permissions
+
+Clone/PartialEq/Eq: Implemented via derive, as std::fs::Permissions supports them.
+
+Copy/Hash/Default: Not implemented, matching std::fs::Permissions.
+
+Display: Not implemented. Use mode() on Unix for a printable form.
+
+Send/Sync/Unpin: Automatically derived.
+ */
+
+/*
+FileType
+
+Clone/Copy: Implemented via derive. A fieldless enum is trivially copyable.
+
+PartialEq/Eq/Hash: Implemented via derive, so callers can match, compare and
+group by type.
+
+Default: Not implemented. No file type is a sensible default.
+
+Display: Not implemented. Debug output is enough for diagnostics.
+
+Send/Sync/Unpin: Automatically derived.
+ */
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        BufFile, Data, Error, File, Metadata, Priority, PriorityScope, set_default_origin,
+    };
+    use std::task::Poll;
+
+    /// Where the WASM tests fetch from, other than the original open, read,
+    /// seek, length and exists tests, which always use thinkbroadband.com. Set
+    /// `ASYNC_FILE_TEST_ORIGIN` at build time to test against a local
+    /// `devserver` instead.
+    const TEST_ORIGIN: &str = match option_env!("ASYNC_FILE_TEST_ORIGIN") {
+        Some(origin) => origin,
+        None => "http://ipv4.download.thinkbroadband.com/",
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    const TEST_FILE: &str = "5MB.zip";
+    #[cfg(not(target_arch = "wasm32"))]
+    const TEST_FILE: &str = "/dev/zero";
+
+    #[cfg(target_arch = "wasm32")]
+    const SEEK_FILE: &str = TEST_FILE;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    const SEEK_FILE: &str = "/etc/services";
+
+    /// A scratch path, removed with everything under it when dropped.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) struct TempPath(std::path::PathBuf);
+
+    #[cfg(not(target_arch = "wasm32"))]
+    impl std::ops::Deref for TempPath {
+        type Target = std::path::PathBuf;
+
+        fn deref(&self) -> &std::path::PathBuf {
+            &self.0
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    impl AsRef<std::path::Path> for TempPath {
+        fn as_ref(&self) -> &std::path::Path {
+            &self.0
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    impl Drop for TempPath {
+        fn drop(&mut self) {
+            // Not followed: a symlink is removed, not what it points to.
+            match std::fs::symlink_metadata(&self.0) {
+                Ok(metadata) if metadata.is_dir() => {
+                    let _ = std::fs::remove_dir_all(&self.0);
+                }
+                Ok(_) => {
+                    let _ = std::fs::remove_file(&self.0);
+                }
+                Err(_) => {}
+            }
+        }
+    }
+
+    /// Returns a fresh path in the system temp directory, unique to this
+    /// process and `name`, that is removed when the guard drops.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn temp_path(name: &str) -> TempPath {
+        let path =
+            std::env::temp_dir().join(format!("async_file_tests_{}_{name}", std::process::id()));
+        let path = TempPath(path);
+        // Left over from a run that was killed before it could clean up.
+        drop(TempPath(path.to_path_buf()));
+        path
+    }
+
+    /// Polls two futures concurrently until both complete.
+    async fn join<A: Future, B: Future>(a: A, b: B) -> (A::Output, B::Output) {
+        let (mut a, mut b) = (Box::pin(a), Box::pin(b));
+        let (mut out_a, mut out_b) = (None, None);
+        std::future::poll_fn(|cx| {
+            if out_a.is_none() {
+                if let Poll::Ready(v) = a.as_mut().poll(cx) {
+                    out_a = Some(v);
+                }
+            }
+            if out_b.is_none() {
+                if let Poll::Ready(v) = b.as_mut().poll(cx) {
+                    out_b = Some(v);
+                }
+            }
+            if out_a.is_some() && out_b.is_some() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+        (out_a.unwrap(), out_b.unwrap())
+    }
+
+    #[test_executors::async_test]
+    async fn test_open_file() {
+        logwise::context::Context::reset("test_open_file".to_string());
+        set_default_origin("http://ipv4.download.thinkbroadband.com/");
+        let _file = File::open(TEST_FILE, Priority::unit_test()).await.unwrap();
+    }
+    #[test_executors::async_test]
+    #[allow(clippy::bool_assert_comparison)]
+    async fn test_read_file() {
+        logwise::context::Context::reset("test_read_file".to_string());
+        set_default_origin("http://ipv4.download.thinkbroadband.com/");
+        let file = File::open(TEST_FILE, Priority::unit_test()).await.unwrap();
+        let buf = file.read(1024, Priority::unit_test()).await.unwrap();
+        assert_eq!(buf.len(), 1024);
+        #[cfg(not(target_arch = "wasm32"))]
+        assert_eq!(buf.iter().all(|&x| x == 0), true);
+        #[cfg(target_arch = "wasm32")]
+        assert!(
+            buf.starts_with(&[121, 153, 245, 9, 197, 194]),
+            "Expected output, got: {:?}",
+            buf
+        );
+    }
+
+    #[test_executors::async_test]
+    async fn test_seek_file() {
+        logwise::context::Context::reset("test_seek_file".to_string());
+        set_default_origin("http://ipv4.download.thinkbroadband.com/");
+
+        //tough to seek /dev/zero on linux for some reason
+        let mut file = File::open(SEEK_FILE, Priority::unit_test()).await.unwrap();
+        let pos = file
+            .seek(std::io::SeekFrom::Start(1024), Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(pos, 1024);
+        let buf = file.read(1024, Priority::unit_test()).await.unwrap();
+        assert_eq!(buf.len(), 1024);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_send_sync() {
+        fn _assert_send_sync<T: Send + Sync>() {}
+        _assert_send_sync::<Data>();
+        _assert_send_sync::<File>();
+        _assert_send_sync::<crate::Exclusive<'static>>();
+        _assert_send_sync::<Metadata>();
+        _assert_send_sync::<Error>();
+        _assert_send_sync::<BufFile>();
+        _assert_send_sync::<PriorityScope>();
+        _assert_send_sync::<crate::BulkResult<Data>>();
+        _assert_send_sync::<crate::CopyDirOptions>();
+        _assert_send_sync::<crate::RemoveDirOptions>();
+        _assert_send_sync::<crate::Hooks>();
+        _assert_send_sync::<crate::Operation>();
+        _assert_send_sync::<crate::SchedulerStats>();
+        _assert_send_sync::<crate::WalkOptions>();
+        _assert_send_sync::<crate::FsCapabilities>();
+        _assert_send_sync::<crate::FsStats>();
+        _assert_send_sync::<crate::OpenOptions>();
+        _assert_send_sync::<crate::FetchOptions>();
+        _assert_send_sync::<crate::FetchMode>();
+        _assert_send_sync::<crate::Advice>();
+        _assert_send_sync::<crate::DirSizeOptions>();
+        _assert_send_sync::<crate::CacheDir>();
+        _assert_send_sync::<crate::FileType>();
+        _assert_send_sync::<crate::Permissions>();
+        _assert_send_sync::<crate::Text>();
+        _assert_send_sync::<crate::TextOptions>();
+        _assert_send_sync::<crate::FileTimes>();
+        _assert_send_sync::<crate::ErrorKind>();
+        _assert_send_sync::<crate::PathStore>();
+        _assert_send_sync::<crate::Config>();
+        _assert_send_sync::<crate::OperationHandle>();
+        #[cfg(not(target_arch = "wasm32"))]
+        _assert_send_sync::<crate::BlockingReader>();
+        fn _assert_send<T: Send>() {}
+        _assert_send::<crate::Timeout<std::future::Ready<Result<(), Error>>>>();
+        _assert_send::<crate::WithPriority<std::future::Ready<()>>>();
+        _assert_send::<crate::WithHandle<std::future::Ready<()>>>();
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_unpin() {
+        fn _assert_unpin<T: Unpin>() {}
+        _assert_unpin::<Data>();
+        _assert_unpin::<File>();
+        _assert_unpin::<Metadata>();
+        _assert_unpin::<Error>();
+        _assert_unpin::<BufFile>();
+        _assert_unpin::<PriorityScope>();
+        _assert_unpin::<crate::BulkResult<Data>>();
+        _assert_unpin::<crate::CacheDir>();
+    }
+
+    #[test_executors::async_test]
+    async fn test_length() {
+        set_default_origin("http://ipv4.download.thinkbroadband.com/");
+
+        logwise::context::Context::reset("test_length".to_string());
+        let file = File::open(TEST_FILE, Priority::unit_test()).await.unwrap();
+        let metadata = file.metadata(Priority::unit_test()).await.unwrap();
+        #[cfg(target_arch = "wasm32")]
+        assert_eq!(metadata.len(), 5242880);
+        #[cfg(not(target_arch = "wasm32"))]
+        assert_eq!(metadata.len(), 0);
+    }
+
+    #[test_executors::async_test]
+    #[allow(clippy::bool_assert_comparison)]
+    async fn test_exists() {
+        logwise::context::Context::reset("test_exists".to_string());
+        set_default_origin("http://ipv4.download.thinkbroadband.com/");
+        assert_eq!(crate::exists(TEST_FILE, Priority::unit_test()).await, true);
+        assert_eq!(
+            crate::exists("/nonexistent/path", Priority::unit_test()).await,
+            false
+        );
+    }
+
+    #[test_executors::async_test]
+    async fn test_buf_file_matches_file() {
+        logwise::context::Context::reset("test_buf_file_matches_file".to_string());
+        set_default_origin(TEST_ORIGIN);
+        let file = File::open(SEEK_FILE, Priority::unit_test()).await.unwrap();
+        let expected = file.read(1000, Priority::unit_test()).await.unwrap();
+
+        let file = File::open(SEEK_FILE, Priority::unit_test()).await.unwrap();
+        let mut reader = BufFile::with_capacity(64, file);
+        let mut actual = Vec::new();
+        while actual.len() < expected.len() {
+            let chunk = reader.read(7, Priority::unit_test()).await.unwrap();
+            assert!(!chunk.is_empty());
+            actual.extend_from_slice(&chunk);
+        }
+        assert_eq!(&actual[..expected.len()], &expected[..]);
+    }
+
+    #[test_executors::async_test]
+    async fn test_buf_file_seek_current() {
+        logwise::context::Context::reset("test_buf_file_seek_current".to_string());
+        set_default_origin(TEST_ORIGIN);
+        let file = File::open(SEEK_FILE, Priority::unit_test()).await.unwrap();
+        let mut reader = BufFile::with_capacity(64, file);
+        reader.read(10, Priority::unit_test()).await.unwrap();
+        assert_eq!(reader.buffer().len(), 54);
+        let pos = reader
+            .seek(std::io::SeekFrom::Current(5), Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(pos, 15);
+        assert!(reader.buffer().is_empty());
+    }
+
+    #[test_executors::async_test]
+    async fn test_priority_scope_byte_budget() {
+        logwise::context::Context::reset("test_priority_scope_byte_budget".to_string());
+        set_default_origin(TEST_ORIGIN);
+        let parent = PriorityScope::new(Priority::unit_test()).with_byte_budget(1500);
+        let child = parent.child();
+        let file = child.open(SEEK_FILE).await.unwrap();
+        assert_eq!(child.read(&file, 1000).await.unwrap().len(), 1000);
+        // clamped to what's left of the parent's budget
+        assert_eq!(child.read(&file, 1000).await.unwrap().len(), 500);
+        assert_eq!(parent.bytes_used(), 1500);
+        assert_eq!(child.remaining_bytes(), Some(0));
+        assert!(child.read(&file, 1).await.is_err());
+    }
+
+    #[test_executors::async_test]
+    async fn test_priority_scope_time_budget() {
+        logwise::context::Context::reset("test_priority_scope_time_budget".to_string());
+        set_default_origin(TEST_ORIGIN);
+        let scope =
+            PriorityScope::new(Priority::unit_test()).with_time_budget(std::time::Duration::ZERO);
+        assert!(scope.child().is_expired());
+        assert!(scope.open(TEST_FILE).await.is_err());
+    }
+
+    #[test_executors::async_test]
+    async fn test_buf_file_read_until() {
+        logwise::context::Context::reset("test_buf_file_read_until".to_string());
+        set_default_origin(TEST_ORIGIN);
+        let file = File::open(SEEK_FILE, Priority::unit_test()).await.unwrap();
+        let expected = file.read(4096, Priority::unit_test()).await.unwrap();
+        let delimiter = expected[100];
+        let end = expected.iter().position(|&b| b == delimiter).unwrap() + 1;
+
+        let file = File::open(SEEK_FILE, Priority::unit_test()).await.unwrap();
+        let mut reader = BufFile::with_capacity(16, file);
+        let record = reader
+            .read_until(delimiter, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(&record[..], &expected[..end]);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_buf_file_lines() {
+        logwise::context::Context::reset("test_buf_file_lines".to_string());
+        let expected = std::fs::read_to_string(SEEK_FILE).unwrap();
+        let file = File::open(SEEK_FILE, Priority::unit_test()).await.unwrap();
+        let mut lines = BufFile::new(file).lines(Priority::unit_test());
+        let mut actual = Vec::new();
+        while let Some(line) = lines.next_line().await {
+            actual.push(line.unwrap());
+        }
+        assert_eq!(actual, expected.lines().collect::<Vec<_>>());
+    }
+
+    #[test_executors::async_test]
+    async fn test_read_coalescing() {
+        logwise::context::Context::reset("test_read_coalescing".to_string());
+        set_default_origin(TEST_ORIGIN);
+        let expected = File::open(SEEK_FILE, Priority::unit_test())
+            .await
+            .unwrap()
+            .read(2048, Priority::unit_test())
+            .await
+            .unwrap();
+
+        // Per file, so other tests running meanwhile keep their read paths.
+        let options = crate::OpenOptions::new().coalesce(true);
+        let a = File::open_with(SEEK_FILE, &options, Priority::unit_test())
+            .await
+            .unwrap();
+        let b = File::open_with(SEEK_FILE, &options, Priority::unit_test())
+            .await
+            .unwrap();
+        let (data_a, data_b) = join(
+            a.read(1024, Priority::unit_test()),
+            b.read(1024, Priority::unit_test()),
+        )
+        .await;
+
+        assert_eq!(&data_a.unwrap()[..], &expected[..1024]);
+        assert_eq!(&data_b.unwrap()[..], &expected[..1024]);
+        // both files advanced, whether or not they shared the read
+        let next = b.read(1024, Priority::unit_test()).await.unwrap();
+        assert_eq!(&next[..], &expected[1024..]);
+    }
+
+    #[test_executors::async_test]
+    async fn test_small_read_aggregation() {
+        logwise::context::Context::reset("test_small_read_aggregation".to_string());
+        set_default_origin(TEST_ORIGIN);
+        let expected = File::open(SEEK_FILE, Priority::unit_test())
+            .await
+            .unwrap()
+            .read(200, Priority::unit_test())
+            .await
+            .unwrap();
+
+        let mut file = File::open(SEEK_FILE, Priority::unit_test()).await.unwrap();
+        file.set_small_read_aggregation(true);
+        let mut actual = Vec::new();
+        for _ in 0..10 {
+            let chunk = file.read(16, Priority::unit_test()).await.unwrap();
+            actual.extend_from_slice(&chunk);
+        }
+        assert_eq!(&actual[..], &expected[..160]);
+        // a single page was read physically
+        assert_eq!(file.pos.load(std::sync::atomic::Ordering::Relaxed), 4096);
+
+        let pos = file
+            .seek(std::io::SeekFrom::Current(8), Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(pos, 168);
+        let chunk = file.read(16, Priority::unit_test()).await.unwrap();
+        assert_eq!(&chunk[..], &expected[168..184]);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_copy() {
+        logwise::context::Context::reset("test_copy".to_string());
+        let to = temp_path("test_copy");
+        let copied = crate::copy(SEEK_FILE, &to, Priority::unit_test())
+            .await
+            .unwrap();
+        let expected = std::fs::read(SEEK_FILE).unwrap();
+        assert_eq!(copied, expected.len() as u64);
+        assert_eq!(std::fs::read(&to).unwrap(), expected);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_read_all_chunked() {
+        logwise::context::Context::reset("test_read_all_chunked".to_string());
+        let expected = std::fs::read(SEEK_FILE).unwrap();
+        let file = File::open(SEEK_FILE, Priority::unit_test()).await.unwrap();
+        let options = crate::ReadOptions::new().chunk_size(1000);
+        let contents = file
+            .read_all_with(&options, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(&contents[..], &expected[..]);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_large_read_spans_slices() {
+        logwise::context::Context::reset("test_large_read_spans_slices".to_string());
+        let path = temp_path("test_large_read_spans_slices");
+        let expected: Vec<u8> = (0..20 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&path, &expected).unwrap();
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        let data = file
+            .read(expected.len() + 1024, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(&data[..], &expected[..]);
+        assert_eq!(file.read(1, Priority::unit_test()).await.unwrap().len(), 0);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_remove_file_and_dir() {
+        logwise::context::Context::reset("test_remove_file_and_dir".to_string());
+        let dir = temp_path("test_remove_file_and_dir");
+        let file = dir.join("file");
+        std::fs::create_dir(&dir).unwrap();
+        std::fs::write(&file, b"bye").unwrap();
+        // not empty yet
+        assert!(
+            crate::remove_dir(&dir, Priority::unit_test())
+                .await
+                .is_err()
+        );
+        crate::remove_file(&file, Priority::unit_test())
+            .await
+            .unwrap();
+        crate::remove_dir(&dir, Priority::unit_test())
+            .await
+            .unwrap();
+        assert!(!crate::exists(&dir, Priority::unit_test()).await);
+        assert!(
+            crate::remove_file(&file, Priority::unit_test())
+                .await
+                .is_err()
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_read_many_partial() {
+        logwise::context::Context::reset("test_read_many_partial".to_string());
+        let missing = temp_path("test_read_many_partial_missing");
+        let result = crate::read_many(
+            [SEEK_FILE.as_ref(), missing.as_path()],
+            Priority::unit_test(),
+        )
+        .await;
+        assert!(!result.is_ok());
+        assert_eq!(result.succeeded().len(), 1);
+        assert_eq!(
+            &result.succeeded()[0].1[..],
+            &std::fs::read(SEEK_FILE).unwrap()[..]
+        );
+        assert_eq!(result.failed_paths().collect::<Vec<_>>(), [&*missing]);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_copy_dir_all() {
+        logwise::context::Context::reset("test_copy_dir_all".to_string());
+        let from = temp_path("test_copy_dir_all_from");
+        let to = temp_path("test_copy_dir_all_to");
+        std::fs::create_dir_all(from.join("sub/deeper")).unwrap();
+        std::fs::write(from.join("a"), b"a").unwrap();
+        std::fs::write(from.join("sub/b"), b"bb").unwrap();
+        std::fs::write(from.join("sub/deeper/c"), b"ccc").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(from.join("nowhere"), from.join("sub/dangling")).unwrap();
+
+        let result = crate::copy_dir_all(&from, &to, Priority::unit_test()).await;
+        let copied: Vec<_> = result
+            .succeeded()
+            .iter()
+            .map(|(path, n)| (path.strip_prefix(&from).unwrap().to_owned(), *n))
+            .collect();
+        assert_eq!(
+            copied,
+            [
+                ("a".into(), 1),
+                ("sub/b".into(), 2),
+                ("sub/deeper/c".into(), 3)
+            ]
+        );
+        assert_eq!(std::fs::read(to.join("sub/deeper/c")).unwrap(), b"ccc");
+        #[cfg(unix)]
+        assert_eq!(
+            result.failed_paths().collect::<Vec<_>>(),
+            [&from.join("sub/dangling")]
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_copy_dir_all_dry_run() {
+        logwise::context::Context::reset("test_copy_dir_all_dry_run".to_string());
+        let from = temp_path("test_copy_dir_all_dry_run_from");
+        let to = temp_path("test_copy_dir_all_dry_run_to");
+        std::fs::create_dir_all(from.join("sub")).unwrap();
+        std::fs::write(from.join("a"), b"a").unwrap();
+        std::fs::write(from.join("sub/b"), b"bb").unwrap();
+
+        let options = crate::CopyDirOptions::new().dry_run(true);
+        let plan = crate::copy_dir_all_with(&from, &to, &options, Priority::unit_test()).await;
+        assert!(plan.is_ok());
+        let planned: Vec<_> = plan
+            .succeeded()
+            .iter()
+            .map(|(path, n)| (path.strip_prefix(&from).unwrap().to_owned(), *n))
+            .collect();
+        assert_eq!(planned, [("a".into(), 1), ("sub/b".into(), 2)]);
+        assert!(!to.exists());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_remove_dir_all_progress() {
+        logwise::context::Context::reset("test_remove_dir_all_progress".to_string());
+        let dir = temp_path("test_remove_dir_all_progress");
+        std::fs::create_dir_all(dir.join("sub/deeper")).unwrap();
+        std::fs::write(dir.join("a"), b"a").unwrap();
+        std::fs::write(dir.join("sub/b"), b"b").unwrap();
+        std::fs::write(dir.join("sub/deeper/c"), b"c").unwrap();
+
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let record = seen.clone();
+        let options = crate::RemoveDirOptions::new()
+            .dry_run(true)
+            .on_progress(move |path, n| record.lock().unwrap().push((path.to_owned(), n)));
+        let planned = crate::remove_dir_all_with(&dir, &options, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(planned, 6);
+        assert!(dir.join("sub/deeper/c").exists());
+        let order: Vec<_> = seen
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(p, _)| p.clone())
+            .collect();
+        assert_eq!(
+            order,
+            [
+                dir.join("a"),
+                dir.join("sub/b"),
+                dir.join("sub/deeper/c"),
+                dir.join("sub/deeper"),
+                dir.join("sub"),
+                dir.clone(),
+            ]
+        );
+        assert_eq!(seen.lock().unwrap().last().unwrap().1, 6);
+
+        crate::remove_dir_all(&dir, Priority::unit_test())
+            .await
+            .unwrap();
+        assert!(!dir.exists());
+        assert!(
+            crate::remove_dir_all(&dir, Priority::unit_test())
+                .await
+                .is_err()
+        );
+    }
+
+    // Elsewhere a read-only file in a writable directory can be removed as is.
+    #[cfg(windows)]
+    #[test_executors::async_test]
+    async fn test_remove_dir_all_readonly() {
+        logwise::context::Context::reset("test_remove_dir_all_readonly".to_string());
+        let dir = temp_path("test_remove_dir_all_readonly");
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("locked");
+        std::fs::write(&file, b"locked").unwrap();
+        let mut permissions = std::fs::metadata(&file).unwrap().permissions();
+        permissions.set_readonly(true);
+        std::fs::set_permissions(&file, permissions).unwrap();
+
+        crate::remove_dir_all(&dir, Priority::unit_test())
+            .await
+            .unwrap();
+        assert!(!dir.exists());
+    }
+
+    #[cfg(unix)]
+    #[test_executors::async_test]
+    async fn test_walk_dir_symlink_policies() {
+        use crate::{EntryKind, SymlinkPolicy, WalkOptions, walk_dir};
+        logwise::context::Context::reset("test_walk_dir_symlink_policies".to_string());
+        let root = temp_path("test_walk_dir_symlink_policies");
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("sub/file"), b"x").unwrap();
+        // a link back to the root makes a cycle
+        std::os::unix::fs::symlink(&root, root.join("sub/loop")).unwrap();
+
+        let walk = |policy| {
+            let root = root.clone();
+            async move {
+                let options = WalkOptions::new().symlinks(policy);
+                let result = walk_dir(&root, &options, Priority::unit_test()).await;
+                let entries: Vec<_> = result
+                    .succeeded()
+                    .iter()
+                    .map(|(path, kind)| (path.strip_prefix(&root).unwrap().to_owned(), *kind))
+                    .collect();
+                let failed: Vec<_> = result.failed_paths().cloned().collect();
+                (entries, failed)
+            }
+        };
+
+        let (entries, failed) = walk(SymlinkPolicy::NoFollow).await;
+        assert_eq!(
+            entries,
+            [
+                ("sub".into(), EntryKind::Dir),
+                ("sub/file".into(), EntryKind::File),
+                ("sub/loop".into(), EntryKind::Symlink),
+            ]
+        );
+        assert!(failed.is_empty());
+
+        let (entries, _) = walk(SymlinkPolicy::Skip).await;
+        assert_eq!(entries.len(), 2);
+
+        // following the link finds the root again, which is reported rather than walked
+        let (entries, failed) = walk(SymlinkPolicy::Follow).await;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(failed, [root.join("sub/loop")]);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_probe() {
+        logwise::context::Context::reset("test_probe".to_string());
+        let dir = temp_path("test_probe");
+        std::fs::create_dir_all(&dir).unwrap();
+        let caps = crate::probe(&dir, Priority::unit_test()).await.unwrap();
+        assert!(caps.max_filename_len() > 0);
+        assert!(
+            crate::probe(dir.join("missing"), Priority::unit_test())
+                .await
+                .is_err()
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_fs_stats() {
+        logwise::context::Context::reset("test_fs_stats".to_string());
+        let dir = temp_path("test_fs_stats");
+        std::fs::create_dir_all(&dir).unwrap();
+        let stats = crate::fs_stats(&dir, Priority::unit_test()).await.unwrap();
+        assert!(stats.total() > 0);
+        assert!(stats.available() <= stats.free() && stats.free() <= stats.total());
+        assert!(
+            crate::fs_stats(dir.join("missing"), Priority::unit_test())
+                .await
+                .is_err()
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_dir_size() {
+        use crate::{DirSizeOptions, SizeKind};
+        logwise::context::Context::reset("test_dir_size".to_string());
+        let dir = temp_path("test_dir_size");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        std::fs::write(dir.join("a"), b"12345").unwrap();
+        std::fs::write(dir.join("sub/b"), b"1234567").unwrap();
+        #[cfg(unix)]
+        std::fs::hard_link(dir.join("a"), dir.join("sub/a_again")).unwrap();
+        assert_eq!(
+            crate::dir_size(&dir, Priority::unit_test()).await.unwrap(),
+            12
+        );
+        let options = DirSizeOptions::new().size(SizeKind::Allocated);
+        let allocated = crate::dir_size_with(&dir, &options, Priority::unit_test())
+            .await
+            .unwrap();
+        // Small files take at least a block each, except on filesystems that
+        // store them inline.
+        assert!(allocated == 0 || allocated >= 12, "{allocated}");
+        assert!(
+            crate::dir_size(dir.join("missing"), Priority::unit_test())
+                .await
+                .is_err()
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_hard_link() {
+        logwise::context::Context::reset("test_hard_link".to_string());
+        let original = temp_path("test_hard_link_original");
+        let link = temp_path("test_hard_link_link");
+        std::fs::write(&original, b"shared").unwrap();
+        crate::hard_link(&original, &link, Priority::unit_test())
+            .await
+            .unwrap();
+        std::fs::remove_file(&original).unwrap();
+        assert_eq!(std::fs::read(&link).unwrap(), b"shared");
+    }
+
+    #[cfg(unix)]
+    #[test_executors::async_test]
+    async fn test_symlink() {
+        logwise::context::Context::reset("test_symlink".to_string());
+        let original = temp_path("test_symlink_original");
+        let link = temp_path("test_symlink_link");
+        std::fs::write(&original, b"pointed at").unwrap();
+        crate::symlink(&original, &link, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_link(&link).unwrap(), *original);
+        assert_eq!(
+            crate::read_link(&link, Priority::unit_test())
+                .await
+                .unwrap(),
+            *original
+        );
+        assert_eq!(
+            crate::canonicalize(&link, Priority::unit_test())
+                .await
+                .unwrap(),
+            std::fs::canonicalize(&original).unwrap()
+        );
+        let link_metadata = crate::symlink_metadata(&link, Priority::unit_test())
+            .await
+            .unwrap();
+        assert!(link_metadata.is_symlink());
+        let file_metadata = crate::symlink_metadata(&original, Priority::unit_test())
+            .await
+            .unwrap();
+        assert!(!file_metadata.is_symlink());
+        assert_eq!(file_metadata.len(), 10);
+        assert!(
+            crate::read_link(&original, Priority::unit_test())
+                .await
+                .is_err()
+        );
+        assert!(
+            crate::symlink(&original, &link, Priority::unit_test())
+                .await
+                .is_err()
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_canonicalize() {
+        logwise::context::Context::reset("test_canonicalize".to_string());
+        let dir = temp_path("test_canonicalize");
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        let roundabout = dir.join("sub").join("..").join(".").join("sub");
+        let canonical = crate::canonicalize(&roundabout, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(canonical, std::fs::canonicalize(dir.join("sub")).unwrap());
+        assert!(
+            crate::canonicalize(dir.join("missing"), Priority::unit_test())
+                .await
+                .is_err()
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_metadata_times() {
+        logwise::context::Context::reset("test_metadata_times".to_string());
+        let path = temp_path("test_metadata_times");
+        std::fs::write(&path, b"timestamped").unwrap();
+        let expected = std::fs::metadata(&path).unwrap();
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        let metadata = file.metadata(Priority::unit_test()).await.unwrap();
+        assert_eq!(metadata.modified().unwrap(), expected.modified().unwrap());
+        assert_eq!(metadata.accessed().unwrap(), expected.accessed().unwrap());
+        assert_eq!(metadata.created().ok(), expected.created().ok());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_cache_dir() {
+        use crate::{CacheDir, CacheDirOptions};
+        use std::time::{Duration, SystemTime};
+        logwise::context::Context::reset("test_cache_dir".to_string());
+        let root = temp_path("cache_dir");
+        let _ = std::fs::remove_dir_all(&root);
+        let options = CacheDirOptions::new().max_size(25);
+        let cache = CacheDir::open_with(&root, 1, &options, Priority::unit_test())
+            .await
+            .unwrap();
+        let build = |byte: u8| move || async move { Ok::<_, Error>(vec![byte; 10]) };
+
+        assert!(
+            cache
+                .get("a", Priority::unit_test())
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let a = cache
+            .get_or_insert_with("a", build(b'a'), Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(&*a, &[b'a'; 10]);
+        // A hit doesn't run the builder.
+        let hit = cache
+            .get_or_insert_with::<_, _, Error>(
+                "a",
+                || async { panic!("built twice") },
+                Priority::unit_test(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(hit, a);
+        let old = SystemTime::now() - Duration::from_secs(3600);
+        std::fs::File::options()
+            .write(true)
+            .open(cache.path().join("e-a"))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        cache
+            .get_or_insert_with("b", build(b'b'), Priority::unit_test())
+            .await
+            .unwrap();
+        cache
+            .get_or_insert_with("c", build(b'c'), Priority::unit_test())
+            .await
+            .unwrap();
+        // 30 bytes is over the limit, so the oldest entry goes.
+        assert!(
+            cache
+                .get("a", Priority::unit_test())
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            cache
+                .get("b", Priority::unit_test())
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            cache
+                .get("c", Priority::unit_test())
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert!(cache.remove("b", Priority::unit_test()).await.unwrap());
+        assert!(!cache.remove("b", Priority::unit_test()).await.unwrap());
+        assert!(cache.get("", Priority::unit_test()).await.is_err());
+
+        let v2 = CacheDir::open(&root, 2, Priority::unit_test())
+            .await
+            .unwrap();
+        assert!(!root.join("v1").exists());
+        assert!(v2.get("c", Priority::unit_test()).await.unwrap().is_none());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_file_type() {
+        use crate::FileType;
+        logwise::context::Context::reset("test_file_type".to_string());
+        let dir = temp_path("file_type_dir");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir(&dir).unwrap();
+        let file = dir.join("file");
+        std::fs::write(&file, b"x").unwrap();
+
+        let metadata = crate::symlink_metadata(&file, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(metadata.file_type(), FileType::File);
+        assert!(metadata.is_file() && !metadata.is_dir() && !metadata.is_symlink());
+        let metadata = crate::symlink_metadata(&dir, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(metadata.file_type(), FileType::Dir);
+        assert!(metadata.is_dir() && !metadata.is_file());
+        #[cfg(unix)]
+        {
+            let link = dir.join("link");
+            std::os::unix::fs::symlink(&file, &link).unwrap();
+            let metadata = crate::symlink_metadata(&link, Priority::unit_test())
+                .await
+                .unwrap();
+            assert_eq!(metadata.file_type(), FileType::Symlink);
+            assert!(!metadata.is_file());
+            // File::open follows the link.
+            let opened = File::open(&link, Priority::unit_test()).await.unwrap();
+            let metadata = opened.metadata(Priority::unit_test()).await.unwrap();
+            assert!(metadata.is_file());
+            let opened = File::open(&dir, Priority::unit_test()).await.unwrap();
+            let metadata = opened.metadata(Priority::unit_test()).await.unwrap();
+            assert!(metadata.is_dir());
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_stream_to_channel() {
+        logwise::context::Context::reset("test_stream_to_channel".to_string());
+        let path = temp_path("stream_to_channel");
+        std::fs::write(&path, b"0123456789").unwrap();
+
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        let (tx, rx) = async_channel::bounded::<Data>(1);
+        let consumer = async move {
+            let mut chunks = Vec::new();
+            while let Ok(chunk) = rx.recv().await {
+                // The producer can't run more than one chunk ahead.
+                assert!(rx.len() <= 1);
+                chunks.push(chunk.into_boxed_slice().into_vec());
+            }
+            chunks
+        };
+        let (sent, chunks) = join(
+            crate::stream_to_channel(&file, 3, tx, Priority::unit_test()),
+            consumer,
+        )
+        .await;
+        assert_eq!(sent.unwrap(), 10);
+        assert_eq!(chunks, [&b"012"[..], b"345", b"678", b"9"]);
+
+        // With the receiver gone, nothing more is read.
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        let (tx, rx) = async_channel::bounded(1);
+        drop(rx);
+        let sent = crate::stream_to_channel(&file, 3, tx, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(sent, 0);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_set_permissions() {
+        logwise::context::Context::reset("test_set_permissions".to_string());
+        let path = temp_path("set_permissions");
+        std::fs::write(&path, b"#!/bin/sh\n").unwrap();
+        let permissions = || async {
+            crate::symlink_metadata(&path, Priority::unit_test())
+                .await
+                .unwrap()
+                .permissions()
+        };
+
+        let mut readonly = permissions().await;
+        assert!(!readonly.readonly());
+        readonly.set_readonly(true);
+        crate::set_permissions(&path, readonly, Priority::unit_test())
+            .await
+            .unwrap();
+        assert!(permissions().await.readonly());
+        assert!(std::fs::OpenOptions::new().write(true).open(&path).is_err() || is_root());
+
+        #[cfg(unix)]
+        {
+            crate::set_permissions(
+                &path,
+                crate::Permissions::from_mode(0o755),
+                Priority::unit_test(),
+            )
+            .await
+            .unwrap();
+            assert_eq!(permissions().await.mode() & 0o777, 0o755);
+        }
+        let mut writable = permissions().await;
+        writable.set_readonly(false);
+        crate::set_permissions(&path, writable, Priority::unit_test())
+            .await
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// Root can write to read-only files on Unix.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn is_root() -> bool {
+        #[cfg(unix)]
+        return unsafe { libc::geteuid() } == 0;
+        #[cfg(not(unix))]
+        return false;
+    }
+
+    #[cfg(all(unix, not(target_arch = "wasm32")))]
+    #[test_executors::async_test]
+    async fn test_unix_metadata_ext() {
+        use crate::unix::MetadataExt;
+        logwise::context::Context::reset("test_unix_metadata_ext".to_string());
+        let original = temp_path("unix_ext_original");
+        let link = temp_path("unix_ext_link");
+        std::fs::write(&original, vec![1; 4096]).unwrap();
+        std::fs::hard_link(&original, &link).unwrap();
+
+        let a = crate::symlink_metadata(&original, Priority::unit_test())
+            .await
+            .unwrap();
+        let b = crate::symlink_metadata(&link, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!((a.dev(), a.ino()), (b.dev(), b.ino()));
+        assert_eq!(a.nlink(), 2);
+        assert_eq!(a.uid(), unsafe { libc::geteuid() });
+        assert_eq!(a.gid(), b.gid());
+        assert!(a.blocks() > 0);
+        std::fs::remove_file(&original).unwrap();
+        std::fs::remove_file(&link).unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_in_memory_len() {
+        assert_eq!(crate::in_memory_len(0).unwrap(), 0);
+        assert_eq!(crate::in_memory_len(4096).unwrap(), 4096);
+        let error = crate::in_memory_len(u64::MAX).unwrap_err();
+        assert!(error.to_string().contains("too large"), "{error}");
+        // Over 4 GiB is representable only on 64-bit targets.
+        let over_4_gib = crate::in_memory_len(5 << 30);
+        assert_eq!(over_4_gib.is_ok(), usize::BITS == 64);
+    }
+
+    /// Offsets and lengths past 4 GiB survive the trip through every layer.
+    ///
+    /// Unix only: elsewhere the file would not be sparse, and every run would
+    /// write 5 GiB of zeros.
+    #[cfg(unix)]
+    #[test_executors::async_test]
+    async fn test_huge_sparse_file() {
+        logwise::context::Context::reset("test_huge_sparse_file".to_string());
+        let path = temp_path("huge_sparse");
+        let size = 5u64 << 30;
+        {
+            use std::io::{Seek, Write};
+            let mut file = std::fs::File::create(&path).unwrap();
+            file.set_len(size).unwrap();
+            file.seek(std::io::SeekFrom::Start(size - 4)).unwrap();
+            file.write_all(b"tail").unwrap();
+        }
+        let mut file = File::open(&path, Priority::unit_test()).await.unwrap();
+        let metadata = file.metadata(Priority::unit_test()).await.unwrap();
+        assert_eq!(metadata.len(), size);
+        let pos = file
+            .seek(std::io::SeekFrom::End(-4), Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(pos, size - 4);
+        let tail = file.read(16, Priority::unit_test()).await.unwrap();
+        assert_eq!(&*tail, b"tail");
+    }
+
+    #[test_executors::async_test]
+    async fn test_path_metadata() {
+        set_default_origin(TEST_ORIGIN);
+        logwise::context::Context::reset("test_path_metadata".to_string());
+        let metadata = crate::metadata(TEST_FILE, Priority::unit_test())
+            .await
+            .unwrap();
+        let file = File::open(TEST_FILE, Priority::unit_test()).await.unwrap();
+        let opened = file.metadata(Priority::unit_test()).await.unwrap();
+        assert_eq!(metadata.len(), opened.len());
+        assert!(
+            crate::metadata("does/not/exist", Priority::unit_test())
+                .await
+                .is_err()
+        );
+    }
+
+    #[cfg(all(unix, not(target_arch = "wasm32")))]
+    #[test_executors::async_test]
+    async fn test_metadata_follows_symlinks() {
+        logwise::context::Context::reset("test_metadata_follows_symlinks".to_string());
+        let target = temp_path("metadata_target");
+        let link = temp_path("metadata_link");
+        std::fs::write(&target, b"12345").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        let metadata = crate::metadata(&link, Priority::unit_test()).await.unwrap();
+        assert!(metadata.is_file());
+        assert_eq!(metadata.len(), 5);
+        std::fs::remove_file(&link).unwrap();
+        std::fs::remove_file(&target).unwrap();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_text() {
+        use crate::{TextEncoding, TextFallback, TextOptions};
+        logwise::context::Context::reset("test_text".to_string());
+        let path = temp_path("text");
+        std::fs::write(&path, b"caf\xC3\xA9 \xFF").unwrap();
+
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        let text = file.text(Priority::unit_test()).await.unwrap();
+        assert_eq!(text.as_str(), "caf\u{e9} \u{FFFD}");
+        assert!(text.is_lossy() && !text.is_truncated());
+
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        let options = TextOptions::new().max_len(4);
+        let text = file
+            .text_with(&options, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(text.as_str(), "caf");
+        assert!(text.is_truncated() && !text.is_lossy());
+
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        let options = TextOptions::new().fallback(TextFallback::Latin1);
+        let text = file
+            .text_with(&options, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(text.encoding(), TextEncoding::Latin1);
+        assert_eq!(text.as_str(), "caf\u{c3}\u{a9} \u{ff}");
+
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        let options = TextOptions::new().fallback(TextFallback::Strict);
+        assert!(
+            file.text_with(&options, Priority::unit_test())
+                .await
+                .is_err()
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_set_times() {
+        use crate::FileTimes;
+        use std::time::{Duration, UNIX_EPOCH};
+        logwise::context::Context::reset("test_set_times".to_string());
+        let path = temp_path("set_times");
+        std::fs::write(&path, b"x").unwrap();
+        let modified = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        let accessed = UNIX_EPOCH + Duration::from_secs(1_100_000_000);
+
+        let times = FileTimes::new()
+            .set_modified(modified)
+            .set_accessed(accessed);
+        crate::set_times(&path, times, Priority::unit_test())
+            .await
+            .unwrap();
+        let metadata = std::fs::metadata(&path).unwrap();
+        assert_eq!(metadata.modified().unwrap(), modified);
+        assert_eq!(metadata.accessed().unwrap(), accessed);
+
+        // Through an open handle, on a read-only file, changing only mtime.
+        let mut permissions = metadata.permissions();
+        permissions.set_readonly(true);
+        std::fs::set_permissions(&path, permissions).unwrap();
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        let later = modified + Duration::from_secs(60);
+        file.set_modified(later, Priority::unit_test())
+            .await
+            .unwrap();
+        let metadata = std::fs::metadata(&path).unwrap();
+        assert_eq!(metadata.modified().unwrap(), later);
+        assert_eq!(metadata.accessed().unwrap(), accessed);
+
+        // Directories too.
+        let dir = temp_path("set_times_dir");
+        let _ = std::fs::remove_dir(&dir);
+        std::fs::create_dir(&dir).unwrap();
+        crate::set_modified(&dir, modified, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::metadata(&dir).unwrap().modified().unwrap(),
+            modified
+        );
+        std::fs::remove_dir(&dir).unwrap();
+        let mut permissions = std::fs::metadata(&path).unwrap().permissions();
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        std::fs::set_permissions(&path, permissions).unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_empty_file_fast_paths() {
+        logwise::context::Context::reset("test_empty_file_fast_paths".to_string());
+        let path = temp_path("empty_marker");
+        std::fs::write(&path, b"").unwrap();
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        assert!(
+            file.read_all(Priority::unit_test())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            file.read(0, Priority::unit_test())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let result = crate::read_many([&path], Priority::unit_test()).await;
+        assert!(result.is_ok());
+        assert!(result.succeeded()[0].1.is_empty());
+
+        // A zero-length read is answered without touching the position.
+        let full = temp_path("zero_length_read");
+        std::fs::write(&full, b"abc").unwrap();
+        let file = File::open(&full, Priority::unit_test()).await.unwrap();
+        assert!(
+            file.read(0, Priority::unit_test())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(&*file.read(3, Priority::unit_test()).await.unwrap(), b"abc");
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&full).unwrap();
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+    #[test_executors::async_test]
+    async fn test_xattr() {
+        logwise::context::Context::reset("test_xattr".to_string());
+        let path = temp_path("xattr");
+        std::fs::write(&path, b"contents").unwrap();
+        // Not every filesystem the tests run on (tmpfs on older kernels,
+        // overlay mounts) allows user attributes.
+        if crate::set_xattr(&path, "user.hash", b"abc", Priority::unit_test())
+            .await
+            .is_err()
+        {
+            std::fs::remove_file(&path).unwrap();
+            return;
+        }
+        let value = crate::get_xattr(&path, "user.hash", Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(value.as_deref(), Some(&b"abc"[..]));
+        let names = crate::list_xattrs(&path, Priority::unit_test())
+            .await
+            .unwrap();
+        assert!(names.iter().any(|n| n == "user.hash"), "{names:?}");
+
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        file.set_xattr("user.flag", b"", Priority::unit_test())
+            .await
+            .unwrap();
+        let flag = file
+            .get_xattr("user.flag", Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(flag.as_deref(), Some(&b""[..]));
+        file.remove_xattr("user.hash", Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(
+            file.get_xattr("user.hash", Priority::unit_test())
+                .await
+                .unwrap(),
+            None
+        );
+        assert!(
+            crate::remove_xattr(&path, "user.hash", Priority::unit_test())
+                .await
+                .is_err()
+        );
+        drop(file);
+        std::fs::remove_file(&path).unwrap();
+    }
 
-Display: Not implemented. File handles are not typically displayed to users.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_read_once() {
+        logwise::context::Context::reset("test_read_once".to_string());
+        let path = temp_path("read_once");
+        std::fs::write(&path, b"0123456789").unwrap();
+        let whole = crate::read_once(&path, .., Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(&whole[..], b"0123456789");
+        let middle = crate::read_once(&path, 2..=4, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(&middle[..], b"234");
+        let tail = crate::read_once(&path, 7..100, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(&tail[..], b"789");
+        let past_end = crate::read_once(&path, 20.., Priority::unit_test())
+            .await
+            .unwrap();
+        assert!(past_end.is_empty());
+        std::fs::remove_file(&path).unwrap();
+        assert!(
+            crate::read_once(&path, .., Priority::unit_test())
+                .await
+                .is_err()
+        );
+    }
 
-AsRef/AsMut: Not implemented. We want to control the API surface and not expose
-the underlying file handle directly.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_open_direct() {
+        use crate::OpenOptions;
+        logwise::context::Context::reset("test_open_direct".to_string());
+        let path = temp_path("open_direct");
+        let contents: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&path, &contents).unwrap();
+        let options = OpenOptions::new().direct(true);
+        // tmpfs and some other filesystems refuse direct I/O.
+        let Ok(mut file) = File::open_with(&path, &options, Priority::unit_test()).await else {
+            std::fs::remove_file(&path).unwrap();
+            return;
+        };
+        let first = file.read(4096, Priority::unit_test()).await.unwrap();
+        assert_eq!(&first[..], &contents[..4096]);
+        // An unaligned position and length are handled internally.
+        file.seek(std::io::SeekFrom::Start(100), Priority::unit_test())
+            .await
+            .unwrap();
+        let middle = file.read(5000, Priority::unit_test()).await.unwrap();
+        assert_eq!(&middle[..], &contents[100..5100]);
+        let rest = file.read_all(Priority::unit_test()).await.unwrap();
+        assert_eq!(&rest[..], &contents[5100..]);
+        std::fs::remove_file(&path).unwrap();
+    }
 
-Send/Sync: Automatically derived and safe. Files can be sent between threads
-and accessed from multiple threads (though our API enforces single operation
-at a time).
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_advise() {
+        use crate::{Advice, OpenOptions};
+        logwise::context::Context::reset("test_advise".to_string());
+        let path = temp_path("advise");
+        std::fs::write(&path, b"advice is only a hint").unwrap();
+        let options = OpenOptions::new().advice(Advice::Sequential);
+        let file = File::open_with(&path, &options, Priority::unit_test())
+            .await
+            .unwrap();
+        for advice in [
+            Advice::Normal,
+            Advice::Sequential,
+            Advice::Random,
+            Advice::WillNeed,
+            Advice::DontNeed,
+        ] {
+            file.advise(advice, .., Priority::unit_test())
+                .await
+                .unwrap();
+            file.advise(advice, 6..=8, Priority::unit_test())
+                .await
+                .unwrap();
+        }
+        file.advise(Advice::WillNeed, 4..4, Priority::unit_test())
+            .await
+            .unwrap();
+        let data = file.read_all(Priority::unit_test()).await.unwrap();
+        assert_eq!(&data[..], b"advice is only a hint");
+        std::fs::remove_file(&path).unwrap();
+    }
 
-Unpin: Automatically derived and safe since there are no self-references.
- */
+    #[test]
+    fn test_data_slice() {
+        let data = Data::from_boxed_slice((0..100u8).collect());
+        let middle = data.slice(10..20);
+        assert_eq!(&middle[..], &(10..20u8).collect::<Vec<_>>()[..]);
+        assert_eq!(middle.as_ptr(), data[10..].as_ptr());
+        // Slices of slices are relative to the slice
+        let inner = middle.slice(2..=3);
+        assert_eq!(&inner[..], &[12, 13]);
+        assert_eq!(data.slice(..).len(), 100);
+        assert!(data.slice(100..).is_empty());
+        drop(data);
+        assert_eq!(&*inner.into_boxed_slice(), &[12, 13]);
+        assert_eq!(middle.len(), 10);
+    }
 
-/*
-Data
+    #[test]
+    fn test_data_concat() {
+        let data = Data::from_boxed_slice((0..10u8).collect());
+        let parts = [data.slice(..3), data.slice(3..3), data.slice(3..)];
+        assert_eq!(Data::concat(parts), data);
+        assert!(Data::concat([]).is_empty());
+        let only = data.slice(4..6);
+        let ptr = only.as_ptr();
+        assert_eq!(Data::concat([only]).as_ptr(), ptr);
+    }
 
-Clone: Not implemented. The OS probably supports a clone operation via refcount,
-but we deliberately don't expose it. Use Arc<Data> if you need shared ownership.
+    #[test]
+    fn test_data_with_alignment() {
+        let data = Data::from_boxed_slice((0..100u8).collect());
+        for align in [1, 2, 16, 4096] {
+            let unaligned = data.slice(1..);
+            let aligned = unaligned.with_alignment(align);
+            assert_eq!(aligned.as_ptr() as usize % align, 0);
+            assert_eq!(&aligned[..], &data[1..]);
+        }
+        let ptr = data.as_ptr();
+        assert_eq!(data.with_alignment(1).as_ptr(), ptr);
+    }
 
-Copy: Not implemented. Data represents potentially large buffers that shouldn't
-be copied implicitly.
+    #[test]
+    fn test_data_into_string() {
+        let data = Data::from_boxed_slice(Box::from("héllo".as_bytes()));
+        let ptr = data.as_ptr();
+        let text = data.into_string().unwrap();
+        assert_eq!(text, "héllo");
+        assert_eq!(text.as_ptr(), ptr);
 
-PartialEq/Eq: Implemented. Comparison via the underlying byte slice is meaningful
-and useful for testing and validation.
+        let data = Data::from_boxed_slice(Box::new([b'o', b'k', 0xff]));
+        let slice = data.slice(..2);
+        let (data, error) = data.into_string().unwrap_err();
+        assert_eq!(error.valid_up_to(), 2);
+        assert_eq!(&data[..], &[b'o', b'k', 0xff]);
+        // A shared or sliced buffer is copied.
+        assert_eq!(slice.into_string().unwrap(), "ok");
+    }
 
-Ord: Not implemented. Lexicographic ordering of byte data rarely makes sense
-in file I/O contexts.
+    #[test]
+    fn test_data_clone_shares() {
+        let data = Data::from_boxed_slice((0..10u8).collect());
+        let clone = data.slice(2..).clone();
+        assert_eq!(clone.as_ptr(), data[2..].as_ptr());
+        drop(data);
+        assert_eq!(&clone[..], &(2..10u8).collect::<Vec<_>>()[..]);
+    }
 
-Hash: Implemented. Hashing byte data is useful for caching and deduplication
-scenarios.
+    #[test]
+    #[should_panic]
+    fn test_data_slice_out_of_bounds() {
+        let data = Data::from_boxed_slice(Box::new([0; 4]));
+        let _ = data.slice(2..5);
+    }
 
-Default: Not implemented. There's no meaningful default data buffer.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_read_into() {
+        logwise::context::Context::reset("test_read_into".to_string());
+        let path = temp_path("read_into");
+        let contents: Vec<u8> = (0..300_000u32).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&path, &contents).unwrap();
+        let mut file = File::open(&path, Priority::unit_test()).await.unwrap();
+        let chunk_size = 128 * 1024;
+        let mut chunk = file.read(chunk_size, Priority::unit_test()).await.unwrap();
+        let ptr = chunk.as_ptr();
+        let mut read = chunk.to_vec();
+        loop {
+            chunk = file
+                .read_into(chunk, chunk_size, Priority::unit_test())
+                .await
+                .unwrap();
+            if chunk.is_empty() {
+                break;
+            }
+            assert_eq!(chunk.as_ptr(), ptr);
+            read.extend_from_slice(&chunk);
+        }
+        assert_eq!(read, contents);
 
-Display: Not implemented. Binary data is not typically displayed as text.
+        // A buffer still shared with a slice is not reused.
+        file.seek(std::io::SeekFrom::Start(0), Priority::unit_test())
+            .await
+            .unwrap();
+        let first = file.read(chunk_size, Priority::unit_test()).await.unwrap();
+        let header = first.slice(..4);
+        let second = file
+            .read_into(first, chunk_size, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(&header[..], &contents[..4]);
+        assert_eq!(&second[..], &contents[chunk_size..2 * chunk_size]);
+        std::fs::remove_file(&path).unwrap();
+    }
 
-Send/Sync: Automatically derived and safe since the data is immutable.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_read_all_mapped() {
+        logwise::context::Context::reset("test_read_all_mapped".to_string());
+        let path = temp_path("read_all_mapped");
+        let contents: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&path, &contents).unwrap();
+        let mut file = File::open(&path, Priority::unit_test()).await.unwrap();
+        // SAFETY: the file is private to this test, which doesn't change it
+        // while the data is alive.
+        let options = unsafe { crate::ReadOptions::new().map_threshold(Some(50_000)) };
+        let all = file
+            .read_all_with(&options, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(&all[..], &contents[..]);
+        // The position ends up at the end, as after a read.
+        assert!(
+            file.read(10, Priority::unit_test())
+                .await
+                .unwrap()
+                .is_empty()
+        );
 
-Unpin: Safe to unpin even if there are internal pointers, as the data is
-immutable after creation.
- */
+        // Mapping from an unaligned position.
+        file.seek(std::io::SeekFrom::Start(5_000), Priority::unit_test())
+            .await
+            .unwrap();
+        let rest = file
+            .read_all_with(&options, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(&rest[..], &contents[5_000..]);
+        drop(file);
+        assert_eq!(&all.slice(99_990..)[..], &contents[99_990..]);
+        std::fs::remove_file(&path).unwrap();
+    }
 
-impl PartialEq for Data {
-    fn eq(&self, other: &Self) -> bool {
-        self.0.eq(&other.0)
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_read_all_progress() {
+        logwise::context::Context::reset("test_read_all_progress".to_string());
+        let path = temp_path("read_all_progress");
+        std::fs::write(&path, b"0123456789").unwrap();
+        let mut file = File::open(&path, Priority::unit_test()).await.unwrap();
+        file.seek(std::io::SeekFrom::Start(1), Priority::unit_test())
+            .await
+            .unwrap();
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let record = std::sync::Arc::clone(&seen);
+        let options = crate::ReadOptions::new()
+            .chunk_size(4)
+            .on_progress(move |read, total| record.lock().unwrap().push((read, total)));
+        let all = file
+            .read_all_with(&options, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(&all[..], b"123456789");
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [(4, Some(9)), (8, Some(9)), (9, Some(9))]
+        );
+        std::fs::remove_file(&path).unwrap();
     }
-}
-impl Eq for Data {}
 
-impl Hash for Data {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.0.hash(state)
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_operation_queue() {
+        logwise::context::Context::reset("test_operation_queue".to_string());
+        set_default_origin(TEST_ORIGIN);
+        let file = File::open(SEEK_FILE, Priority::unit_test()).await.unwrap();
+        let in_flight = file.queue.turn(Priority::unit_test()).await;
+        let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+        let mut first = std::pin::pin!(file.read(1024, Priority::Background));
+        let mut second = std::pin::pin!(file.read(1024, Priority::UserInteractive));
+        // Both wait behind the operation in flight, without failing.
+        assert!(first.as_mut().poll(&mut context).is_pending());
+        assert!(second.as_mut().poll(&mut context).is_pending());
+        assert_eq!(file.pos.load(std::sync::atomic::Ordering::Relaxed), 0);
+        // Once it finishes, the more urgent read goes first.
+        drop(in_flight);
+        assert!(first.as_mut().poll(&mut context).is_pending());
+        let second = second.await.unwrap();
+        let first = first.await.unwrap();
+        let expected = std::fs::read(SEEK_FILE).unwrap();
+        assert_eq!(&*second, &expected[..1024]);
+        assert_eq!(&*first, &expected[1024..2048]);
     }
-}
 
-/*
-Metadata
+    #[test_executors::async_test]
+    async fn test_exclusive() {
+        logwise::context::Context::reset("test_exclusive".to_string());
+        set_default_origin(TEST_ORIGIN);
+        let expected = File::open(SEEK_FILE, Priority::unit_test())
+            .await
+            .unwrap()
+            .read(2048, Priority::unit_test())
+            .await
+            .unwrap();
+        let mut file = File::open(SEEK_FILE, Priority::unit_test()).await.unwrap();
+        let mut exclusive = file.exclusive();
+        let first = exclusive.read(1024, Priority::unit_test()).await.unwrap();
+        assert_eq!(&first[..], &expected[..1024]);
+        exclusive
+            .seek(std::io::SeekFrom::Start(512), Priority::unit_test())
+            .await
+            .unwrap();
+        let len = exclusive
+            .metadata(Priority::unit_test())
+            .await
+            .unwrap()
+            .len();
+        let rest = exclusive.read_all(Priority::unit_test()).await.unwrap();
+        assert_eq!(rest.len() as u64, len - 512);
+        assert_eq!(&rest[..1536], &expected[512..]);
+    }
 
-Clone: Implemented via derive. std::fs::Metadata supports Clone and it makes sense
-for metadata to be cloneable as it's just informational data that users may want
-to store or pass around.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_dropped_read_keeps_position() {
+        logwise::context::Context::reset("test_dropped_read_keeps_position".to_string());
+        let path = temp_path("dropped_read");
+        let contents: Vec<u8> = (0..16 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&path, &contents).unwrap();
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        file.read(10, Priority::unit_test()).await.unwrap();
 
-Copy: Not implemented. std::fs::Metadata doesn't support Copy as it's not a trivial
-type - it contains platform-specific metadata that may include complex structures.
+        // A large read is still running on a worker after its first poll.
+        let mut read = Box::pin(file.read(contents.len(), Priority::unit_test()));
+        let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+        assert!(read.as_mut().poll(&mut cx).is_pending());
+        drop(read);
 
-PartialEq/Eq: Not implemented. std::fs::Metadata doesn't support equality comparison,
-likely because metadata can include timestamps and other volatile information that
-makes equality semantics unclear.
+        let next = file.read(4, Priority::unit_test()).await.unwrap();
+        assert_eq!(&next[..], &contents[10..14]);
+    }
 
-Hash: Not implemented. std::fs::Metadata doesn't support hashing, and without Eq
-it wouldn't make sense anyway.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_dropped_read_all_keeps_position() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicU64, Ordering};
+        logwise::context::Context::reset("test_dropped_read_all_keeps_position".to_string());
+        let path = temp_path("dropped_read_all");
+        let contents: Vec<u8> = (0..256 * 1024).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&path, &contents).unwrap();
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        file.read(10, Priority::unit_test()).await.unwrap();
 
-Default: Not implemented. There's no meaningful default metadata - metadata must
-come from an actual file.
+        let progress = Arc::new(AtomicU64::new(0));
+        let reported = progress.clone();
+        let options = crate::ReadOptions::new()
+            .chunk_size(4096)
+            .on_progress(move |read, _| reported.store(read, Ordering::Relaxed));
+        let mut read = Box::pin(file.read_all_with(&options, Priority::unit_test()));
+        let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+        // Drop the read once a few chunks have moved the position.
+        while progress.load(Ordering::Relaxed) < 3 * 4096 {
+            assert!(read.as_mut().poll(&mut cx).is_pending());
+            std::thread::yield_now();
+        }
+        drop(read);
 
-Display: Not implemented. Metadata is not typically formatted for end-user display.
+        let next = file.read(4, Priority::unit_test()).await.unwrap();
+        assert_eq!(&next[..], &contents[10..14]);
+        let rest = file.read_all(Priority::unit_test()).await.unwrap();
+        assert_eq!(&rest[..], &contents[14..]);
+    }
 
-From/Into: Not obvious conversions exist. We don't want users converting between
-our metadata and std metadata directly.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_retry_policy_options() {
+        use crate::{OpenOptions, ReadOptions, RetryPolicy};
+        logwise::context::Context::reset("test_retry_policy_options".to_string());
+        // A retried error would wait an hour before its second try.
+        let retry = RetryPolicy::new().backoff(std::time::Duration::from_secs(3600));
+        let options = OpenOptions::new().retry(retry.clone());
+        assert_eq!(options.get_retry(), Some(&retry));
+        let missing = File::open_with("does_not_exist.txt", &options, Priority::unit_test())
+            .await
+            .unwrap_err();
+        assert!(missing.0.is_not_found());
 
-AsRef/AsMut: Not implemented. We want to control the API surface and not expose
-the underlying std::fs::Metadata directly.
+        let file = File::open_with("README.md", &options, Priority::unit_test())
+            .await
+            .unwrap();
+        let read_options = ReadOptions::new().retry(RetryPolicy::new().attempts(1));
+        let data = file
+            .read_all_with(&read_options, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(
+            data.len() as u64,
+            std::fs::metadata("README.md").unwrap().len()
+        );
+        assert!(file.retry_override.lock().unwrap().is_none());
+    }
 
-Send/Sync: Automatically derived since std::fs::Metadata is Send + Sync.
-Unpin: Automatically derived and safe since there are no self-references.
- */
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_error_kind() {
+        use crate::ErrorKind;
+        logwise::context::Context::reset("test_error_kind".to_string());
+        let missing = File::open("does_not_exist.txt", Priority::unit_test())
+            .await
+            .unwrap_err();
+        assert_eq!(missing.kind(), ErrorKind::NotFound);
+        assert_eq!(
+            Error(crate::sys::Error::timed_out()).kind(),
+            ErrorKind::TimedOut
+        );
+        assert_eq!(
+            Error(crate::sys::Error::Io(
+                std::io::ErrorKind::Interrupted.into()
+            ))
+            .kind(),
+            ErrorKind::Interrupted
+        );
+    }
 
-#[cfg(test)]
-mod tests {
-    use crate::{Data, Error, File, Metadata, Priority, set_default_origin};
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test_executors::async_test]
+    async fn test_into_io_error() {
+        logwise::context::Context::reset("test_into_io_error".to_string());
+        let missing = File::open("does_not_exist.txt", Priority::unit_test())
+            .await
+            .unwrap_err();
+        let io = std::io::Error::from(missing);
+        assert_eq!(io.kind(), std::io::ErrorKind::NotFound);
+        assert!(io.raw_os_error().is_some());
+    }
 
-    #[cfg(target_arch = "wasm32")]
-    const TEST_FILE: &str = "5MB.zip";
     #[cfg(not(target_arch = "wasm32"))]
-    const TEST_FILE: &str = "/dev/zero";
+    #[test_executors::async_test]
+    async fn test_from_std_into_std() {
+        use std::io::{Read, Seek};
+        logwise::context::Context::reset("test_from_std_into_std".to_string());
+        let path = temp_path("from_std");
+        std::fs::write(&path, b"0123456789").unwrap();
+        let mut std_file = std::fs::File::open(&path).unwrap();
+        std_file.seek(std::io::SeekFrom::Start(2)).unwrap();
 
-    #[cfg(target_arch = "wasm32")]
-    const SEEK_FILE: &str = TEST_FILE;
+        let mut file = File::from_std(std_file);
+        file.set_small_read_aggregation(true);
+        let data = file.read(3, Priority::unit_test()).await.unwrap();
+        assert_eq!(&*data, b"234");
 
-    #[cfg(not(target_arch = "wasm32"))]
-    const SEEK_FILE: &str = "/etc/services";
+        // The aggregation page holds the rest, but the cursor is where the
+        // next read would start.
+        let mut std_file = file.into_std().unwrap();
+        let mut rest = String::new();
+        std_file.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "56789");
+    }
 
+    #[cfg(all(unix, not(target_arch = "wasm32")))]
     #[test_executors::async_test]
-    async fn test_open_file() {
-        logwise::context::Context::reset("test_open_file".to_string());
-        set_default_origin("http://ipv4.download.thinkbroadband.com/");
-        let _file = File::open(TEST_FILE, Priority::unit_test()).await.unwrap();
+    async fn test_owned_fd() {
+        use std::os::fd::{AsFd, AsRawFd, OwnedFd};
+        logwise::context::Context::reset("test_owned_fd".to_string());
+        let fd = OwnedFd::from(std::fs::File::open("README.md").unwrap());
+        let raw = fd.as_raw_fd();
+        let file = File::from_owned_fd(fd);
+        assert_eq!(file.as_raw_fd(), raw);
+        assert_eq!(file.as_fd().as_raw_fd(), raw);
+        let data = file.read(2, Priority::unit_test()).await.unwrap();
+        assert_eq!(&*data, &std::fs::read("README.md").unwrap()[..2]);
     }
+
+    #[cfg(windows)]
     #[test_executors::async_test]
-    async fn test_read_file() {
-        logwise::context::Context::reset("test_read_file".to_string());
-        set_default_origin("http://ipv4.download.thinkbroadband.com/");
-        let file = File::open(TEST_FILE, Priority::unit_test()).await.unwrap();
-        let buf = file.read(1024, Priority::unit_test()).await.unwrap();
-        assert_eq!(buf.len(), 1024);
-        #[cfg(not(target_arch = "wasm32"))]
-        assert_eq!(buf.iter().all(|&x| x == 0), true);
-        #[cfg(target_arch = "wasm32")]
-        assert!(
-            buf.starts_with(&[121, 153, 245, 9, 197, 194]),
-            "Expected output, got: {:?}",
-            buf
-        );
+    async fn test_owned_handle() {
+        use std::os::windows::io::{AsHandle, AsRawHandle, OwnedHandle};
+        logwise::context::Context::reset("test_owned_handle".to_string());
+        let handle = OwnedHandle::from(std::fs::File::open("README.md").unwrap());
+        let raw = handle.as_raw_handle();
+        let file = File::from_owned_handle(handle);
+        assert_eq!(file.as_raw_handle(), raw);
+        assert_eq!(file.as_handle().as_raw_handle(), raw);
+        let data = file.read(2, Priority::unit_test()).await.unwrap();
+        assert_eq!(&*data, &std::fs::read("README.md").unwrap()[..2]);
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
     #[test_executors::async_test]
-    async fn test_seek_file() {
-        logwise::context::Context::reset("test_seek_file".to_string());
-        set_default_origin("http://ipv4.download.thinkbroadband.com/");
-
-        //tough to seek /dev/zero on linux for some reason
-        let mut file = File::open(SEEK_FILE, Priority::unit_test()).await.unwrap();
-        let pos = file
-            .seek(std::io::SeekFrom::Start(1024), Priority::unit_test())
+    async fn test_try_clone() {
+        logwise::context::Context::reset("test_try_clone".to_string());
+        let expected = std::fs::read("README.md").unwrap();
+        let file = File::open("README.md", Priority::unit_test())
             .await
             .unwrap();
-        assert_eq!(pos, 1024);
-        let buf = file.read(1024, Priority::unit_test()).await.unwrap();
-        assert_eq!(buf.len(), 1024);
-    }
-
-    #[test]
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
-    fn test_send_sync() {
-        fn _assert_send_sync<T: Send + Sync>() {}
-        _assert_send_sync::<Data>();
-        _assert_send_sync::<File>();
-        _assert_send_sync::<Metadata>();
-        _assert_send_sync::<Error>();
-    }
+        let head = file.read(4, Priority::unit_test()).await.unwrap();
+        assert_eq!(&*head, &expected[..4]);
 
-    #[test]
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
-    fn test_unpin() {
-        fn _assert_unpin<T: Unpin>() {}
-        _assert_unpin::<Data>();
-        _assert_unpin::<File>();
-        _assert_unpin::<Metadata>();
-        _assert_unpin::<Error>();
+        let mut clone = file.try_clone(Priority::unit_test()).await.unwrap();
+        clone
+            .seek(std::io::SeekFrom::Start(10), Priority::unit_test())
+            .await
+            .unwrap();
+        // Both handles can have a read in flight, each at its own position.
+        let read = file.read(4, Priority::unit_test());
+        let cloned_read = clone.read(4, Priority::unit_test());
+        assert_eq!(&*read.await.unwrap(), &expected[4..8]);
+        assert_eq!(&*cloned_read.await.unwrap(), &expected[10..14]);
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
     #[test_executors::async_test]
-    async fn test_length() {
-        set_default_origin("http://ipv4.download.thinkbroadband.com/");
+    async fn test_hooks() {
+        use crate::{Hooks, Operation, set_hooks};
+        use std::future::Future;
+        use std::sync::{Arc, Mutex};
+        logwise::context::Context::reset("test_hooks".to_string());
+        let path = temp_path("hooks");
+        std::fs::write(&path, b"hello hooks").unwrap();
+        // Other tests' operations are reported too; keep only this file's.
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let record = |events: &Arc<Mutex<Vec<_>>>, path: &std::path::Path, done| {
+            let events = events.clone();
+            let path = path.to_owned();
+            move |event: &crate::OperationEvent<'_>| {
+                if event.path() == path {
+                    let entry = (done, event.operation(), event.bytes(), event.is_cancelled());
+                    events.lock().unwrap().push(entry);
+                }
+            }
+        };
+        set_hooks(Some(
+            Hooks::new()
+                .on_start(record(&events, &path, false))
+                .on_complete(record(&events, &path, true)),
+        ));
 
-        logwise::context::Context::reset("test_length".to_string());
-        let file = File::open(TEST_FILE, Priority::unit_test()).await.unwrap();
-        let metadata = file.metadata(Priority::unit_test()).await.unwrap();
-        #[cfg(target_arch = "wasm32")]
-        assert_eq!(metadata.len(), 5242880);
-        #[cfg(not(target_arch = "wasm32"))]
-        assert_eq!(metadata.len(), 0);
+        let mut file = File::open(&path, Priority::unit_test()).await.unwrap();
+        file.read(5, Priority::unit_test()).await.unwrap();
+        file.seek(std::io::SeekFrom::Start(6), Priority::unit_test())
+            .await
+            .unwrap();
+        file.metadata(Priority::unit_test()).await.unwrap();
+        {
+            let in_flight = file.queue.turn(Priority::unit_test()).await;
+            let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+            let mut read = std::pin::pin!(file.read(5, Priority::unit_test()));
+            assert!(read.as_mut().poll(&mut context).is_pending());
+            drop(in_flight);
+        }
+        file.read_all(Priority::unit_test()).await.unwrap();
+        set_hooks(None);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                (false, Operation::Open, 0, false),
+                (true, Operation::Open, 0, false),
+                (false, Operation::Read, 0, false),
+                (true, Operation::Read, 5, false),
+                (false, Operation::Seek, 0, false),
+                (true, Operation::Seek, 0, false),
+                (false, Operation::Metadata, 0, false),
+                (true, Operation::Metadata, 0, false),
+                (false, Operation::Read, 0, false),
+                (true, Operation::Read, 0, true),
+                (false, Operation::Read, 0, false),
+                (true, Operation::Read, 5, false),
+            ]
+        );
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
     #[test_executors::async_test]
-    async fn test_exists() {
-        logwise::context::Context::reset("test_exists".to_string());
-        set_default_origin("http://ipv4.download.thinkbroadband.com/");
-        assert_eq!(crate::exists(TEST_FILE, Priority::unit_test()).await, true);
-        assert_eq!(
-            crate::exists("/nonexistent/path", Priority::unit_test()).await,
-            false
+    async fn test_rename() {
+        logwise::context::Context::reset("test_rename".to_string());
+        let from = temp_path("test_rename_from");
+        let to = temp_path("test_rename_to");
+        std::fs::write(&from, b"renamed").unwrap();
+        crate::rename(&from, &to, Priority::unit_test())
+            .await
+            .unwrap();
+        assert!(!crate::exists(&from, Priority::unit_test()).await);
+        assert_eq!(std::fs::read(&to).unwrap(), b"renamed");
+        assert!(
+            crate::rename(&from, &to, Priority::unit_test())
+                .await
+                .is_err()
         );
     }
 }
@@ -0,0 +1,236 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Accounting for the memory held by live [`Data`](crate::Data) buffers.
+//!
+//! Every `Data` carries a [`Lease`] on the bytes it holds, released when it is
+//! dropped. With a budget set, large reads wait for enough leases to be released
+//! before they start, so a burst of reads on a constrained device queues up
+//! instead of allocating everything at once.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Poll, Waker};
+
+/// Reads smaller than this are never delayed by the budget.
+pub(crate) const LARGE_READ: usize = 64 * 1024;
+
+/// Means "no budget" in [`Budget::cap`].
+const UNLIMITED: u64 = u64::MAX;
+
+static GLOBAL: Budget = Budget::new();
+
+/// Sets a soft cap, in bytes, on memory held by live [`Data`](crate::Data)
+/// buffers, or removes it with `None`.
+///
+/// While the bytes in use plus the size of a new read would exceed the cap,
+/// reads of 64 KiB or more wait until enough buffers are dropped. Smaller reads
+/// are counted but never wait. The cap is soft: a read is always admitted when
+/// no other buffers are live, even if it alone exceeds the cap, and buffers
+/// created other than by reading (for example by [`BufFile`](crate::BufFile)
+/// copying out of its buffer) are counted without waiting.
+///
+/// Waiting is keyed on the size requested, not the size eventually read, so a
+/// 1 MiB read near the end of a file waits as if it would return 1 MiB.
+///
+/// # Deadlock
+///
+/// A read only proceeds once other buffers are released. A task that holds
+/// buffers while reading more can wait on itself forever if its own buffers
+/// are what puts it over the cap. Size the cap for the largest working set a
+/// single task keeps, or drop buffers before reading more.
+///
+/// # Examples
+///
+/// ```
+/// use async_file::{memory_in_use, set_memory_budget};
+///
+/// // Keep read buffers on this device under 64 MiB
+/// set_memory_budget(Some(64 * 1024 * 1024));
+/// println!("{} bytes in use", memory_in_use());
+/// ```
+pub fn set_memory_budget(cap: Option<u64>) {
+    GLOBAL.set_cap(cap);
+}
+
+/// Returns the cap set by [`set_memory_budget`], if any.
+pub fn memory_budget() -> Option<u64> {
+    GLOBAL.cap()
+}
+
+/// Returns the number of bytes held by live [`Data`](crate::Data) buffers,
+/// including reads that have been admitted but not yet completed.
+pub fn memory_in_use() -> u64 {
+    GLOBAL.in_use()
+}
+
+/// Counts bytes in use against an optional cap.
+#[derive(Debug)]
+pub(crate) struct Budget {
+    in_use: AtomicU64,
+    cap: AtomicU64,
+    waiters: Mutex<Vec<Waker>>,
+}
+
+impl Budget {
+    const fn new() -> Self {
+        Budget {
+            in_use: AtomicU64::new(0),
+            cap: AtomicU64::new(UNLIMITED),
+            waiters: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn set_cap(&self, cap: Option<u64>) {
+        self.cap.store(cap.unwrap_or(UNLIMITED), Ordering::Relaxed);
+        // A higher cap may admit reads that were waiting.
+        self.wake_all();
+    }
+
+    fn cap(&self) -> Option<u64> {
+        Some(self.cap.load(Ordering::Relaxed)).filter(|&cap| cap != UNLIMITED)
+    }
+
+    fn in_use(&self) -> u64 {
+        self.in_use.load(Ordering::Relaxed)
+    }
+
+    /// Counts `bytes` as in use immediately.
+    fn lease(&'static self, bytes: u64) -> Lease {
+        self.in_use.fetch_add(bytes, Ordering::Relaxed);
+        Lease {
+            budget: self,
+            bytes,
+        }
+    }
+
+    /// Counts `bytes` as in use once they fit under the cap.
+    async fn admit(&'static self, bytes: u64) -> Lease {
+        std::future::poll_fn(|cx| {
+            // Admission and waker registration happen under the lock that
+            // `release` takes after decrementing, so no release is missed.
+            let mut waiters = self.waiters.lock().unwrap();
+            let in_use = self.in_use();
+            let cap = self.cap.load(Ordering::Relaxed);
+            if in_use == 0 || in_use.saturating_add(bytes) <= cap {
+                drop(waiters);
+                Poll::Ready(self.lease(bytes))
+            } else {
+                waiters.push(cx.waker().clone());
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    fn release(&self, bytes: u64) {
+        if bytes == 0 {
+            return;
+        }
+        self.in_use.fetch_sub(bytes, Ordering::Relaxed);
+        if self.cap.load(Ordering::Relaxed) != UNLIMITED {
+            self.wake_all();
+        }
+    }
+
+    fn wake_all(&self) {
+        let waiters = std::mem::take(&mut *self.waiters.lock().unwrap());
+        for waker in waiters {
+            waker.wake();
+        }
+    }
+}
+
+/// A claim on bytes counted by a [`Budget`], released on drop.
+#[derive(Debug)]
+pub(crate) struct Lease {
+    budget: &'static Budget,
+    bytes: u64,
+}
+
+impl Lease {
+    /// Counts `bytes` against the global budget immediately.
+    pub(crate) fn new(bytes: usize) -> Self {
+        GLOBAL.lease(bytes as u64)
+    }
+
+    /// Waits until a read of `bytes` is admitted by the global budget.
+    pub(crate) async fn admit(bytes: usize) -> Self {
+        if bytes < LARGE_READ {
+            return Self::new(bytes);
+        }
+        GLOBAL.admit(bytes as u64).await
+    }
+
+    /// Adjusts the lease to cover exactly `bytes`, as when a read returns
+    /// fewer bytes than it reserved.
+    pub(crate) fn resize(mut self, bytes: usize) -> Self {
+        let bytes = bytes as u64;
+        if bytes > self.bytes {
+            self.budget
+                .in_use
+                .fetch_add(bytes - self.bytes, Ordering::Relaxed);
+        } else {
+            self.budget.release(self.bytes - bytes);
+        }
+        self.bytes = bytes;
+        self
+    }
+}
+
+impl Drop for Lease {
+    fn drop(&mut self) {
+        self.budget.release(self.bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Budget;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    fn budget(cap: u64) -> &'static Budget {
+        let budget = Box::leak(Box::new(Budget::new()));
+        budget.set_cap(Some(cap));
+        budget
+    }
+
+    #[test]
+    fn test_admit_waits_for_release() {
+        let budget = budget(100);
+        let held = budget.lease(80);
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut admit = pin!(budget.admit(50));
+        assert!(admit.as_mut().poll(&mut cx).is_pending());
+        drop(held);
+        let Poll::Ready(lease) = admit.as_mut().poll(&mut cx) else {
+            panic!("not admitted after release");
+        };
+        assert_eq!(budget.in_use(), 50);
+        let lease = lease.resize(20);
+        assert_eq!(budget.in_use(), 20);
+        drop(lease);
+        assert_eq!(budget.in_use(), 0);
+    }
+
+    #[test]
+    fn test_oversized_read_admitted_when_idle() {
+        let budget = budget(10);
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut admit = pin!(budget.admit(1000));
+        assert!(admit.as_mut().poll(&mut cx).is_ready());
+    }
+
+    #[test]
+    fn test_raising_cap_admits() {
+        let budget = budget(100);
+        let _held = budget.lease(80);
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut admit = pin!(budget.admit(50));
+        assert!(admit.as_mut().poll(&mut cx).is_pending());
+        budget.set_cap(None);
+        assert_eq!(budget.cap(), None);
+        assert!(admit.as_mut().poll(&mut cx).is_ready());
+    }
+}
@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Streaming reads of newline-delimited JSON.
+//!
+//! Logs, exports and data pipelines often store one JSON record per line, in
+//! files too large to parse as a single document. [`read_ndjson`] reads such a
+//! file line by line through a [`BufFile`] and deserializes each line as it
+//! arrives, so only one record is in memory at a time and a malformed record
+//! costs that record, not the whole file.
+
+use crate::buf_file::Lines;
+use crate::{BufFile, Error, File, Priority, sys};
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Opens `path` and returns a stream of the JSON records on its lines.
+///
+/// Each non-blank line is deserialized as one `T`. Blank lines (including lines
+/// of only whitespace) are skipped. Lines may end in `\n` or `\r\n`.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be opened. Errors while streaming are
+/// reported as items:
+///
+/// - A line that is not a valid `T` yields an error naming its line number,
+///   and the stream continues with the next line.
+/// - A failed read, or a line that is not valid UTF-8, yields an error and
+///   ends the stream, as with [`BufFile::lines`].
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{read_ndjson, Priority};
+///
+/// let mut events = read_ndjson::<(String, u64)>("events.ndjson", Priority::unit_test()).await?;
+/// while let Some(event) = events.next_record().await {
+///     match event {
+///         Ok((name, timestamp)) => println!("{timestamp}: {name}"),
+///         Err(e) => eprintln!("skipping: {e}"),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn read_ndjson<T: DeserializeOwned>(
+    path: impl AsRef<std::path::Path>,
+    priority: Priority,
+) -> Result<NdjsonRecords<T>, Error> {
+    let file = File::open(path, priority).await?;
+    Ok(NdjsonRecords {
+        lines: BufFile::new(file).lines(priority),
+        line: 0,
+        _record: PhantomData,
+    })
+}
+
+/// A stream of records parsed from newline-delimited JSON.
+///
+/// Returned by [`read_ndjson`]. Use [`next_record`](NdjsonRecords::next_record),
+/// or poll it as a `futures_core::Stream`.
+pub struct NdjsonRecords<T> {
+    lines: Lines,
+    line: u64,
+    _record: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> NdjsonRecords<T> {
+    /// Returns the next record, or `None` when the stream is exhausted.
+    pub async fn next_record(&mut self) -> Option<Result<T, Error>> {
+        std::future::poll_fn(|cx| self.poll_next_record(cx)).await
+    }
+
+    fn poll_next_record(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<T, Error>>> {
+        loop {
+            let line = match self.lines.poll_next_line(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(Some(Ok(line))) => line,
+            };
+            self.line += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            return Poll::Ready(Some(serde_json::from_str(&line).map_err(|source| {
+                Error(sys::Error::Json {
+                    line: self.line,
+                    source,
+                })
+            })));
+        }
+    }
+}
+
+impl<T: DeserializeOwned> futures_core::Stream for NdjsonRecords<T> {
+    type Item = Result<T, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_next_record(cx)
+    }
+}
+
+impl<T> std::fmt::Debug for NdjsonRecords<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NdjsonRecords")
+            .field("line", &self.line)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::read_ndjson;
+    use crate::Priority;
+
+    #[test_executors::async_test]
+    async fn test_read_ndjson() {
+        logwise::context::Context::reset("test_read_ndjson".to_string());
+        let path = crate::tests::temp_path("read_ndjson.ndjson");
+        std::fs::write(&path, "[\"a\", 1]\r\n\n  \n{oops}\n[\"b\", 2]").unwrap();
+        let mut records = read_ndjson::<(String, u32)>(&path, Priority::unit_test())
+            .await
+            .unwrap();
+        let first = records.next_record().await.unwrap().unwrap();
+        assert_eq!(first, ("a".to_string(), 1));
+        let error = records.next_record().await.unwrap().unwrap_err();
+        assert!(error.to_string().contains("line 4"), "{error}");
+        let last = records.next_record().await.unwrap().unwrap();
+        assert_eq!(last, ("b".to_string(), 2));
+        assert!(records.next_record().await.is_none());
+    }
+}
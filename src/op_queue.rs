@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! The queue that serializes operations on one [`File`](crate::File).
+//!
+//! Only one operation runs on a file at a time, because each reads and moves
+//! the file position. An operation started while another is running waits
+//! here for its turn instead of failing. Waiting operations are served in
+//! priority order, and first come, first served within a priority.
+
+use crate::{Priority, rank};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll, Waker};
+
+/// Hands out turns to run an operation on one file, one at a time.
+#[derive(Debug, Default)]
+pub(crate) struct OpQueue {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    holder: Holder,
+    /// Operations waiting for a turn, in no particular order.
+    waiters: Vec<Waiter>,
+    /// The ticket to give the next operation to ask for a turn.
+    next_ticket: u64,
+}
+
+#[derive(Debug, Default, PartialEq)]
+enum Holder {
+    /// No operation is running.
+    #[default]
+    Free,
+    /// An operation is running.
+    Running,
+    /// The turn was handed to the waiter with this ticket, which hasn't been
+    /// polled since.
+    Handed(u64),
+}
+
+#[derive(Debug)]
+struct Waiter {
+    rank: u8,
+    ticket: u64,
+    waker: Waker,
+}
+
+impl OpQueue {
+    /// Waits until no other operation is running on the file, then returns a
+    /// guard that lets the next one run when it drops.
+    pub(crate) fn turn(&self, priority: Priority) -> WaitTurn<'_> {
+        WaitTurn {
+            queue: self,
+            rank: rank(priority),
+            ticket: None,
+            done: false,
+        }
+    }
+
+    /// Gives the turn to the most urgent waiter, or frees it if there is none.
+    fn pass(&self) {
+        let mut state = self.state.lock().unwrap();
+        let next = state
+            .waiters
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, waiter)| (waiter.rank, waiter.ticket))
+            .map(|(i, _)| i);
+        match next {
+            Some(i) => {
+                let waiter = state.waiters.swap_remove(i);
+                state.holder = Holder::Handed(waiter.ticket);
+                drop(state);
+                waiter.waker.wake();
+            }
+            None => state.holder = Holder::Free,
+        }
+    }
+}
+
+/// The future returned by [`OpQueue::turn`].
+///
+/// Dropping it before it completes gives up its place in the queue.
+#[derive(Debug)]
+pub(crate) struct WaitTurn<'a> {
+    queue: &'a OpQueue,
+    rank: u8,
+    ticket: Option<u64>,
+    done: bool,
+}
+
+impl<'a> Future for WaitTurn<'a> {
+    type Output = Turn<'a>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Turn<'a>> {
+        let queue = self.queue;
+        let mut state = queue.state.lock().unwrap();
+        let ready = match self.ticket {
+            None => state.holder == Holder::Free && state.waiters.is_empty(),
+            Some(ticket) => state.holder == Holder::Handed(ticket),
+        };
+        if ready {
+            state.holder = Holder::Running;
+            drop(state);
+            self.done = true;
+            return Poll::Ready(Turn(queue));
+        }
+        match self.ticket {
+            Some(ticket) => {
+                let waiter = state
+                    .waiters
+                    .iter_mut()
+                    .find(|waiter| waiter.ticket == ticket)
+                    .expect("waiter is queued");
+                waiter.waker.clone_from(cx.waker());
+            }
+            None => {
+                let ticket = state.next_ticket;
+                state.next_ticket += 1;
+                state.waiters.push(Waiter {
+                    rank: self.rank,
+                    ticket,
+                    waker: cx.waker().clone(),
+                });
+                self.ticket = Some(ticket);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for WaitTurn<'_> {
+    fn drop(&mut self) {
+        let Some(ticket) = self.ticket else { return };
+        if self.done {
+            return;
+        }
+        let mut state = self.queue.state.lock().unwrap();
+        if state.holder == Holder::Handed(ticket) {
+            // The turn came just as this was cancelled; pass it on.
+            drop(state);
+            self.queue.pass();
+        } else {
+            state.waiters.retain(|waiter| waiter.ticket != ticket);
+        }
+    }
+}
+
+/// Lets the next queued operation run when dropped, whether the operation
+/// completed or its future was dropped.
+#[derive(Debug)]
+pub(crate) struct Turn<'a>(&'a OpQueue);
+
+impl Drop for Turn<'_> {
+    fn drop(&mut self) {
+        self.0.pass();
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    struct Flag(std::sync::atomic::AtomicBool);
+
+    impl Wake for Flag {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+
+    fn poll<'a>(wait: &mut Pin<Box<WaitTurn<'a>>>) -> Option<Turn<'a>> {
+        let waker = Waker::from(Arc::new(Flag(false.into())));
+        match wait.as_mut().poll(&mut Context::from_waker(&waker)) {
+            Poll::Ready(turn) => Some(turn),
+            Poll::Pending => None,
+        }
+    }
+
+    #[test]
+    fn test_priority_order() {
+        let queue = OpQueue::default();
+        let first = poll(&mut Box::pin(queue.turn(Priority::UserInitiated))).unwrap();
+        let mut background = Box::pin(queue.turn(Priority::Background));
+        let mut interactive = Box::pin(queue.turn(Priority::UserInteractive));
+        let mut cancelled = Box::pin(queue.turn(Priority::UserInteractive));
+        assert!(poll(&mut background).is_none());
+        assert!(poll(&mut interactive).is_none());
+        assert!(poll(&mut cancelled).is_none());
+        drop(cancelled);
+
+        drop(first);
+        assert!(poll(&mut background).is_none());
+        let second = poll(&mut interactive).unwrap();
+        drop(second);
+        let third = poll(&mut background).unwrap();
+        drop(third);
+        assert!(poll(&mut Box::pin(queue.turn(Priority::Background))).is_some());
+    }
+
+    #[test]
+    fn test_cancel_after_handoff() {
+        let queue = OpQueue::default();
+        let first = poll(&mut Box::pin(queue.turn(Priority::UserInitiated))).unwrap();
+        let mut second = Box::pin(queue.turn(Priority::UserInitiated));
+        let mut third = Box::pin(queue.turn(Priority::UserInitiated));
+        assert!(poll(&mut second).is_none());
+        assert!(poll(&mut third).is_none());
+        drop(first);
+        // The turn went to `second`, which is dropped before taking it.
+        drop(second);
+        assert!(poll(&mut third).is_some());
+    }
+}
@@ -0,0 +1,127 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Options for opening files.
+
+use crate::{Advice, FetchOptions, RetryPolicy};
+
+/// Options controlling how [`File::open_with`](crate::File::open_with) opens a
+/// file.
+///
+/// # Direct I/O
+///
+/// With [`direct`](OpenOptions::direct), reads bypass the operating system's
+/// page cache. Databases and other programs that cache data themselves use this
+/// to avoid keeping two copies in memory, and to keep one large scan from
+/// evicting everything else from the cache.
+///
+/// Direct I/O normally requires reads at block-aligned offsets, of
+/// block-aligned lengths, into block-aligned buffers. The crate handles this:
+/// reads of any size at any position work, and are widened to 4096-byte
+/// boundaries internally. Reads that start on a 4096-byte boundary return their
+/// buffer without copying, and that buffer is itself 4096-byte aligned.
+///
+/// Direct I/O is supported on Linux and Android (`O_DIRECT`), macOS and iOS
+/// (`F_NOCACHE`), and Windows (`FILE_FLAG_NO_BUFFERING`). Some filesystems,
+/// such as tmpfs, refuse it, and opening fails. On other native platforms,
+/// opening fails with an unsupported error. On WASM, where files are fetched
+/// over HTTP and there is no page cache to bypass, the option is ignored.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{File, OpenOptions, Priority};
+///
+/// let options = OpenOptions::new().direct(true);
+/// let file = File::open_with("table.db", &options, Priority::unit_test()).await?;
+/// let page = file.read(16 * 1024, Priority::unit_test()).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OpenOptions {
+    direct: bool,
+    advice: Advice,
+    fetch: FetchOptions,
+    retry: Option<RetryPolicy>,
+    coalesce: Option<bool>,
+}
+
+impl OpenOptions {
+    /// Creates options with default values: reads go through the page cache,
+    /// with no access-pattern advice.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether reads bypass the page cache.
+    pub fn direct(mut self, direct: bool) -> Self {
+        self.direct = direct;
+        self
+    }
+
+    /// Sets how the file will be read.
+    ///
+    /// On Windows this is the only way to give [`Advice::Sequential`] or
+    /// [`Advice::Random`], as flags to the open call. Elsewhere it is the same
+    /// as calling [`File::advise`](crate::File::advise) on the whole file after
+    /// opening, except that a failure to apply the advice is ignored.
+    pub fn advice(mut self, advice: Advice) -> Self {
+        self.advice = advice;
+        self
+    }
+
+    /// Sets options for the HTTP requests that read the file on WASM, applied
+    /// on top of those from [`set_fetch_options`](crate::set_fetch_options).
+    /// Ignored on native platforms.
+    pub fn fetch(mut self, fetch: FetchOptions) -> Self {
+        self.fetch = fetch;
+        self
+    }
+
+    /// Sets how operations on the file, and opening it, are retried when they
+    /// fail with a transient I/O error. Operations given their own policy,
+    /// such as with [`ReadOptions::retry`](crate::ReadOptions::retry), use
+    /// that instead.
+    ///
+    /// Ignored on WASM, where requests are retried as the
+    /// [`fetch`](OpenOptions::fetch) options say.
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Sets whether the file's reads are
+    /// [coalesced](crate::set_read_coalescing) with matching reads of other
+    /// files, in place of the process-wide setting.
+    pub fn coalesce(mut self, coalesce: bool) -> Self {
+        self.coalesce = Some(coalesce);
+        self
+    }
+
+    /// Returns whether reads bypass the page cache.
+    pub fn get_direct(&self) -> bool {
+        self.direct
+    }
+
+    /// Returns the configured advice.
+    pub fn get_advice(&self) -> Advice {
+        self.advice
+    }
+
+    /// Returns the configured fetch options.
+    pub fn get_fetch(&self) -> &FetchOptions {
+        &self.fetch
+    }
+
+    /// Returns the configured retry policy, if any.
+    pub fn get_retry(&self) -> Option<&RetryPolicy> {
+        self.retry.as_ref()
+    }
+
+    /// Returns whether reads are coalesced, if set rather than left to the
+    /// process-wide setting.
+    pub fn get_coalesce(&self) -> Option<bool> {
+        self.coalesce
+    }
+}
@@ -0,0 +1,242 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Hierarchical priorities with shared budgets.
+//!
+//! A single user request often fans out into many file operations. [`PriorityScope`]
+//! lets those operations be governed as a unit: they share one priority, and
+//! optionally an aggregate byte and time budget. Scopes nest, so a child scope's
+//! usage is also charged to every ancestor.
+
+use crate::{Data, Error, File, Metadata, Priority, sys};
+use std::io::SeekFrom;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// A priority shared by a group of operations, with optional byte and time budgets.
+///
+/// Operations issued through a scope run at the scope's priority. Reads are
+/// charged against the byte budget of the scope and all of its ancestors, and
+/// every operation checks the time budgets before it starts.
+///
+/// # Budgets
+///
+/// - **Byte budget**: [`read`](PriorityScope::read) is clamped to the remaining
+///   budget, so it returns a short read as the budget runs low. Once the budget
+///   is exhausted, reads fail with a budget error. [`read_all`](PriorityScope::read_all)
+///   fails up front if the whole file does not fit.
+/// - **Time budget**: measured from when the budget is set. Operations that
+///   would start after the deadline fail with a budget error. An operation that
+///   is already running is not interrupted.
+///
+/// # Sharing
+///
+/// Cloning a scope yields another handle to the same budget, so clones can be
+/// handed to concurrent tasks. Configure budgets before cloning.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{PriorityScope, Priority};
+/// use std::time::Duration;
+///
+/// // Everything done to serve this request shares a priority and a 1 MiB budget
+/// let scope = PriorityScope::new(Priority::highest_async())
+///     .with_byte_budget(1024 * 1024)
+///     .with_time_budget(Duration::from_secs(5));
+///
+/// let file = scope.open("/dev/zero").await?;
+/// let header = scope.read(&file, 512).await?;
+/// assert_eq!(scope.bytes_used(), 512);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PriorityScope {
+    priority: Priority,
+    byte_budget: Option<u64>,
+    deadline: Option<sys::Instant>,
+    used: Arc<AtomicU64>,
+    parent: Option<Box<PriorityScope>>,
+}
+
+impl PriorityScope {
+    /// Creates a root scope with the given priority and no budgets.
+    pub fn new(priority: Priority) -> Self {
+        PriorityScope {
+            priority,
+            byte_budget: None,
+            deadline: None,
+            used: Arc::new(AtomicU64::new(0)),
+            parent: None,
+        }
+    }
+
+    /// Limits the total number of bytes read through this scope and its children.
+    pub fn with_byte_budget(mut self, bytes: u64) -> Self {
+        self.byte_budget = Some(bytes);
+        self
+    }
+
+    /// Limits the wall-clock time during which operations may be started
+    /// through this scope and its children, measured from now.
+    pub fn with_time_budget(mut self, budget: Duration) -> Self {
+        self.deadline = Some(sys::Instant::now() + budget);
+        self
+    }
+
+    /// Creates a child scope.
+    ///
+    /// The child inherits this scope's priority. Bytes read through the child are
+    /// charged to the child and to every ancestor, and ancestors' time budgets
+    /// apply to the child. The child may be given tighter budgets of its own.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{PriorityScope, Priority};
+    ///
+    /// let request = PriorityScope::new(Priority::highest_async()).with_byte_budget(4096);
+    /// let thumbnail = request.child().with_byte_budget(1024);
+    ///
+    /// let file = thumbnail.open("/dev/zero").await?;
+    /// thumbnail.read(&file, 1024).await?;
+    /// assert_eq!(request.bytes_used(), 1024);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn child(&self) -> PriorityScope {
+        PriorityScope {
+            priority: self.priority,
+            byte_budget: None,
+            deadline: None,
+            used: Arc::new(AtomicU64::new(0)),
+            parent: Some(Box::new(self.clone())),
+        }
+    }
+
+    /// Returns the priority shared by operations in this scope.
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    /// Returns the number of bytes read through this scope and its children.
+    pub fn bytes_used(&self) -> u64 {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of bytes that may still be read through this scope,
+    /// taking ancestors into account, or `None` if no byte budget applies.
+    pub fn remaining_bytes(&self) -> Option<u64> {
+        let own = self
+            .byte_budget
+            .map(|budget| budget.saturating_sub(self.bytes_used()));
+        let inherited = self.parent.as_ref().and_then(|p| p.remaining_bytes());
+        match (own, inherited) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Returns `true` if a time budget on this scope or an ancestor has elapsed.
+    pub fn is_expired(&self) -> bool {
+        let own = self
+            .deadline
+            .is_some_and(|deadline| sys::Instant::now() >= deadline);
+        own || self.parent.as_ref().is_some_and(|p| p.is_expired())
+    }
+
+    /// Opens a file at this scope's priority.
+    ///
+    /// See [`File::open`].
+    pub async fn open(&self, path: impl AsRef<Path>) -> Result<File, Error> {
+        self.check_time()?;
+        File::open(path, self.priority).await
+    }
+
+    /// Reads up to `buf_size` bytes at this scope's priority, charging the bytes
+    /// read to the byte budget.
+    ///
+    /// The read is clamped to [`remaining_bytes`](PriorityScope::remaining_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns a budget error if the time budget has elapsed, or if the byte
+    /// budget is exhausted and `buf_size` is nonzero. See also [`File::read`].
+    pub async fn read(&self, file: &File, buf_size: usize) -> Result<Data, Error> {
+        self.check_time()?;
+        let buf_size = match self.remaining_bytes() {
+            Some(0) if buf_size > 0 => return Err(Error(sys::Error::BudgetExceeded)),
+            Some(remaining) => buf_size.min(remaining.try_into().unwrap_or(usize::MAX)),
+            None => buf_size,
+        };
+        let data = file.read(buf_size, self.priority).await?;
+        self.charge(data.len() as u64);
+        Ok(data)
+    }
+
+    /// Reads the entire file at this scope's priority, charging the bytes read
+    /// to the byte budget.
+    ///
+    /// # Errors
+    ///
+    /// Returns a budget error if the time budget has elapsed, or if the file is
+    /// larger than [`remaining_bytes`](PriorityScope::remaining_bytes). See also
+    /// [`File::read_all`].
+    pub async fn read_all(&self, file: &File) -> Result<Data, Error> {
+        self.check_time()?;
+        if let Some(remaining) = self.remaining_bytes() {
+            let len = file.metadata(self.priority).await?.len();
+            if len > remaining {
+                return Err(Error(sys::Error::BudgetExceeded));
+            }
+        }
+        let data = file.read_all(self.priority).await?;
+        self.charge(data.len() as u64);
+        Ok(data)
+    }
+
+    /// Seeks at this scope's priority.
+    ///
+    /// See [`File::seek`].
+    pub async fn seek(&self, file: &mut File, pos: SeekFrom) -> Result<u64, Error> {
+        self.check_time()?;
+        file.seek(pos, self.priority).await
+    }
+
+    /// Retrieves metadata at this scope's priority.
+    ///
+    /// See [`File::metadata`].
+    pub async fn metadata(&self, file: &File) -> Result<Metadata, Error> {
+        self.check_time()?;
+        file.metadata(self.priority).await
+    }
+
+    /// Tests whether a path exists at this scope's priority.
+    ///
+    /// Returns `false` if the time budget has elapsed. See [`exists`](crate::exists).
+    pub async fn exists(&self, path: impl AsRef<Path>) -> bool {
+        if self.is_expired() {
+            return false;
+        }
+        crate::exists(path, self.priority).await
+    }
+
+    fn check_time(&self) -> Result<(), Error> {
+        if self.is_expired() {
+            Err(Error(sys::Error::BudgetExceeded))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn charge(&self, bytes: u64) {
+        self.used.fetch_add(bytes, Ordering::Relaxed);
+        if let Some(parent) = &self.parent {
+            parent.charge(bytes);
+        }
+    }
+}
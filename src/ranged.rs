@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Range reads from named objects, for readers built on object stores.
+//!
+//! Columnar readers such as Arrow's Parquet reader fetch a file's footer and
+//! then the column chunks they need, each as a byte range of a named object.
+//! [`RangedRead`] is that interface, and [`PathStore`] implements it over
+//! [`read_once`](crate::read_once) and [`metadata`](crate::metadata), so the
+//! same reader works on native files and, on WASM, over HTTP. Adapting it to
+//! a particular reader's trait is a few lines of forwarding.
+
+use crate::{Data, Error, Priority};
+use std::future::Future;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+
+/// The future returned by [`RangedRead`]'s methods.
+pub type RangedFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'a>>;
+
+/// Reads byte ranges of objects by name.
+///
+/// The methods return boxed futures so that the trait can be used as
+/// `dyn RangedRead`, as object store clients usually are.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{PathStore, Priority, RangedRead};
+/// use std::path::Path;
+///
+/// async fn footer(store: &dyn RangedRead, name: &Path) -> Result<Vec<u8>, async_file::Error> {
+///     let size = store.size(name, Priority::unit_test()).await?;
+///     let footer = store
+///         .get_range(name, size.saturating_sub(8)..size, Priority::unit_test())
+///         .await?;
+///     Ok(footer.to_vec())
+/// }
+///
+/// let store = PathStore::new("data");
+/// let tail = footer(&store, Path::new("table.parquet")).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait RangedRead: Send + Sync {
+    /// Reads `range` of the object. The result is shorter than the range if
+    /// the object ends first, and empty if the range starts at or past the
+    /// end.
+    fn get_range<'a>(
+        &'a self,
+        location: &'a Path,
+        range: Range<u64>,
+        priority: Priority,
+    ) -> RangedFuture<'a, Data>;
+
+    /// Returns the object's size in bytes.
+    fn size<'a>(&'a self, location: &'a Path, priority: Priority) -> RangedFuture<'a, u64>;
+
+    /// Reads each of `ranges` of the object, returning them in order.
+    ///
+    /// The default reads them one after another with
+    /// [`get_range`](RangedRead::get_range).
+    fn get_ranges<'a>(
+        &'a self,
+        location: &'a Path,
+        ranges: &'a [Range<u64>],
+        priority: Priority,
+    ) -> RangedFuture<'a, Vec<Data>> {
+        Box::pin(async move {
+            let mut data = Vec::with_capacity(ranges.len());
+            for range in ranges {
+                data.push(self.get_range(location, range.clone(), priority).await?);
+            }
+            Ok(data)
+        })
+    }
+}
+
+/// A [`RangedRead`] whose objects are files under a root path.
+///
+/// On native platforms the root is a directory; on WASM it is a path
+/// relative to the origin, or a URL. Each range is a single
+/// [`read_once`](crate::read_once): one blocking call natively, and one
+/// ranged GET on WASM.
+#[derive(Debug, Clone)]
+pub struct PathStore {
+    root: PathBuf,
+}
+
+impl PathStore {
+    /// Creates a store whose object names are joined onto `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        PathStore { root: root.into() }
+    }
+
+    /// Returns the root that object names are joined onto.
+    pub fn get_root(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl RangedRead for PathStore {
+    fn get_range<'a>(
+        &'a self,
+        location: &'a Path,
+        range: Range<u64>,
+        priority: Priority,
+    ) -> RangedFuture<'a, Data> {
+        Box::pin(crate::read_once(self.root.join(location), range, priority))
+    }
+
+    fn size<'a>(&'a self, location: &'a Path, priority: Priority) -> RangedFuture<'a, u64> {
+        Box::pin(async move {
+            let metadata = crate::metadata(self.root.join(location), priority).await?;
+            Ok(metadata.len())
+        })
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::{PathStore, RangedRead};
+    use crate::Priority;
+    use std::path::Path;
+
+    #[test_executors::async_test]
+    async fn test_path_store() {
+        logwise::context::Context::reset("test_path_store".to_string());
+        let dir = crate::tests::temp_path("ranged");
+        std::fs::create_dir_all(&dir).unwrap();
+        let contents: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        std::fs::write(dir.join("object"), &contents).unwrap();
+
+        let store: &dyn RangedRead = &PathStore::new(&*dir);
+        let name = Path::new("object");
+        assert_eq!(store.size(name, Priority::unit_test()).await.unwrap(), 1000);
+        let data = store
+            .get_range(name, 10..20, Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(&*data, &contents[10..20]);
+        let ranges = store
+            .get_ranges(name, &[0..4, 990..1010, 2000..2010], Priority::unit_test())
+            .await
+            .unwrap();
+        assert_eq!(&*ranges[0], &contents[..4]);
+        assert_eq!(&*ranges[1], &contents[990..]);
+        assert!(ranges[2].is_empty());
+        assert!(
+            store
+                .size(Path::new("missing"), Priority::unit_test())
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! One-shot ranged reads.
+//!
+//! Opening a [`File`](crate::File), reading, and dropping it costs three trips
+//! to the backend: on WASM a HEAD request before the GET, and on native
+//! platforms separate blocking calls for the open and the read. [`read_once`]
+//! hands the whole sequence to the backend as one operation.
+
+use crate::{Data, Error, Priority, in_memory_len, memory, sys, throttle};
+use std::ops::{Bound, RangeBounds};
+use std::path::Path;
+
+/// Reads a byte range of a file in a single operation, without keeping it open.
+///
+/// `range` selects bytes by offset, as with slicing: `..` reads the whole file,
+/// `1024..` everything after the first KiB, and `0..512` a header. The result
+/// is shorter than the range if the file ends first, and empty if the range
+/// starts at or past the end.
+///
+/// On native platforms the open, read and close happen in one blocking call.
+/// On WASM this is a single ranged GET, with no HEAD request beforehand.
+///
+/// # Errors
+///
+/// Returns an error if the file does not exist or cannot be read, or if the
+/// range (or, for an open-ended range, the rest of the file) is too large to
+/// fit in memory.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{read_once, Priority};
+///
+/// // Read a 512-byte header without keeping the file open
+/// let header = read_once("assets/atlas.bin", 0..512, Priority::unit_test()).await?;
+///
+/// // Read a whole small file
+/// let config = read_once("config.toml", .., Priority::unit_test()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn read_once(
+    path: impl AsRef<Path>,
+    range: impl RangeBounds<u64>,
+    priority: Priority,
+) -> Result<Data, Error> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => Some(end.saturating_add(1)),
+        Bound::Excluded(&end) => Some(end),
+        Bound::Unbounded => None,
+    };
+    let len = match end {
+        Some(end) => Some(in_memory_len(end.saturating_sub(start))?),
+        None => None,
+    };
+    if len == Some(0) {
+        return Ok(Data::from_boxed_slice(Box::new([])));
+    }
+    let lease = memory::Lease::admit(len.unwrap_or(0)).await;
+    throttle::admit(priority).await;
+    let data = sys::read_once(path, start, len, priority)
+        .await
+        .map_err(Error)?;
+    throttle::charge(priority, data.len() as u64);
+    let lease = lease.resize(data.len());
+    Ok(Data::from_sys(data, lease))
+}
@@ -0,0 +1,253 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tuning knobs for whole-file reads.
+
+use crate::{RetryPolicy, sys};
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+type ProgressFn = dyn Fn(u64, Option<u64>) + Send + Sync;
+
+/// Options controlling how [`File::read_all_with`](crate::File::read_all_with)
+/// reads a file.
+///
+/// # Chunking
+///
+/// Whole-file reads are split into chunks of at most
+/// [`chunk_size`](ReadOptions::chunk_size) bytes, with a yield to the executor
+/// between chunks. This keeps a single huge read from occupying one pool thread
+/// (or one giant HTTP request on WASM) for its entire duration, and gives
+/// cancellation a chance to take effect between chunks.
+///
+/// Smaller chunks mean more cancellation points and fairer sharing with other
+/// operations; larger chunks mean fewer round trips.
+///
+/// # Memory Mapping
+///
+/// With a [`map_threshold`](ReadOptions::map_threshold), regular files at least
+/// that large are memory-mapped on native platforms instead of read: the
+/// returned [`Data`](crate::Data) refers to the file's pages in the page cache,
+/// which are loaded as they are touched. Multi-gigabyte files are "read"
+/// immediately, and peak memory use no longer includes a private copy of the
+/// whole file. Mapped bytes are not counted by
+/// [`memory_in_use`](crate::memory_in_use).
+///
+/// Mapping is off by default, and turning it on is `unsafe`, because the
+/// `Data` is then only sound while nothing else changes the file:
+///
+/// - If another process modifies the file while the `Data` is alive, the
+///   bytes change under a shared reference.
+/// - If another process truncates the file, touching the bytes past the new end
+///   crashes the process (`SIGBUS` on Unix).
+///
+/// Files opened for direct I/O are never mapped, and on WASM the threshold is
+/// ignored.
+///
+/// # Progress
+///
+/// The [`on_progress`](ReadOptions::on_progress) callback is called as bytes
+/// arrive, with the number read so far and the number expected in total, if
+/// known. On native platforms it is called after each chunk. On WASM it is also
+/// called for each piece of a response body as it streams in, so even a
+/// single-chunk download reports progress. Counts only increase; a mapped file
+/// reports once, with everything.
+///
+/// # Deadlines
+///
+/// A [`deadline`](ReadOptions::deadline) says when the bytes are needed, for
+/// example by the frame that will draw them. On native platforms, each of the
+/// read's calls waiting for a worker thread moves up to
+/// [`Priority::UserInitiated`](crate::Priority::UserInitiated)'s place in the
+/// queue within a second of the deadline, and to
+/// [`Priority::UserInteractive`](crate::Priority::UserInteractive)'s within
+/// 100 ms, ahead of calls at those priorities without a deadline; calls
+/// with deadlines go earliest first. The priority passed to the read still
+/// sets its place until then, its I/O priority and which
+/// [`Config`](crate::Config) limit it counts against. Missing the deadline
+/// doesn't fail the read. On WASM, where reads are HTTP requests, the deadline
+/// has no effect.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{File, Priority, ReadOptions};
+///
+/// let file = File::open("large_asset.bin", Priority::unit_test()).await?;
+/// let options = ReadOptions::new()
+///     .chunk_size(1024 * 1024)
+///     .on_progress(|read, total| match total {
+///         Some(total) => println!("{read} of {total} bytes"),
+///         None => println!("{read} bytes"),
+///     });
+/// let contents = file.read_all_with(&options, Priority::unit_test()).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ReadOptions {
+    chunk_size: usize,
+    map_threshold: Option<u64>,
+    progress: Option<Arc<ProgressFn>>,
+    retry: Option<RetryPolicy>,
+    deadline: Option<sys::Instant>,
+}
+
+impl ReadOptions {
+    /// The default chunk size for whole-file reads: 4 MiB.
+    pub const DEFAULT_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+    /// Creates options with default values.
+    pub fn new() -> Self {
+        ReadOptions {
+            chunk_size: Self::DEFAULT_CHUNK_SIZE,
+            map_threshold: None,
+            progress: None,
+            retry: None,
+            deadline: None,
+        }
+    }
+
+    /// Sets the maximum number of bytes requested from the backend at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must be nonzero");
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// Sets the size, in bytes, from which regular files are memory-mapped
+    /// instead of read, or turns mapping off with `None`, the default.
+    ///
+    /// See [Memory Mapping](ReadOptions#memory-mapping) for the trade-offs.
+    ///
+    /// # Safety
+    ///
+    /// With a threshold set, the caller must ensure that nothing, in this
+    /// process or another, modifies or truncates a mapped file for as long as
+    /// the [`Data`](crate::Data) read from it (or any clone of it) is alive.
+    pub unsafe fn map_threshold(mut self, threshold: Option<u64>) -> Self {
+        self.map_threshold = threshold;
+        self
+    }
+
+    /// Sets a callback invoked as the read makes progress, with the bytes read
+    /// so far and the total expected, if known.
+    pub fn on_progress(
+        mut self,
+        progress: impl Fn(u64, Option<u64>) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    /// Sets how the read is retried when it fails with a transient I/O error,
+    /// in place of the file's [`OpenOptions::retry`](crate::OpenOptions::retry)
+    /// policy. Ignored on WASM.
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Sets when the read is needed by, so that it moves ahead of other work
+    /// as the time nears. See [Deadlines](ReadOptions#deadlines).
+    ///
+    /// This is a `std::time::Instant` natively and a `web_time::Instant` on
+    /// WASM.
+    pub fn deadline(mut self, deadline: sys::Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Returns a reporter for a read of `total` bytes, if there is a
+    /// progress callback.
+    pub(crate) fn reporter(&self, total: Option<u64>) -> Option<Arc<Reporter>> {
+        self.progress.as_ref().map(|progress| {
+            Arc::new(Reporter {
+                progress: Arc::clone(progress),
+                total,
+                reported: AtomicU64::new(0),
+            })
+        })
+    }
+
+    /// Returns the configured chunk size.
+    pub fn get_chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Returns the configured map threshold.
+    pub fn get_map_threshold(&self) -> Option<u64> {
+        self.map_threshold
+    }
+
+    /// Returns the configured retry policy, if any.
+    pub fn get_retry(&self) -> Option<&RetryPolicy> {
+        self.retry.as_ref()
+    }
+
+    /// Returns the configured deadline, if any.
+    pub fn get_deadline(&self) -> Option<sys::Instant> {
+        self.deadline
+    }
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for ReadOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadOptions")
+            .field("chunk_size", &self.chunk_size)
+            .field("map_threshold", &self.map_threshold)
+            .field("progress", &self.progress.is_some())
+            .field("retry", &self.retry)
+            .field("deadline", &self.deadline)
+            .finish()
+    }
+}
+
+/// Passes one read's progress to an [`on_progress`](ReadOptions::on_progress)
+/// callback, dropping counts that don't increase.
+pub(crate) struct Reporter {
+    progress: Arc<ProgressFn>,
+    total: Option<u64>,
+    reported: AtomicU64,
+}
+
+impl Reporter {
+    /// Reports that `read` bytes have been read in total.
+    pub(crate) fn report(&self, read: u64) {
+        if self.reported.fetch_max(read, Ordering::Relaxed) < read {
+            (self.progress)(read, self.total);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReadOptions;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_reporter_only_increases() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let record = Arc::clone(&seen);
+        let options = ReadOptions::new().on_progress(move |read, total| {
+            record.lock().unwrap().push((read, total));
+        });
+        let reporter = options.reporter(Some(10)).unwrap();
+        for read in [0, 4, 4, 3, 10] {
+            reporter.report(read);
+        }
+        assert_eq!(*seen.lock().unwrap(), [(4, Some(10)), (10, Some(10))]);
+        assert!(ReadOptions::new().reporter(None).is_none());
+    }
+}
@@ -0,0 +1,169 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Recursive directory removal.
+//!
+//! Unlike `std::fs::remove_dir_all`, the tree is removed one entry per blocking
+//! call, so large deletions can report progress and be cancelled part way by
+//! dropping the future.
+
+use crate::{Error, Priority, sys};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+type ProgressFn = dyn Fn(&Path, u64) + Send + Sync;
+
+/// Options controlling [`remove_dir_all_with`].
+///
+/// # Progress
+///
+/// The [`on_progress`](RemoveDirOptions::on_progress) callback is called after
+/// each entry is removed, with the entry's path and the number of entries
+/// removed so far, including that one.
+///
+/// # Dry runs
+///
+/// With [`dry_run`](RemoveDirOptions::dry_run) set, the tree is walked and every
+/// entry is reported to the progress callback in the order it would be removed,
+/// but nothing is deleted.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{remove_dir_all_with, Priority, RemoveDirOptions};
+///
+/// let options = RemoveDirOptions::new().on_progress(|path, removed| {
+///     println!("{removed}: removed {}", path.display());
+/// });
+/// remove_dir_all_with("cache", &options, Priority::unit_test()).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct RemoveDirOptions {
+    dry_run: bool,
+    progress: Option<Arc<ProgressFn>>,
+}
+
+impl RemoveDirOptions {
+    /// Creates options with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether to report what would be removed without removing anything.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Returns whether this is a dry run.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Sets a callback invoked after each entry is removed.
+    pub fn on_progress(mut self, progress: impl Fn(&Path, u64) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+}
+
+impl fmt::Debug for RemoveDirOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoveDirOptions")
+            .field("dry_run", &self.dry_run)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+/// Removes a directory and everything inside it.
+///
+/// Entries are removed one at a time on the worker pool: files first, then
+/// each directory once it is empty. Symlinks are removed, not followed.
+///
+/// # Windows
+///
+/// Read-only files and directories are made writable so they can be removed.
+/// Sharing violations, which scanners and indexers cause routinely, are retried
+/// with a short backoff. Directory symlinks and junctions are removed without
+/// descending into their targets.
+///
+/// This is [`remove_dir_all_with`] with default options.
+///
+/// # Arguments
+///
+/// * `path` - The directory to remove
+/// * `priority` - The priority for this operation
+///
+/// # Errors
+///
+/// Returns the first error encountered; entries removed before it stay removed.
+/// Fails if `path` is not a directory. On WASM, where files are served over
+/// HTTP, this always fails.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{remove_dir_all, Priority};
+///
+/// remove_dir_all("build/tmp", Priority::unit_test()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn remove_dir_all(path: impl AsRef<Path>, priority: Priority) -> Result<(), Error> {
+    remove_dir_all_with(path, &RemoveDirOptions::default(), priority)
+        .await
+        .map(|_| ())
+}
+
+/// Removes a directory and everything inside it, with the given options.
+///
+/// Returns the number of entries removed, including `path` itself, or the
+/// number that would have been removed for a dry run.
+///
+/// See [`remove_dir_all`] and [`RemoveDirOptions`].
+pub async fn remove_dir_all_with(
+    path: impl AsRef<Path>,
+    options: &RemoveDirOptions,
+    priority: Priority,
+) -> Result<u64, Error> {
+    let mut removed = 0;
+    let mut report = |path: &Path| {
+        removed += 1;
+        if let Some(progress) = &options.progress {
+            progress(path, removed);
+        }
+    };
+    // Directories are pushed twice: once to list them, and once more, below
+    // their children, to remove them after the children are gone.
+    let mut pending: Vec<(PathBuf, bool)> = vec![(path.as_ref().to_owned(), false)];
+    while let Some((dir, listed)) = pending.pop() {
+        if listed {
+            if !options.dry_run {
+                sys::remove_tree_dir(&dir, priority).await.map_err(Error)?;
+            }
+            report(&dir);
+            continue;
+        }
+        let mut entries = sys::read_dir(&dir, priority).await.map_err(Error)?;
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        pending.push((dir, true));
+        for entry in entries {
+            if entry.is_dir {
+                pending.push((entry.path, false));
+            } else {
+                if !options.dry_run {
+                    sys::remove_tree_file(&entry.path, priority)
+                        .await
+                        .map_err(Error)?;
+                }
+                report(&entry.path);
+            }
+        }
+    }
+    Ok(removed)
+}
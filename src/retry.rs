@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Retrying native operations that fail with transient I/O errors.
+
+use crate::{RetryPolicy, sys};
+use std::future::Future;
+
+/// Runs `op`, running it again after a delay each time it fails with an I/O
+/// error that `policy` retries, until it succeeds or the tries run out.
+pub(crate) async fn retrying<T, Fut>(
+    policy: Option<&RetryPolicy>,
+    mut op: impl FnMut() -> Fut,
+) -> Result<T, sys::Error>
+where
+    Fut: Future<Output = Result<T, sys::Error>>,
+{
+    let mut attempt = 0;
+    loop {
+        let error = match op().await {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+        let Some(delay) = policy.and_then(|policy| delay_before(policy, &error, attempt + 1))
+        else {
+            return Err(error);
+        };
+        logwise::debuginternal_sync!(
+            "Retrying after {error} in {delay}",
+            error = logwise::privacy::LogIt(&error),
+            delay = logwise::privacy::LogIt(delay)
+        );
+        attempt += 1;
+        sys::delay(delay).await;
+    }
+}
+
+/// Returns the delay before try number `attempt` after `error`, or `None` if
+/// `error` isn't retried or there are no tries left.
+fn delay_before(
+    policy: &RetryPolicy,
+    error: &sys::Error,
+    attempt: u32,
+) -> Option<std::time::Duration> {
+    let kind = error.io_kind()?;
+    if !policy.get_error_kinds().contains(&kind) {
+        return None;
+    }
+    policy.delay_before(attempt)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::retrying;
+    use crate::{RetryPolicy, sys};
+    use std::io::ErrorKind;
+    use std::time::Duration;
+
+    fn failing(kind: ErrorKind) -> Result<(), sys::Error> {
+        Err(sys::Error::Io(kind.into()))
+    }
+
+    #[test_executors::async_test]
+    async fn test_retries_transient_errors() {
+        let policy = RetryPolicy::new()
+            .attempts(4)
+            .backoff(Duration::from_millis(1));
+        let mut tries = 0;
+        let result = retrying(Some(&policy), || {
+            tries += 1;
+            let result = if tries < 3 {
+                failing(ErrorKind::ResourceBusy)
+            } else {
+                Ok(())
+            };
+            async move { result }
+        })
+        .await;
+        assert!(result.is_ok());
+        assert_eq!(tries, 3);
+
+        let mut tries = 0;
+        let result = retrying(Some(&policy), || {
+            tries += 1;
+            async { failing(ErrorKind::WouldBlock) }
+        })
+        .await;
+        assert_eq!(result.unwrap_err().io_kind(), Some(ErrorKind::WouldBlock));
+        assert_eq!(tries, 4);
+    }
+
+    #[test_executors::async_test]
+    async fn test_does_not_retry_other_errors() {
+        let policy = RetryPolicy::new().backoff(Duration::from_millis(1));
+        let mut tries = 0;
+        let result = retrying(Some(&policy), || {
+            tries += 1;
+            async { failing(ErrorKind::NotFound) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(tries, 1);
+
+        let mut tries = 0;
+        let result = retrying(None, || {
+            tries += 1;
+            async { failing(ErrorKind::ResourceBusy) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(tries, 1);
+    }
+}
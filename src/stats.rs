@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! A snapshot of the scheduler's load.
+
+use crate::{BANDS, Priority, rank, sys};
+use std::time::Duration;
+
+/// The load on one priority band of the worker queue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct BandStats {
+    pub(crate) queued: usize,
+    pub(crate) in_flight: usize,
+    pub(crate) wait: Option<Duration>,
+    pub(crate) service: Option<Duration>,
+}
+
+/// The load on the native worker queue, for each priority, as reported by
+/// [`stats`].
+///
+/// The figures are a snapshot; calls start and finish at any time.
+/// [`Priority::Utility`] and [`Priority::Unknown`] share one set of figures,
+/// as they share a place in the queue. Latencies are moving averages that
+/// favor the most recent calls, so they follow changes in load within a few
+/// dozen calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SchedulerStats {
+    /// Indexed by rank.
+    pub(crate) bands: [BandStats; BANDS],
+}
+
+impl SchedulerStats {
+    fn band(&self, priority: Priority) -> &BandStats {
+        &self.bands[rank(priority) as usize]
+    }
+
+    /// Returns how many calls at `priority` are waiting for a worker thread.
+    pub fn queued(&self, priority: Priority) -> usize {
+        self.band(priority).queued
+    }
+
+    /// Returns how many calls at `priority` are running.
+    pub fn in_flight(&self, priority: Priority) -> usize {
+        self.band(priority).in_flight
+    }
+
+    /// Returns how long recent calls at `priority` waited in the queue before
+    /// starting, or `None` if none has started yet.
+    pub fn wait(&self, priority: Priority) -> Option<Duration> {
+        self.band(priority).wait
+    }
+
+    /// Returns how long recent calls at `priority` took to run once started,
+    /// or `None` if none has finished yet.
+    pub fn service(&self, priority: Priority) -> Option<Duration> {
+        self.band(priority).service
+    }
+}
+
+/// Reports the load on the native worker queue, for each priority.
+///
+/// Applications can use this to adapt to I/O pressure: shedding optional
+/// work while background calls back up, or raising the priority of reads
+/// whose waits grow too long. On WASM, where operations are HTTP requests,
+/// every figure is zero or `None`.
+///
+/// # Examples
+///
+/// ```
+/// use async_file::{Priority, stats};
+/// use std::time::Duration;
+///
+/// let stats = stats();
+/// let slow = stats
+///     .wait(Priority::Background)
+///     .is_some_and(|wait| wait > Duration::from_secs(1));
+/// if slow || stats.queued(Priority::Background) > 1000 {
+///     println!("skipping thumbnail generation for now");
+/// }
+/// ```
+pub fn stats() -> SchedulerStats {
+    SchedulerStats {
+        bands: sys::scheduler_stats(),
+    }
+}
@@ -3,8 +3,8 @@
 //! Standard library implementation of async file I/O operations.
 //!
 //! This module provides the default implementation of `async_file`'s file operations
-//! for non-WASM platforms. It uses the `blocking` crate to convert synchronous
-//! standard library file operations into asynchronous operations.
+//! for non-WASM platforms. It runs synchronous standard library file operations
+//! on a pool of worker threads, queued in priority order (see `pool`).
 //!
 //! # Architecture
 //!
@@ -12,7 +12,7 @@
 //! async operations. Each async operation:
 //!
 //! 1. Clones the `Arc<std::fs::File>` to get an owned handle
-//! 2. Uses `blocking::unblock` to run the sync operation in a thread pool
+//! 2. Uses `pool::unblock` to run the sync operation on a worker thread
 //! 3. Returns the result wrapped in platform-agnostic types
 //!
 //! # Performance Considerations
@@ -66,13 +66,33 @@
 //! # test_executors::spin_on(example()).unwrap();
 //! ```
 
+mod aligned;
+mod buffers;
+mod io_priority;
+mod mmap;
+mod pool;
+mod probe;
+mod timer;
+mod xattr;
+
 use crate::Priority;
-use blocking::unblock;
+use pool::unblock;
 use std::io::Read;
 use std::io::Seek;
+use std::io::Write;
 use std::ops::Deref;
 use std::path::Path;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub use std::time::Instant;
+
+/// The largest read issued in a single blocking call.
+const READ_SLICE_SIZE: usize = 8 * 1024 * 1024;
+
+/// The offset, length and buffer alignment direct I/O requires. Devices with
+/// 512-byte sectors accept it too.
+const DIRECT_ALIGN: usize = 4096;
 
 /// A file handle for asynchronous I/O operations.
 ///
@@ -89,10 +109,20 @@ use std::sync::Arc;
 ///
 /// # Thread Safety
 ///
-/// While the `Arc` allows multiple references, the main `async_file` API ensures
-/// only one operation is in-flight at a time per file handle, preventing race
+/// While the `Arc` allows multiple references, the main `async_file` API queues
+/// operations so only one is in-flight at a time per file handle, preventing race
 /// conditions on the file position.
 ///
+/// # Cancellation
+///
+/// The file position is kept here rather than in the operating system's file
+/// cursor. Reads are positional (`pread` on Unix, `seek_read` on Windows) from
+/// that position, and it only advances once the read's future returns the
+/// bytes. So dropping a read future always leaves the position where it was,
+/// whether the read was still queued, in the middle of a blocking call (which
+/// runs to completion on its worker, its bytes discarded), or between the
+/// slices of a large read.
+///
 /// # Example
 ///
 /// ```
@@ -109,7 +139,19 @@ use std::sync::Arc;
 /// # test_executors::spin_on(example()).unwrap();
 /// ```
 #[derive(Debug)]
-pub struct File(Arc<std::fs::File>);
+pub struct File {
+    file: Arc<std::fs::File>,
+    direct: Direct,
+    /// The position the next read starts from.
+    pos: AtomicU64,
+}
+
+/// Whether a [`File`] was opened for direct I/O, whose reads must be aligned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direct {
+    No,
+    Yes,
+}
 
 /// Error type for file operations in the standard library implementation.
 ///
@@ -119,6 +161,13 @@ pub struct File(Arc<std::fs::File>);
 /// # Variants
 ///
 /// - `Io`: Wraps a standard library I/O error
+/// - `BudgetExceeded`: A [`PriorityScope`](crate::PriorityScope) budget was exhausted
+/// - `InvalidUtf8`: Bytes read for a text operation were not valid UTF-8
+/// - `FilesystemLoop`: A directory walk following symlinks reached a directory it had already visited
+/// - `InvalidCacheKey`: A [`CacheDir`](crate::CacheDir) key was empty or too long
+/// - `TooLarge`: A file was too large to read into memory on this target
+/// - `Json`: A JSON document, or a line of newline-delimited JSON, could not be deserialized (`serde` feature)
+/// - `Toml`: A TOML document could not be deserialized (`toml` feature)
 ///
 /// # Non-exhaustive
 ///
@@ -144,6 +193,97 @@ pub struct File(Arc<std::fs::File>);
 pub enum Error {
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
+    #[error("priority scope budget exceeded")]
+    BudgetExceeded,
+    #[error("invalid UTF-8: {0}")]
+    InvalidUtf8(#[from] std::str::Utf8Error),
+    #[error("filesystem loop")]
+    FilesystemLoop,
+    #[error("invalid cache key")]
+    InvalidCacheKey,
+    #[error("file of {0} bytes is too large to read into memory")]
+    TooLarge(u64),
+    #[cfg(feature = "serde")]
+    #[error("invalid JSON on line {line}: {source}")]
+    Json {
+        line: u64,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[cfg(feature = "toml")]
+    #[error("invalid TOML on line {line}: {}", source.message())]
+    Toml {
+        line: u64,
+        #[source]
+        source: Box<toml::de::Error>,
+    },
+}
+
+impl Error {
+    /// Whether the path (or a parent) did not exist.
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Error::Io(e) if e.kind() == std::io::ErrorKind::NotFound)
+    }
+
+    /// Whether the operation timed out.
+    pub fn is_timed_out(&self) -> bool {
+        matches!(self, Error::Io(e) if e.kind() == std::io::ErrorKind::TimedOut)
+    }
+
+    /// Classifies the error by its I/O error kind.
+    pub fn kind(&self) -> crate::ErrorKind {
+        use std::io::ErrorKind as Io;
+        match self.io_kind() {
+            Some(Io::NotFound) => crate::ErrorKind::NotFound,
+            Some(Io::PermissionDenied) => crate::ErrorKind::PermissionDenied,
+            Some(Io::TimedOut) => crate::ErrorKind::TimedOut,
+            Some(Io::Interrupted) => crate::ErrorKind::Interrupted,
+            _ => crate::ErrorKind::Other,
+        }
+    }
+
+    /// Unwraps the I/O error this is, if it is one.
+    pub fn into_io(self) -> Result<std::io::Error, Self> {
+        match self {
+            Error::Io(e) => Ok(e),
+            other => Err(other),
+        }
+    }
+
+    /// The I/O error kind closest to this error, which is not an I/O error.
+    pub fn closest_io_kind(&self) -> std::io::ErrorKind {
+        use std::io::ErrorKind as Io;
+        match self {
+            Error::Io(e) => e.kind(),
+            Error::InvalidUtf8(_) => Io::InvalidData,
+            #[cfg(feature = "serde")]
+            Error::Json { .. } => Io::InvalidData,
+            #[cfg(feature = "toml")]
+            Error::Toml { .. } => Io::InvalidData,
+            Error::InvalidCacheKey => Io::InvalidInput,
+            Error::TooLarge(_) => Io::OutOfMemory,
+            Error::BudgetExceeded | Error::FilesystemLoop => Io::Other,
+        }
+    }
+
+    /// The kind of I/O error this is, if it is one.
+    pub fn io_kind(&self) -> Option<std::io::ErrorKind> {
+        match self {
+            Error::Io(e) => Some(e.kind()),
+            _ => None,
+        }
+    }
+
+    /// The error for an operation cut short by a timeout.
+    pub fn timed_out() -> Self {
+        Error::Io(std::io::ErrorKind::TimedOut.into())
+    }
+
+    /// Local files are read as they are; a change between reads is not
+    /// detected, so this is always false.
+    pub fn is_changed(&self) -> bool {
+        false
+    }
 }
 
 /// A buffer containing data read from a file.
@@ -188,7 +328,30 @@ pub enum Error {
 /// # test_executors::spin_on(example()).unwrap();
 /// ```
 #[derive(Debug)]
-pub struct Data(Box<[u8]>);
+pub struct Data(Bytes);
+
+/// Where the bytes of a [`Data`] live.
+#[derive(Debug)]
+enum Bytes {
+    /// Returned to the buffer pool on drop.
+    Vec(Vec<u8>),
+    /// Read by direct I/O into a block-aligned buffer.
+    Aligned(aligned::AlignedBuf),
+    /// A read-only memory map of the file.
+    Mapped(mmap::Mapping),
+}
+
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Bytes::Vec(bytes) => bytes,
+            Bytes::Aligned(bytes) => bytes,
+            Bytes::Mapped(bytes) => bytes,
+        }
+    }
+}
 
 /// File metadata information.
 ///
@@ -232,6 +395,35 @@ pub struct Data(Box<[u8]>);
 #[derive(Debug, Clone)]
 pub struct Metadata(std::fs::Metadata);
 
+/// File permissions, wrapping `std::fs::Permissions`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Permissions(std::fs::Permissions);
+
+impl Permissions {
+    pub fn readonly(&self) -> bool {
+        self.0.readonly()
+    }
+
+    pub fn set_readonly(&mut self, readonly: bool) {
+        self.0.set_readonly(readonly)
+    }
+
+    #[cfg(unix)]
+    pub fn mode(&self) -> u32 {
+        std::os::unix::fs::PermissionsExt::mode(&self.0)
+    }
+
+    #[cfg(unix)]
+    pub fn set_mode(&mut self, mode: u32) {
+        std::os::unix::fs::PermissionsExt::set_mode(&mut self.0, mode)
+    }
+
+    #[cfg(unix)]
+    pub fn from_mode(mode: u32) -> Self {
+        Permissions(std::os::unix::fs::PermissionsExt::from_mode(mode))
+    }
+}
+
 impl Metadata {
     /// Returns the size of the file in bytes.
     ///
@@ -254,6 +446,40 @@ impl Metadata {
     pub fn len(&self) -> u64 {
         self.0.len()
     }
+
+    #[cfg(unix)]
+    pub fn as_std(&self) -> &std::fs::Metadata {
+        &self.0
+    }
+
+    pub fn permissions(&self) -> Permissions {
+        Permissions(self.0.permissions())
+    }
+
+    pub fn file_type(&self) -> crate::FileType {
+        let file_type = self.0.file_type();
+        if file_type.is_symlink() || is_junction(&file_type) {
+            crate::FileType::Symlink
+        } else if file_type.is_dir() {
+            crate::FileType::Dir
+        } else if file_type.is_file() {
+            crate::FileType::File
+        } else {
+            crate::FileType::Other
+        }
+    }
+
+    pub fn modified(&self) -> Result<std::time::SystemTime, Error> {
+        self.0.modified().map_err(Error::Io)
+    }
+
+    pub fn accessed(&self) -> Result<std::time::SystemTime, Error> {
+        self.0.accessed().map_err(Error::Io)
+    }
+
+    pub fn created(&self) -> Result<std::time::SystemTime, Error> {
+        self.0.created().map_err(Error::Io)
+    }
 }
 
 impl AsRef<[u8]> for Data {
@@ -345,78 +571,424 @@ impl Data {
     /// # }
     /// # test_executors::spin_on(example()).unwrap();
     /// ```
-    pub fn into_boxed_slice(self) -> Box<[u8]> {
-        self.0
+    pub fn into_boxed_slice(mut self) -> Box<[u8]> {
+        match &mut self.0 {
+            Bytes::Vec(bytes) => std::mem::take(bytes).into_boxed_slice(),
+            // A Box must be freed with the alignment it was allocated with.
+            Bytes::Aligned(bytes) => Box::from(&**bytes),
+            Bytes::Mapped(bytes) => Box::from(&**bytes),
+        }
+    }
+
+    /// Wraps an existing boxed byte slice.
+    ///
+    /// Used by crate-level helpers that assemble a buffer from bytes that were
+    /// already read through this backend.
+    pub fn from_boxed_slice(slice: Box<[u8]>) -> Self {
+        Data(Bytes::Vec(slice.into_vec()))
+    }
+
+    /// Converts this `Data` into a vector, without copying if it was read
+    /// into one.
+    pub fn into_vec(self) -> Vec<u8> {
+        match self.take_vec() {
+            Ok(bytes) => bytes,
+            Err(data) => Vec::from(&*data.0),
+        }
+    }
+
+    /// Takes the allocation back for another read, if it can hold one.
+    fn take_vec(mut self) -> Result<Vec<u8>, Self> {
+        match &mut self.0 {
+            Bytes::Vec(bytes) => Ok(std::mem::take(bytes)),
+            Bytes::Aligned(_) | Bytes::Mapped(_) => Err(self),
+        }
+    }
+}
+
+impl Drop for Data {
+    fn drop(&mut self) {
+        if let Bytes::Vec(bytes) = &mut self.0 {
+            buffers::give(std::mem::take(bytes));
+        }
     }
 }
 
 impl File {
     fn new(file: std::fs::File) -> Self {
-        File(Arc::new(file))
+        File {
+            file: Arc::new(file),
+            direct: Direct::No,
+            pos: AtomicU64::new(0),
+        }
     }
-    pub async fn open(path: impl AsRef<Path>, _priority: Priority) -> Result<Self, Error> {
+    pub async fn open(path: impl AsRef<Path>, priority: Priority) -> Result<Self, Error> {
         logwise::perfwarn_begin_if!(
             logwise::Duration::from_millis(1),
             "async_file uses blocking on this platform"
         );
         let path = path.as_ref().to_owned();
-        unblock(|| std::fs::File::open(path))
+        unblock(priority, || std::fs::File::open(path))
             .await
             .map(File::new)
             .map_err(|e| e.into())
     }
 
-    pub async fn read(&self, buf_size: usize, _priority: Priority) -> Result<Data, Error> {
-        let mut move_file = self.0.clone();
+    pub async fn open_with(
+        path: impl AsRef<Path>,
+        options: &crate::OpenOptions,
+        priority: Priority,
+    ) -> Result<Self, Error> {
+        let (direct, advice) = (options.get_direct(), options.get_advice());
+        logwise::perfwarn_begin_if!(
+            logwise::Duration::from_millis(1),
+            "async_file uses blocking on this platform"
+        );
+        let path = path.as_ref().to_owned();
+        let file = unblock(priority, move || open_with_options(&path, direct, advice)).await?;
+        let direct = if direct { Direct::Yes } else { Direct::No };
+        Ok(File {
+            file: Arc::new(file),
+            direct,
+            pos: AtomicU64::new(0),
+        })
+    }
+
+    /// Wraps a file opened elsewhere, reading from its current position, or
+    /// from the start if it has none, as pipes don't.
+    pub fn from_std(mut file: std::fs::File) -> Self {
+        let pos = file.stream_position().unwrap_or(0);
+        let file = File::new(file);
+        file.pos.store(pos, Ordering::Relaxed);
+        file
+    }
+
+    /// Returns the file, its cursor moved to `pos`. If a dropped operation
+    /// still holds the file on a worker thread, the descriptor is duplicated.
+    pub fn into_std(self, pos: u64) -> std::io::Result<std::fs::File> {
+        let mut file = Arc::try_unwrap(self.file).or_else(|file| file.try_clone())?;
+        // Streams have no cursor to move.
+        match file.seek(std::io::SeekFrom::Start(pos)) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotSeekable => {}
+            result => {
+                result?;
+            }
+        }
+        Ok(file)
+    }
+
+    /// The open file, for calls made directly on its descriptor or handle.
+    #[cfg(any(unix, windows))]
+    pub fn as_std(&self) -> &std::fs::File {
+        &self.file
+    }
+
+    /// Duplicates the descriptor or handle, returning a file whose reads start
+    /// from `pos` and don't move this one's position.
+    pub async fn try_clone(&self, pos: u64, priority: Priority) -> Result<Self, Error> {
+        let move_file = self.file.clone();
+        logwise::perfwarn_begin_if!(
+            logwise::Duration::from_millis(1),
+            "async_file uses blocking on this platform"
+        );
+        let file = unblock(priority, move || move_file.try_clone()).await?;
+        Ok(File {
+            file: Arc::new(file),
+            direct: self.direct,
+            pos: AtomicU64::new(pos),
+        })
+    }
+
+    /// Writes all of `data` at the position the next read starts from, and
+    /// moves that position past it.
+    pub async fn write_all(&self, data: Data, priority: Priority) -> Result<(), Error> {
+        let pos = self.pos.load(Ordering::Relaxed);
+        let len = data.len() as u64;
+        let move_file = self.file.clone();
+        logwise::perfwarn_begin_if!(
+            logwise::Duration::from_millis(1),
+            "async_file uses blocking on this platform"
+        );
+        unblock(priority, move || {
+            io_priority::apply_to_file(&move_file, priority);
+            write_stream_at(&move_file, &data, pos)
+        })
+        .await?;
+        self.pos.store(pos + len, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// The position the next read starts from.
+    pub fn position(&self) -> u64 {
+        self.pos.load(Ordering::Relaxed)
+    }
+
+    /// Moves the position reads start from to `pos`.
+    pub fn set_position(&self, pos: u64) {
+        self.pos.store(pos, Ordering::Relaxed);
+    }
+
+    /// Does nothing: each read is a single blocking call, with nothing to
+    /// report until it returns.
+    pub fn set_progress(&self, _progress: Option<crate::ProgressHook>) {}
+
+    /// Reads up to `buf_size` bytes, into `recycled`'s allocation if it is
+    /// given and has room, or else into one from the buffer pool.
+    pub async fn read_into(
+        &self,
+        recycled: Option<Data>,
+        buf_size: usize,
+        priority: Priority,
+    ) -> Result<Data, Error> {
         logwise::perfwarn_begin_if!(
             logwise::Duration::from_millis(1),
             "async_file uses blocking on this platform"
         );
-        unblock(move || {
-            let mut buf = vec![0; buf_size];
-            let read = move_file.read(&mut buf);
-            match read {
+        let pos = self.pos.load(Ordering::Relaxed);
+        if self.direct == Direct::Yes {
+            // Direct reads need aligned buffers; `recycled` goes to the pool.
+            let move_file = self.file.clone();
+            let data = unblock(priority, move || {
+                io_priority::apply_to_file(&move_file, priority);
+                read_direct(&move_file, pos, buf_size)
+            })
+            .await?;
+            self.pos.store(pos + data.len() as u64, Ordering::Relaxed);
+            return Ok(data);
+        }
+        let mut buf = match recycled.and_then(|data| data.take_vec().ok()) {
+            Some(buf) if buf.capacity() >= buf_size => buf,
+            Some(small) => {
+                buffers::give(small);
+                buffers::take(buf_size)
+            }
+            None => buffers::take(buf_size),
+        };
+        // Bytes already in `buf` are initialized, so a recycled buffer only
+        // needs zeroing past its old length.
+        let mut filled = 0;
+        // Large reads are issued one slice per blocking call, so that no single
+        // call pins a pool thread for long and the read can be dropped between slices.
+        loop {
+            let first = filled == 0;
+            let want = (buf_size - filled).min(READ_SLICE_SIZE);
+            let move_file = self.file.clone();
+            let (returned, result) = unblock(priority, move || {
+                if first {
+                    io_priority::apply_to_file(&move_file, priority);
+                    if buf.capacity() < buf_size {
+                        buf.reserve_exact(buf_size - buf.len());
+                    }
+                }
+                if buf.len() < filled + want {
+                    buf.resize(filled + want, 0);
+                }
+                let result = read_stream_at(
+                    &move_file,
+                    &mut buf[filled..filled + want],
+                    pos + filled as u64,
+                );
+                (buf, result)
+            })
+            .await;
+            buf = returned;
+            match result {
                 Ok(read) => {
-                    buf.truncate(read);
-                    Ok(buf.into_boxed_slice())
+                    filled += read;
+                    if read < want || filled == buf_size {
+                        break;
+                    }
+                }
+                // Return the bytes from earlier slices; the error will
+                // surface again on the next read.
+                Err(_) if filled > 0 => break,
+                Err(e) => {
+                    buffers::give(buf);
+                    return Err(e.into());
                 }
-                Err(e) => Err(e),
             }
-        })
-        .await
-        .map(Data)
-        .map_err(|e| e.into())
+            crate::yield_now::yield_now().await;
+        }
+        buf.truncate(filled);
+        self.pos.store(pos + filled as u64, Ordering::Relaxed);
+        Ok(Data(Bytes::Vec(buf)))
     }
 
-    pub async fn seek(
-        &mut self,
-        pos: std::io::SeekFrom,
-        _priority: Priority,
-    ) -> Result<u64, Error> {
-        let mut move_file = self.0.clone();
+    /// Memory-maps the file from `offset` to its end and seeks to the end.
+    ///
+    /// Returns `None`, leaving the position alone, if there is nothing to map,
+    /// if the file was opened for direct I/O, or if this platform can't map
+    /// files.
+    pub async fn map(&self, offset: u64, priority: Priority) -> Result<Option<Data>, Error> {
+        if self.direct == Direct::Yes {
+            return Ok(None);
+        }
+        let move_file = self.file.clone();
         logwise::perfwarn_begin_if!(
             logwise::Duration::from_millis(1),
             "async_file uses blocking on this platform"
         );
-        unblock(move || {
-            let pos = move_file.seek(pos);
-            match pos {
-                Ok(pos) => Ok(pos),
-                Err(e) => Err(e),
+        let mapping = unblock(priority, move || mmap::map(&move_file, offset)).await?;
+        Ok(mapping.map(|mapping| {
+            self.pos
+                .store(offset + mapping.len() as u64, Ordering::Relaxed);
+            Data(Bytes::Mapped(mapping))
+        }))
+    }
+
+    /// Moves the position reads start from. Only [`SeekFrom::End`](std::io::SeekFrom::End)
+    /// needs the file's length, and so a blocking call.
+    pub async fn seek(&self, pos: std::io::SeekFrom, priority: Priority) -> Result<u64, Error> {
+        let (base, offset) = match pos {
+            std::io::SeekFrom::Start(pos) => {
+                self.pos.store(pos, Ordering::Relaxed);
+                return Ok(pos);
             }
+            std::io::SeekFrom::Current(offset) => (self.pos.load(Ordering::Relaxed), offset),
+            std::io::SeekFrom::End(offset) => {
+                let move_file = self.file.clone();
+                logwise::perfwarn_begin_if!(
+                    logwise::Duration::from_millis(1),
+                    "async_file uses blocking on this platform"
+                );
+                let len = unblock(priority, move || retry_interrupted(|| move_file.metadata()))
+                    .await?
+                    .len();
+                (len, offset)
+            }
+        };
+        // As with std, a position before the start (or past u64::MAX) is an error.
+        let pos = base.checked_add_signed(offset).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        self.pos.store(pos, Ordering::Relaxed);
+        Ok(pos)
+    }
+
+    /// Sets timestamps through the open handle. Windows handles opened for
+    /// reading can't change attributes, so there `path` is reopened instead.
+    pub async fn set_times(
+        &self,
+        path: &Path,
+        times: crate::FileTimes,
+        priority: Priority,
+    ) -> Result<(), Error> {
+        #[cfg(windows)]
+        return set_times(path, times, priority).await;
+        #[cfg(not(windows))]
+        {
+            let _ = path;
+            let move_file = self.file.clone();
+            logwise::perfwarn_begin_if!(
+                logwise::Duration::from_millis(1),
+                "async_file uses blocking on this platform"
+            );
+            unblock(priority, move || move_file.set_times(std_file_times(times)))
+                .await
+                .map_err(|e| e.into())
+        }
+    }
+
+    /// Runs an extended attribute call on the open handle. Only unix
+    /// exposes descriptor-based calls, so elsewhere `path` is used instead.
+    async fn xattr<R: Send + 'static>(
+        &self,
+        path: &Path,
+        priority: Priority,
+        op: impl FnOnce(&xattr::Target<'_>) -> std::io::Result<R> + Send + 'static,
+    ) -> Result<R, Error> {
+        #[cfg(not(unix))]
+        return xattr_path(path, priority, op).await;
+        #[cfg(unix)]
+        {
+            let _ = path;
+            let move_file = self.file.clone();
+            logwise::perfwarn_begin_if!(
+                logwise::Duration::from_millis(1),
+                "async_file uses blocking on this platform"
+            );
+            unblock(priority, move || {
+                use std::os::fd::AsRawFd;
+                op(&xattr::Target::Fd(move_file.as_raw_fd()))
+            })
+            .await
+            .map_err(|e| e.into())
+        }
+    }
+
+    pub async fn list_xattrs(
+        &self,
+        path: &Path,
+        priority: Priority,
+    ) -> Result<Vec<std::ffi::OsString>, Error> {
+        self.xattr(path, priority, xattr::list).await
+    }
+
+    pub async fn get_xattr(
+        &self,
+        path: &Path,
+        name: &std::ffi::OsStr,
+        priority: Priority,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let name = name.to_owned();
+        self.xattr(path, priority, move |target| xattr::get(target, &name))
+            .await
+    }
+
+    pub async fn set_xattr(
+        &self,
+        path: &Path,
+        name: &std::ffi::OsStr,
+        value: &[u8],
+        priority: Priority,
+    ) -> Result<(), Error> {
+        let (name, value) = (name.to_owned(), value.to_owned());
+        self.xattr(path, priority, move |target| {
+            xattr::set(target, &name, &value)
         })
         .await
-        .map_err(|e| e.into())
     }
 
-    pub async fn metadata(&self, _priority: Priority) -> Result<Metadata, Error> {
-        let move_file = self.0.clone();
+    pub async fn remove_xattr(
+        &self,
+        path: &Path,
+        name: &std::ffi::OsStr,
+        priority: Priority,
+    ) -> Result<(), Error> {
+        let name = name.to_owned();
+        self.xattr(path, priority, move |target| xattr::remove(target, &name))
+            .await
+    }
+
+    /// Gives `advice` for `len` bytes (or the rest of the file) from `offset`.
+    pub async fn advise(
+        &self,
+        advice: crate::Advice,
+        offset: u64,
+        len: Option<u64>,
+        priority: Priority,
+    ) -> Result<(), Error> {
+        let move_file = self.file.clone();
+        logwise::perfwarn_begin_if!(
+            logwise::Duration::from_millis(1),
+            "async_file uses blocking on this platform"
+        );
+        unblock(priority, move || advise(&move_file, advice, offset, len))
+            .await
+            .map_err(|e| e.into())
+    }
+
+    pub async fn metadata(&self, priority: Priority) -> Result<Metadata, Error> {
+        let move_file = self.file.clone();
         logwise::perfwarn_begin_if!(
             logwise::Duration::from_millis(1),
             "async_file uses blocking on this platform"
         );
 
-        unblock(move || {
+        unblock(priority, move || {
             let metadata = move_file.metadata();
             metadata.map(Metadata)
         })
@@ -453,50 +1025,1106 @@ impl PartialEq for Data {
     /// # test_executors::spin_on(example()).unwrap();
     /// ```
     fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
+        *self.0 == *other.0
+    }
+}
+
+/// A file opened for writing.
+///
+/// Used by crate-level helpers such as [`copy`](crate::copy) that need to write
+/// through the same worker pool as reads. Like [`File`], the handle is wrapped
+/// in an `Arc` so it can be moved into the pool for each operation.
+#[derive(Debug)]
+pub struct WriteFile(Arc<std::fs::File>);
+
+impl WriteFile {
+    /// Creates (or truncates) the file at `path` for writing.
+    pub async fn create(path: impl AsRef<Path>, priority: Priority) -> Result<Self, Error> {
+        let path = path.as_ref().to_owned();
+        logwise::perfwarn_begin_if!(
+            logwise::Duration::from_millis(1),
+            "async_file uses blocking on this platform"
+        );
+        unblock(priority, move || std::fs::File::create(path))
+            .await
+            .map(|file| WriteFile(Arc::new(file)))
+            .map_err(|e| e.into())
+    }
+
+    /// Writes all of `data` at the current position.
+    pub async fn write_all(&self, data: Data, priority: Priority) -> Result<(), Error> {
+        let mut move_file = self.0.clone();
+        logwise::perfwarn_begin_if!(
+            logwise::Duration::from_millis(1),
+            "async_file uses blocking on this platform"
+        );
+        unblock(priority, move || {
+            io_priority::apply_to_file(&move_file, priority);
+            move_file.write_all(&data)
+        })
+        .await
+        .map_err(|e| e.into())
+    }
+
+    /// Flushes the file's contents and metadata to the disk.
+    pub async fn sync_all(&self, priority: Priority) -> Result<(), Error> {
+        let move_file = self.0.clone();
+        logwise::perfwarn_begin_if!(
+            logwise::Duration::from_millis(1),
+            "async_file uses blocking on this platform"
+        );
+        unblock(priority, move || move_file.sync_all())
+            .await
+            .map_err(|e| e.into())
+    }
+}
+
+/// Flushes the entries of the directory at `path` to the disk, so a rename
+/// into it survives a crash.
+///
+/// Only Unix can open a directory to sync it; elsewhere this does nothing.
+pub async fn sync_dir(path: impl AsRef<Path>, priority: Priority) -> Result<(), Error> {
+    #[cfg(unix)]
+    {
+        let path = path.as_ref().to_owned();
+        logwise::perfwarn_begin_if!(
+            logwise::Duration::from_millis(1),
+            "async_file uses blocking on this platform"
+        );
+        unblock(priority, move || std::fs::File::open(path)?.sync_all())
+            .await
+            .map_err(|e| e.into())
     }
+    #[cfg(not(unix))]
+    {
+        let _ = (path, priority);
+        Ok(())
+    }
+}
+
+pub async fn rename(
+    from: impl AsRef<Path>,
+    to: impl AsRef<Path>,
+    priority: Priority,
+) -> Result<(), Error> {
+    let from = from.as_ref().to_owned();
+    let to = to.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || std::fs::rename(from, to))
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn hard_link(
+    original: impl AsRef<Path>,
+    link: impl AsRef<Path>,
+    priority: Priority,
+) -> Result<(), Error> {
+    let original = original.as_ref().to_owned();
+    let link = link.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || std::fs::hard_link(original, link))
+        .await
+        .map_err(|e| e.into())
+}
+
+#[cfg(unix)]
+pub async fn symlink(
+    original: impl AsRef<Path>,
+    link: impl AsRef<Path>,
+    priority: Priority,
+) -> Result<(), Error> {
+    let original = original.as_ref().to_owned();
+    let link = link.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || std::os::unix::fs::symlink(original, link))
+        .await
+        .map_err(|e| e.into())
+}
+
+#[cfg(windows)]
+pub async fn symlink_file(
+    original: impl AsRef<Path>,
+    link: impl AsRef<Path>,
+    priority: Priority,
+) -> Result<(), Error> {
+    let original = original.as_ref().to_owned();
+    let link = link.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || {
+        std::os::windows::fs::symlink_file(original, link)
+    })
+    .await
+    .map_err(|e| e.into())
+}
+
+#[cfg(windows)]
+pub async fn symlink_dir(
+    original: impl AsRef<Path>,
+    link: impl AsRef<Path>,
+    priority: Priority,
+) -> Result<(), Error> {
+    let original = original.as_ref().to_owned();
+    let link = link.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || {
+        std::os::windows::fs::symlink_dir(original, link)
+    })
+    .await
+    .map_err(|e| e.into())
 }
 
-pub async fn exists(path: impl AsRef<Path>, _priority: Priority) -> bool {
+pub async fn read_link(
+    path: impl AsRef<Path>,
+    priority: Priority,
+) -> Result<std::path::PathBuf, Error> {
     let path = path.as_ref().to_owned();
     logwise::perfwarn_begin_if!(
         logwise::Duration::from_millis(1),
         "async_file uses blocking on this platform"
     );
-    unblock(move || path.exists()).await
+    unblock(priority, move || std::fs::read_link(path))
+        .await
+        .map_err(|e| e.into())
 }
 
-/// Sets the default origin for file operations (no-op in std implementation).
-///
-/// This function exists for API compatibility with the WASM implementation,
-/// where it sets the origin URL for fetching files. In the standard library
-/// implementation, this is a no-op since files are accessed directly from
-/// the file system.
-///
-/// # Arguments
-///
-/// * `_path` - Path parameter (unused in std implementation)
-///
-/// # Returns
-///
-/// Always returns `Ok(())` since there's nothing to configure.
-///
-/// # Example
-///
-/// ```
-/// # use async_file::Priority;
-/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
-/// # struct DummyError;
-/// # impl std::fmt::Display for DummyError {
-/// #     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }
-/// # }
-/// # impl std::fmt::Debug for DummyError {
-/// #     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }
-/// # }
-/// # impl std::error::Error for DummyError {}
-/// # fn set_default_origin(_: impl AsRef<std::path::Path>) -> Result<(), DummyError> { Ok(()) }
-/// // This is a no-op on non-WASM platforms
-/// set_default_origin("/some/path")?;
+pub async fn metadata(path: impl AsRef<Path>, priority: Priority) -> Result<Metadata, Error> {
+    let path = path.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || std::fs::metadata(path))
+        .await
+        .map(Metadata)
+        .map_err(|e| e.into())
+}
+
+pub async fn symlink_metadata(
+    path: impl AsRef<Path>,
+    priority: Priority,
+) -> Result<Metadata, Error> {
+    let path = path.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || std::fs::symlink_metadata(path))
+        .await
+        .map(Metadata)
+        .map_err(|e| e.into())
+}
+
+pub async fn canonicalize(
+    path: impl AsRef<Path>,
+    priority: Priority,
+) -> Result<std::path::PathBuf, Error> {
+    let path = path.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || std::fs::canonicalize(path))
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn set_permissions(
+    path: impl AsRef<Path>,
+    permissions: Permissions,
+    priority: Priority,
+) -> Result<(), Error> {
+    let path = path.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || {
+        std::fs::set_permissions(path, permissions.0)
+    })
+    .await
+    .map_err(|e| e.into())
+}
+
+fn std_file_times(times: crate::FileTimes) -> std::fs::FileTimes {
+    let mut std_times = std::fs::FileTimes::new();
+    if let Some(accessed) = times.accessed() {
+        std_times = std_times.set_accessed(accessed);
+    }
+    if let Some(modified) = times.modified() {
+        std_times = std_times.set_modified(modified);
+    }
+    std_times
+}
+
+/// Opens `path` for reading, optionally bypassing the page cache, and applies
+/// `advice`.
+fn open_with_options(
+    path: &Path,
+    direct: bool,
+    advice: crate::Advice,
+) -> std::io::Result<std::fs::File> {
+    let mut options = std::fs::OpenOptions::new();
+    options.read(true);
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    if direct {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.custom_flags(libc::O_DIRECT);
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        const FILE_FLAG_NO_BUFFERING: u32 = 0x2000_0000;
+        const FILE_FLAG_SEQUENTIAL_SCAN: u32 = 0x0800_0000;
+        const FILE_FLAG_RANDOM_ACCESS: u32 = 0x1000_0000;
+        let mut flags = match advice {
+            crate::Advice::Sequential => FILE_FLAG_SEQUENTIAL_SCAN,
+            crate::Advice::Random => FILE_FLAG_RANDOM_ACCESS,
+            _ => 0,
+        };
+        if direct {
+            flags |= FILE_FLAG_NO_BUFFERING;
+        }
+        options.custom_flags(flags);
+    }
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_vendor = "apple",
+        windows
+    )))]
+    if direct {
+        return Err(std::io::ErrorKind::Unsupported.into());
+    }
+    let file = options.open(path)?;
+    #[cfg(target_vendor = "apple")]
+    if direct {
+        use std::os::fd::AsRawFd;
+        // SAFETY: F_NOCACHE takes an integer argument on an open descriptor.
+        if unsafe { libc::fcntl(file.as_raw_fd(), libc::F_NOCACHE, 1) } == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    if advice != crate::Advice::Normal {
+        // Advice is a hint; not being able to give it is no reason to fail.
+        let _ = advise(&file, advice, 0, None);
+    }
+    Ok(file)
+}
+
+/// Gives `advice` for `len` bytes (or the rest of the file) from `offset`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn advise(
+    file: &std::fs::File,
+    advice: crate::Advice,
+    offset: u64,
+    len: Option<u64>,
+) -> std::io::Result<()> {
+    use crate::Advice;
+    use std::os::fd::AsRawFd;
+    let advice = match advice {
+        Advice::Normal => libc::POSIX_FADV_NORMAL,
+        Advice::Sequential => libc::POSIX_FADV_SEQUENTIAL,
+        Advice::Random => libc::POSIX_FADV_RANDOM,
+        Advice::WillNeed => libc::POSIX_FADV_WILLNEED,
+        Advice::DontNeed => libc::POSIX_FADV_DONTNEED,
+    };
+    let offset = libc::off_t::try_from(offset)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    // A length of 0 means through the end of the file.
+    let len = len.map_or(0, |len| {
+        libc::off_t::try_from(len).unwrap_or(libc::off_t::MAX)
+    });
+    // SAFETY: posix_fadvise takes only integers.
+    match unsafe { libc::posix_fadvise(file.as_raw_fd(), offset, len, advice) } {
+        0 => Ok(()),
+        errno => Err(std::io::Error::from_raw_os_error(errno)),
+    }
+}
+
+/// Gives `advice` for `len` bytes (or the rest of the file) from `offset`.
+///
+/// Darwin has no `posix_fadvise`; readahead is switched per descriptor, and
+/// `F_RDADVISE` prefetches a range.
+#[cfg(target_vendor = "apple")]
+fn advise(
+    file: &std::fs::File,
+    advice: crate::Advice,
+    offset: u64,
+    len: Option<u64>,
+) -> std::io::Result<()> {
+    use crate::Advice;
+    use std::os::fd::AsRawFd;
+    let fd = file.as_raw_fd();
+    // SAFETY: F_RDAHEAD takes an integer; F_RDADVISE takes a pointer to a
+    // radvisory that outlives the call.
+    let ret = match advice {
+        Advice::Normal | Advice::Sequential => unsafe { libc::fcntl(fd, libc::F_RDAHEAD, 1) },
+        Advice::Random => unsafe { libc::fcntl(fd, libc::F_RDAHEAD, 0) },
+        Advice::WillNeed => {
+            let len = match len {
+                Some(len) => len,
+                None => file.metadata()?.len().saturating_sub(offset),
+            };
+            let advisory = libc::radvisory {
+                ra_offset: libc::off_t::try_from(offset)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?,
+                ra_count: libc::c_int::try_from(len).unwrap_or(libc::c_int::MAX),
+            };
+            unsafe { libc::fcntl(fd, libc::F_RDADVISE, &advisory) }
+        }
+        Advice::DontNeed => 0,
+    };
+    if ret == -1 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Advice is not supported here, or (on Windows) only as flags at open.
+#[cfg(not(any(target_os = "linux", target_os = "android", target_vendor = "apple")))]
+fn advise(
+    _file: &std::fs::File,
+    _advice: crate::Advice,
+    _offset: u64,
+    _len: Option<u64>,
+) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// Reads up to `buf_size` bytes from the current position of a file opened
+/// for direct I/O.
+///
+/// The read is widened to [`DIRECT_ALIGN`] boundaries. When the position is
+/// already aligned, the aligned buffer is returned as is; otherwise the
+/// requested bytes are copied out of it.
+fn read_direct(file: &std::fs::File, pos: u64, buf_size: usize) -> Result<Data, Error> {
+    let skip = (pos % DIRECT_ALIGN as u64) as usize;
+    let start = pos - skip as u64;
+    let len = skip
+        .checked_add(buf_size)
+        .and_then(|len| len.checked_next_multiple_of(DIRECT_ALIGN))
+        .ok_or(Error::TooLarge(buf_size as u64))?;
+    let mut buf =
+        aligned::AlignedBuf::zeroed(len, DIRECT_ALIGN).ok_or(Error::TooLarge(len as u64))?;
+    let mut filled = 0;
+    while filled < len {
+        let read = retry_interrupted(|| read_at(file, &mut buf[filled..], start + filled as u64))?;
+        filled += read;
+        // Only the end of the file ends a direct read short of a block.
+        if read == 0 || read % DIRECT_ALIGN != 0 {
+            break;
+        }
+    }
+    let end = filled.min(skip + buf_size).max(skip);
+    if skip == 0 {
+        buf.truncate(end);
+        Ok(Data(Bytes::Aligned(buf)))
+    } else {
+        Ok(Data::from_boxed_slice(buf[skip..end].into()))
+    }
+}
+
+/// Reads at `offset` or, from pipes and other streams, which have no
+/// positions, from wherever the stream has got to. A dropped read of a stream
+/// can't be undone, so its bytes are lost.
+fn read_stream_at(file: &std::fs::File, buf: &mut [u8], offset: u64) -> std::io::Result<usize> {
+    match retry_interrupted(|| read_at(file, buf, offset)) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotSeekable => {
+            let mut stream = file;
+            retry_interrupted(|| stream.read(buf))
+        }
+        result => result,
+    }
+}
+
+/// Writes all of `buf` at `offset`, or, for a pipe or other stream whose
+/// position can't be chosen, wherever the stream is.
+fn write_stream_at(file: &std::fs::File, mut buf: &[u8], mut offset: u64) -> std::io::Result<()> {
+    while !buf.is_empty() {
+        let written = match retry_interrupted(|| write_at(file, buf, offset)) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotSeekable => {
+                let mut stream = file;
+                return stream.write_all(buf);
+            }
+            Ok(0) => return Err(std::io::ErrorKind::WriteZero.into()),
+            result => result?,
+        };
+        buf = &buf[written..];
+        offset += written as u64;
+    }
+    Ok(())
+}
+
+/// Runs `op` again for as long as it fails with
+/// [`Interrupted`](std::io::ErrorKind::Interrupted), as `read_to_end` does,
+/// so that a signal delivered to the process doesn't fail the operation.
+fn retry_interrupted<T>(mut op: impl FnMut() -> std::io::Result<T>) -> std::io::Result<T> {
+    loop {
+        match op() {
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            result => return result,
+        }
+    }
+}
+
+#[cfg(unix)]
+fn read_at(file: &std::fs::File, buf: &mut [u8], offset: u64) -> std::io::Result<usize> {
+    std::os::unix::fs::FileExt::read_at(file, buf, offset)
+}
+
+#[cfg(windows)]
+fn read_at(file: &std::fs::File, buf: &mut [u8], offset: u64) -> std::io::Result<usize> {
+    // Also moves the operating system's file cursor, which reads don't use.
+    std::os::windows::fs::FileExt::seek_read(file, buf, offset)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn read_at(file: &std::fs::File, buf: &mut [u8], offset: u64) -> std::io::Result<usize> {
+    // Without positional reads, seek the cursor there first; operations on one
+    // file never overlap, so nothing moves it in between.
+    let mut file = file;
+    retry_interrupted(|| file.seek(std::io::SeekFrom::Start(offset)))?;
+    file.read(buf)
+}
+
+#[cfg(unix)]
+fn write_at(file: &std::fs::File, buf: &[u8], offset: u64) -> std::io::Result<usize> {
+    std::os::unix::fs::FileExt::write_at(file, buf, offset)
+}
+
+#[cfg(windows)]
+fn write_at(file: &std::fs::File, buf: &[u8], offset: u64) -> std::io::Result<usize> {
+    std::os::windows::fs::FileExt::seek_write(file, buf, offset)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn write_at(file: &std::fs::File, buf: &[u8], offset: u64) -> std::io::Result<usize> {
+    let mut file = file;
+    retry_interrupted(|| file.seek(std::io::SeekFrom::Start(offset)))?;
+    file.write(buf)
+}
+
+/// Opens `path` with just enough access to change its timestamps, including
+/// for directories and read-only files.
+fn open_for_times(path: &Path) -> std::io::Result<std::fs::File> {
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        const FILE_WRITE_ATTRIBUTES: u32 = 0x0100;
+        const FILE_FLAG_BACKUP_SEMANTICS: u32 = 0x0200_0000;
+        std::fs::OpenOptions::new()
+            .access_mode(FILE_WRITE_ATTRIBUTES)
+            .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+            .open(path)
+    }
+    #[cfg(not(windows))]
+    std::fs::File::open(path)
+}
+
+pub async fn set_times(
+    path: impl AsRef<Path>,
+    times: crate::FileTimes,
+    priority: Priority,
+) -> Result<(), Error> {
+    let path = path.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || {
+        open_for_times(&path)?.set_times(std_file_times(times))
+    })
+    .await
+    .map_err(|e| e.into())
+}
+
+/// Opens `path`, reads `len` bytes (or to the end) from `start`, and closes it,
+/// all in one blocking call.
+pub async fn read_once(
+    path: impl AsRef<Path>,
+    start: u64,
+    len: Option<usize>,
+    priority: Priority,
+) -> Result<Data, Error> {
+    let path = path.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || {
+        let mut file = std::fs::File::open(&path)?;
+        let len = match len {
+            Some(len) => len,
+            None => {
+                let remaining = file.metadata()?.len().saturating_sub(start);
+                usize::try_from(remaining).map_err(|_| Error::TooLarge(remaining))?
+            }
+        };
+        let mut buf = Vec::new();
+        buf.try_reserve_exact(len)
+            .map_err(|_| Error::TooLarge(len as u64))?;
+        file.seek(std::io::SeekFrom::Start(start))?;
+        file.take(len as u64).read_to_end(&mut buf)?;
+        Ok(Data::from_boxed_slice(buf.into_boxed_slice()))
+    })
+    .await
+}
+
+/// Runs an extended attribute call on `path`.
+async fn xattr_path<R: Send + 'static>(
+    path: &Path,
+    priority: Priority,
+    op: impl FnOnce(&xattr::Target<'_>) -> std::io::Result<R> + Send + 'static,
+) -> Result<R, Error> {
+    let path = path.to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || op(&xattr::Target::Path(&path)))
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn list_xattrs(
+    path: impl AsRef<Path>,
+    priority: Priority,
+) -> Result<Vec<std::ffi::OsString>, Error> {
+    xattr_path(path.as_ref(), priority, xattr::list).await
+}
+
+pub async fn get_xattr(
+    path: impl AsRef<Path>,
+    name: &std::ffi::OsStr,
+    priority: Priority,
+) -> Result<Option<Vec<u8>>, Error> {
+    let name = name.to_owned();
+    xattr_path(path.as_ref(), priority, move |target| {
+        xattr::get(target, &name)
+    })
+    .await
+}
+
+pub async fn set_xattr(
+    path: impl AsRef<Path>,
+    name: &std::ffi::OsStr,
+    value: &[u8],
+    priority: Priority,
+) -> Result<(), Error> {
+    let (name, value) = (name.to_owned(), value.to_owned());
+    xattr_path(path.as_ref(), priority, move |target| {
+        xattr::set(target, &name, &value)
+    })
+    .await
+}
+
+pub async fn remove_xattr(
+    path: impl AsRef<Path>,
+    name: &std::ffi::OsStr,
+    priority: Priority,
+) -> Result<(), Error> {
+    let name = name.to_owned();
+    xattr_path(path.as_ref(), priority, move |target| {
+        xattr::remove(target, &name)
+    })
+    .await
+}
+
+pub async fn remove_file(path: impl AsRef<Path>, priority: Priority) -> Result<(), Error> {
+    let path = path.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || std::fs::remove_file(path))
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn remove_dir(path: impl AsRef<Path>, priority: Priority) -> Result<(), Error> {
+    let path = path.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || std::fs::remove_dir(path))
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn probe(
+    path: impl AsRef<Path>,
+    priority: Priority,
+) -> Result<crate::FsCapabilities, Error> {
+    let path = path.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || probe::probe(&path))
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn fs_stats(path: impl AsRef<Path>, priority: Priority) -> Result<crate::FsStats, Error> {
+    let path = path.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || probe::fs_stats(&path))
+        .await
+        .map_err(|e| e.into())
+}
+
+/// Junctions are directory reparse points; std reports them as symlinks on
+/// current toolchains, but we check explicitly so they are never descended as
+/// plain directories.
+#[cfg(windows)]
+fn is_junction(file_type: &std::fs::FileType) -> bool {
+    use std::os::windows::fs::FileTypeExt;
+    file_type.is_symlink_dir()
+}
+
+#[cfg(not(windows))]
+fn is_junction(_file_type: &std::fs::FileType) -> bool {
+    false
+}
+
+/// Identifies the volume a path lives on.
+#[cfg(unix)]
+pub type VolumeId = u64;
+/// Identifies the volume a path lives on: the prefix of its canonical path.
+#[cfg(windows)]
+pub type VolumeId = std::ffi::OsString;
+/// Volumes are not distinguished on other platforms.
+#[cfg(not(any(unix, windows)))]
+pub type VolumeId = u64;
+
+/// Where a path leads once all symlinks are followed.
+#[derive(Debug)]
+pub struct Resolved {
+    pub canonical: std::path::PathBuf,
+    pub volume: VolumeId,
+    pub is_dir: bool,
+}
+
+/// Follows `path` to its final target. Fails for dangling symlinks.
+pub async fn resolve(path: impl AsRef<Path>, priority: Priority) -> Result<Resolved, Error> {
+    let path = path.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || {
+        let canonical = std::fs::canonicalize(&path)?;
+        let metadata = std::fs::metadata(&canonical)?;
+        #[cfg(unix)]
+        let volume = std::os::unix::fs::MetadataExt::dev(&metadata);
+        #[cfg(windows)]
+        let volume = match canonical.components().next() {
+            Some(std::path::Component::Prefix(prefix)) => prefix.as_os_str().to_owned(),
+            _ => std::ffi::OsString::new(),
+        };
+        #[cfg(not(any(unix, windows)))]
+        let volume = 0;
+        Ok(Resolved {
+            canonical,
+            volume,
+            is_dir: metadata.is_dir(),
+        })
+    })
+    .await
+    .map_err(|e: std::io::Error| e.into())
+}
+
+/// The size of one entry, on behalf of `dir_size`.
+#[derive(Debug)]
+pub struct Usage {
+    pub len: u64,
+    pub allocated: u64,
+    /// Identifies the file, so one reached by several paths is counted once.
+    pub id: Option<(u64, u64)>,
+}
+
+/// Measures `path`, or the link itself unless `follow` is set.
+pub async fn usage(
+    path: impl AsRef<Path>,
+    follow: bool,
+    priority: Priority,
+) -> Result<Usage, Error> {
+    let path = path.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || {
+        let metadata = if follow {
+            std::fs::metadata(&path)?
+        } else {
+            std::fs::symlink_metadata(&path)?
+        };
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            Ok(Usage {
+                len: metadata.len(),
+                // st_blocks is always in 512-byte units, whatever the block size.
+                allocated: metadata.blocks().saturating_mul(512),
+                id: Some((metadata.dev(), metadata.ino())),
+            })
+        }
+        #[cfg(windows)]
+        {
+            Ok(Usage {
+                len: metadata.len(),
+                allocated: allocation_size(&path, follow)?,
+                id: None,
+            })
+        }
+        #[cfg(not(any(unix, windows)))]
+        Ok(Usage {
+            len: metadata.len(),
+            allocated: metadata.len(),
+            id: None,
+        })
+    })
+    .await
+    .map_err(|e: std::io::Error| e.into())
+}
+
+/// Returns the space allocated to `path`, which std does not expose on Windows.
+#[cfg(windows)]
+fn allocation_size(path: &Path, follow: bool) -> std::io::Result<u64> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{
+        FILE_STANDARD_INFO, FileStandardInfo, GetFileInformationByHandleEx,
+    };
+    const FILE_READ_ATTRIBUTES: u32 = 0x0080;
+    const FILE_FLAG_BACKUP_SEMANTICS: u32 = 0x0200_0000;
+    const FILE_FLAG_OPEN_REPARSE_POINT: u32 = 0x0020_0000;
+
+    let mut flags = FILE_FLAG_BACKUP_SEMANTICS;
+    if !follow {
+        flags |= FILE_FLAG_OPEN_REPARSE_POINT;
+    }
+    let file = std::fs::OpenOptions::new()
+        .access_mode(FILE_READ_ATTRIBUTES)
+        .custom_flags(flags)
+        .open(path)?;
+    // SAFETY: FILE_STANDARD_INFO is plain old data, the handle is open for the
+    // duration of the call, and the buffer size matches the struct.
+    let mut info: FILE_STANDARD_INFO = unsafe { std::mem::zeroed() };
+    let ok = unsafe {
+        GetFileInformationByHandleEx(
+            file.as_raw_handle(),
+            FileStandardInfo,
+            (&mut info as *mut FILE_STANDARD_INFO).cast(),
+            std::mem::size_of::<FILE_STANDARD_INFO>() as u32,
+        )
+    };
+    if ok == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(info.AllocationSize.max(0) as u64)
+}
+
+/// Removes a non-directory entry on behalf of `remove_dir_all`.
+///
+/// On Windows this also handles directory symlinks and junctions (which are
+/// listed as non-directories but must be removed as directories), read-only
+/// attributes, and transient sharing violations.
+pub async fn remove_tree_file(path: impl AsRef<Path>, priority: Priority) -> Result<(), Error> {
+    let path = path.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || tree::remove_file(&path))
+        .await
+        .map_err(|e| e.into())
+}
+
+/// Removes an emptied directory on behalf of `remove_dir_all`.
+///
+/// See [`remove_tree_file`].
+pub async fn remove_tree_dir(path: impl AsRef<Path>, priority: Priority) -> Result<(), Error> {
+    let path = path.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || tree::remove_dir(&path))
+        .await
+        .map_err(|e| e.into())
+}
+
+#[cfg(not(windows))]
+mod tree {
+    use std::io;
+    use std::path::Path;
+
+    pub fn remove_file(path: &Path) -> io::Result<()> {
+        std::fs::remove_file(path)
+    }
+
+    pub fn remove_dir(path: &Path) -> io::Result<()> {
+        std::fs::remove_dir(path)
+    }
+}
+
+/// Recursive delete is notoriously unreliable on Windows: read-only files can't
+/// be deleted, and virus scanners, indexers and just-closed handles cause
+/// sharing violations (or leave a "deleted" child pending, so its parent looks
+/// non-empty) for a short while. Long paths need no handling here, since std
+/// already converts absolute paths to their `\\?\` form.
+#[cfg(windows)]
+mod tree {
+    use std::io;
+    use std::os::windows::fs::FileTypeExt;
+    use std::path::Path;
+    use std::time::Duration;
+
+    const ATTEMPTS: u32 = 6;
+    const FIRST_BACKOFF: Duration = Duration::from_millis(10);
+
+    const ERROR_ACCESS_DENIED: i32 = 5;
+    const ERROR_SHARING_VIOLATION: i32 = 32;
+    const ERROR_LOCK_VIOLATION: i32 = 33;
+    const ERROR_DIR_NOT_EMPTY: i32 = 145;
+
+    pub fn remove_file(path: &Path) -> io::Result<()> {
+        // Directory symlinks and junctions are reparse points that read_dir does
+        // not report as directories. They are removed as directories, without
+        // touching what they point to.
+        let is_dir_link = std::fs::symlink_metadata(path)
+            .map(|m| m.file_type().is_symlink_dir())
+            .unwrap_or(false);
+        retry(path, || {
+            if is_dir_link {
+                std::fs::remove_dir(path)
+            } else {
+                std::fs::remove_file(path)
+            }
+        })
+    }
+
+    pub fn remove_dir(path: &Path) -> io::Result<()> {
+        retry(path, || std::fs::remove_dir(path))
+    }
+
+    fn retry(path: &Path, op: impl Fn() -> io::Result<()>) -> io::Result<()> {
+        let mut backoff = FIRST_BACKOFF;
+        let mut cleared_readonly = false;
+        let mut attempt = 1;
+        loop {
+            match op() {
+                Ok(()) => return Ok(()),
+                Err(e)
+                    if e.kind() == io::ErrorKind::PermissionDenied
+                        && !cleared_readonly
+                        && clear_readonly(path) =>
+                {
+                    cleared_readonly = true;
+                }
+                Err(e) if attempt < ATTEMPTS && is_transient(&e) => {
+                    std::thread::sleep(backoff);
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Clears the read-only attribute, returning whether it was set.
+    fn clear_readonly(path: &Path) -> bool {
+        let Ok(metadata) = std::fs::symlink_metadata(path) else {
+            return false;
+        };
+        let mut permissions = metadata.permissions();
+        if !permissions.readonly() {
+            return false;
+        }
+        // On Windows this clears FILE_ATTRIBUTE_READONLY, which is what we want.
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        std::fs::set_permissions(path, permissions).is_ok()
+    }
+
+    fn is_transient(e: &io::Error) -> bool {
+        matches!(
+            e.raw_os_error(),
+            Some(
+                ERROR_ACCESS_DENIED
+                    | ERROR_SHARING_VIOLATION
+                    | ERROR_LOCK_VIOLATION
+                    | ERROR_DIR_NOT_EMPTY
+            )
+        )
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{ATTEMPTS, ERROR_SHARING_VIOLATION, retry};
+        use std::cell::Cell;
+        use std::io;
+        use std::path::Path;
+
+        #[test]
+        fn test_retries_transient_errors() {
+            let calls = Cell::new(0);
+            let result = retry(Path::new("missing"), || {
+                calls.set(calls.get() + 1);
+                if calls.get() < 3 {
+                    Err(io::Error::from_raw_os_error(ERROR_SHARING_VIOLATION))
+                } else {
+                    Ok(())
+                }
+            });
+            assert!(result.is_ok());
+            assert_eq!(calls.get(), 3);
+        }
+
+        #[test]
+        fn test_gives_up() {
+            let calls = Cell::new(0);
+            let result = retry(Path::new("missing"), || {
+                calls.set(calls.get() + 1);
+                Err(io::Error::from_raw_os_error(ERROR_SHARING_VIOLATION))
+            });
+            assert_eq!(
+                result.unwrap_err().raw_os_error(),
+                Some(ERROR_SHARING_VIOLATION)
+            );
+            assert_eq!(calls.get(), ATTEMPTS);
+
+            // Errors that time won't fix are returned straight away.
+            calls.set(0);
+            let result = retry(Path::new("missing"), || {
+                calls.set(calls.get() + 1);
+                Err(io::Error::from(io::ErrorKind::NotFound))
+            });
+            assert!(result.is_err());
+            assert_eq!(calls.get(), 1);
+        }
+
+        #[test]
+        fn test_clears_readonly() {
+            let path = std::env::temp_dir()
+                .join(format!("async_file_tree_readonly_{}", std::process::id()));
+            std::fs::write(&path, b"locked").unwrap();
+            let mut permissions = std::fs::metadata(&path).unwrap().permissions();
+            permissions.set_readonly(true);
+            std::fs::set_permissions(&path, permissions).unwrap();
+            let result = super::remove_file(&path);
+            let _ = std::fs::remove_file(&path);
+            result.unwrap();
+        }
+    }
+}
+
+pub async fn create_dir_all(path: impl AsRef<Path>, priority: Priority) -> Result<(), Error> {
+    let path = path.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || std::fs::create_dir_all(path))
+        .await
+        .map_err(|e| e.into())
+}
+
+/// An entry returned by [`read_dir`].
+#[derive(Debug)]
+pub struct DirEntry {
+    pub path: std::path::PathBuf,
+    /// Whether the entry itself is a directory. Symlinks are not followed.
+    pub is_dir: bool,
+    /// Whether the entry is a symlink. On Windows this includes junctions.
+    pub is_symlink: bool,
+}
+
+pub async fn read_dir(path: impl AsRef<Path>, priority: Priority) -> Result<Vec<DirEntry>, Error> {
+    let path = path.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || {
+        std::fs::read_dir(path)?
+            .map(|entry| {
+                let entry = entry?;
+                let file_type = entry.file_type()?;
+                Ok(DirEntry {
+                    path: entry.path(),
+                    is_dir: file_type.is_dir(),
+                    is_symlink: file_type.is_symlink() || is_junction(&file_type),
+                })
+            })
+            .collect::<std::io::Result<Vec<_>>>()
+    })
+    .await
+    .map_err(|e| e.into())
+}
+
+pub async fn exists(path: impl AsRef<Path>, priority: Priority) -> bool {
+    let path = path.as_ref().to_owned();
+    logwise::perfwarn_begin_if!(
+        logwise::Duration::from_millis(1),
+        "async_file uses blocking on this platform"
+    );
+    unblock(priority, move || path.exists()).await
+}
+
+/// Sets the default origin for file operations (no-op in std implementation).
+///
+/// This function exists for API compatibility with the WASM implementation,
+/// where it sets the origin URL for fetching files. In the standard library
+/// implementation, this is a no-op since files are accessed directly from
+/// the file system.
+///
+/// # Arguments
+///
+/// * `_path` - Path parameter (unused in std implementation)
+///
+/// # Returns
+///
+/// Always returns `Ok(())` since there's nothing to configure.
+///
+/// # Example
+///
+/// ```
+/// # use async_file::Priority;
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// # struct DummyError;
+/// # impl std::fmt::Display for DummyError {
+/// #     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }
+/// # }
+/// # impl std::fmt::Debug for DummyError {
+/// #     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }
+/// # }
+/// # impl std::error::Error for DummyError {}
+/// # fn set_default_origin(_: impl AsRef<std::path::Path>) -> Result<(), DummyError> { Ok(()) }
+/// // This is a no-op on non-WASM platforms
+/// set_default_origin("/some/path")?;
 ///
 /// // File operations work the same regardless
 /// # Ok(())
@@ -514,3 +2142,85 @@ pub async fn exists(path: impl AsRef<Path>, _priority: Priority) -> bool {
 pub fn set_default_origin(_path: impl AsRef<Path>) {
     //nothing to do here, as std impl does not use origins
 }
+
+pub fn set_fetch_options(_options: crate::FetchOptions) {
+    //nothing to do here, as std impl does not fetch
+}
+
+pub fn set_worker_threads(threads: usize) {
+    pool::set_max_threads(threads);
+}
+
+/// Returns a future that completes once `duration` has passed, woken by the
+/// crate's timer thread.
+pub fn delay(duration: std::time::Duration) -> impl Future<Output = ()> + Send + 'static {
+    timer::sleep(duration)
+}
+
+pub fn worker_threads() -> usize {
+    pool::max_threads()
+}
+
+pub fn set_band_limits(limits: [Option<usize>; crate::BANDS]) {
+    pool::set_band_limits(limits);
+}
+
+pub fn band_limits() -> [Option<usize>; crate::BANDS] {
+    pool::band_limits()
+}
+
+pub fn reschedule() {
+    pool::reschedule();
+}
+
+pub fn scheduler_stats() -> [crate::stats::BandStats; crate::BANDS] {
+    pool::stats()
+}
+
+pub fn set_max_queue(max: Option<usize>) {
+    pool::set_max_queue(max);
+}
+
+pub fn max_queue() -> Option<usize> {
+    pool::max_queue()
+}
+
+pub fn set_aging(step: Option<std::time::Duration>) {
+    pool::set_aging(step);
+}
+
+pub fn aging() -> Option<std::time::Duration> {
+    pool::aging()
+}
+
+pub fn max_queue_waits() -> [std::time::Duration; crate::BANDS] {
+    pool::max_waits()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::retry_interrupted;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn test_retry_interrupted() {
+        let mut tries = 0;
+        let result = retry_interrupted(|| {
+            tries += 1;
+            if tries < 3 {
+                Err(ErrorKind::Interrupted.into())
+            } else {
+                Ok(tries)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+
+        let mut tries = 0;
+        let result = retry_interrupted(|| {
+            tries += 1;
+            Err::<(), _>(ErrorKind::WouldBlock.into())
+        });
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::WouldBlock);
+        assert_eq!(tries, 1);
+    }
+}
@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Heap buffers with a chosen alignment.
+//!
+//! `Box<[u8]>` only promises byte alignment, but direct I/O needs buffers that
+//! start on a block boundary. An [`AlignedBuf`] owns such an allocation and
+//! frees it with the layout it was allocated with.
+
+use std::alloc::Layout;
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+pub(crate) struct AlignedBuf {
+    ptr: NonNull<u8>,
+    len: usize,
+    layout: Layout,
+}
+
+// SAFETY: AlignedBuf uniquely owns its allocation, like Box<[u8]>.
+unsafe impl Send for AlignedBuf {}
+// SAFETY: shared access only hands out &[u8].
+unsafe impl Sync for AlignedBuf {}
+
+impl AlignedBuf {
+    /// Allocates `len` zeroed bytes aligned to `align`, a power of two.
+    ///
+    /// Returns `None` if the allocation fails or the size overflows.
+    pub(crate) fn zeroed(len: usize, align: usize) -> Option<Self> {
+        let layout = Layout::from_size_align(len, align).ok()?;
+        let ptr = if len == 0 {
+            // A dangling pointer with the right alignment is valid for 0 bytes.
+            NonNull::new(std::ptr::without_provenance_mut(align))?
+        } else {
+            // SAFETY: the layout has a nonzero size.
+            NonNull::new(unsafe { std::alloc::alloc_zeroed(layout) })?
+        };
+        Some(AlignedBuf { ptr, len, layout })
+    }
+
+    /// Shortens the buffer to `len` bytes. The allocation is unchanged.
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.len = self.len.min(len);
+    }
+}
+
+impl Deref for AlignedBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: the first `len` bytes are allocated and initialized.
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl DerefMut for AlignedBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // SAFETY: as for `deref`, and `&mut self` guarantees uniqueness.
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Drop for AlignedBuf {
+    fn drop(&mut self) {
+        if self.layout.size() != 0 {
+            // SAFETY: allocated in `zeroed` with this layout.
+            unsafe { std::alloc::dealloc(self.ptr.as_ptr(), self.layout) }
+        }
+    }
+}
+
+impl std::fmt::Debug for AlignedBuf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AlignedBuf")
+            .field("len", &self.len)
+            .field("align", &self.layout.align())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AlignedBuf;
+
+    #[test]
+    fn test_aligned_buf() {
+        let mut buf = AlignedBuf::zeroed(10_000, 4096).unwrap();
+        assert_eq!(buf.as_ptr() as usize % 4096, 0);
+        assert!(buf.iter().all(|&b| b == 0));
+        buf[9_999] = 7;
+        buf.truncate(5);
+        assert_eq!(buf.len(), 5);
+        let empty = AlignedBuf::zeroed(0, 4096).unwrap();
+        assert!(empty.is_empty());
+    }
+}
@@ -0,0 +1,92 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Recycled read buffers.
+//!
+//! Small reads are frequent, and allocating (and zeroing) a fresh buffer for
+//! each one shows up in profiles. When a small [`Data`](super::Data) is
+//! dropped, its allocation is kept here, and the next read takes it back
+//! instead of allocating. The bytes it held count as already initialized, so
+//! only the part of a buffer never written before needs zeroing.
+//!
+//! The pool is bounded in both the number and size of buffers it keeps, so it
+//! holds at most [`MAX_BUFFERS`] × [`MAX_CAPACITY`] bytes.
+
+use std::sync::Mutex;
+
+/// Buffers with a larger capacity are freed rather than kept.
+const MAX_CAPACITY: usize = 64 * 1024;
+
+/// Buffers beyond this many are freed rather than kept.
+const MAX_BUFFERS: usize = 32;
+
+static GLOBAL: Pool = Pool::new();
+
+/// Returns a buffer for a read of up to `len` bytes: a recycled one with room
+/// for `len` if there is one, or else an empty, unallocated one.
+pub(super) fn take(len: usize) -> Vec<u8> {
+    GLOBAL.take(len)
+}
+
+/// Keeps `buf` for a later [`take`], if it is small enough and there is room.
+pub(super) fn give(buf: Vec<u8>) {
+    GLOBAL.give(buf);
+}
+
+struct Pool(Mutex<Vec<Vec<u8>>>);
+
+impl Pool {
+    const fn new() -> Self {
+        Pool(Mutex::new(Vec::new()))
+    }
+
+    fn take(&self, len: usize) -> Vec<u8> {
+        if len > MAX_CAPACITY {
+            return Vec::new();
+        }
+        let mut pool = self.0.lock().unwrap();
+        match pool.iter().rposition(|buf| buf.capacity() >= len) {
+            Some(index) => pool.swap_remove(index),
+            None => Vec::new(),
+        }
+    }
+
+    fn give(&self, buf: Vec<u8>) {
+        if buf.capacity() == 0 || buf.capacity() > MAX_CAPACITY {
+            return;
+        }
+        let mut pool = self.0.lock().unwrap();
+        if pool.len() < MAX_BUFFERS {
+            pool.push(buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_BUFFERS, MAX_CAPACITY, Pool};
+
+    #[test]
+    fn test_take_recycles_large_enough_buffer() {
+        let pool = Pool::new();
+        let small = Vec::with_capacity(16);
+        let big = Vec::<u8>::with_capacity(4096);
+        let big_ptr = big.as_ptr();
+        pool.give(small);
+        pool.give(big);
+        let taken = pool.take(1000);
+        assert_eq!(taken.as_ptr(), big_ptr);
+        assert_eq!(pool.take(1000).capacity(), 0);
+        assert!(pool.take(10).capacity() >= 16);
+        assert_eq!(pool.take(MAX_CAPACITY + 1).capacity(), 0);
+    }
+
+    #[test]
+    fn test_give_is_bounded() {
+        let pool = Pool::new();
+        pool.give(Vec::with_capacity(MAX_CAPACITY + 1));
+        for _ in 0..MAX_BUFFERS + 5 {
+            pool.give(Vec::with_capacity(8));
+        }
+        assert_eq!(pool.0.lock().unwrap().len(), MAX_BUFFERS);
+    }
+}
@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Carrying an operation's [`Priority`] into the OS's I/O scheduling.
+//!
+//! The worker pool orders its own queue by priority, but once a call reaches
+//! the kernel it competes with every other thread's I/O. Before running a call,
+//! a worker thread takes on the OS I/O priority matching the call's, so that
+//! background reads yield to interactive ones system-wide.
+//!
+//! This is best-effort: failures (a sandbox forbidding the call, say) are
+//! ignored, and how much the OS honors the priority depends on the platform
+//! and, on Linux, on the disk's I/O scheduler.
+
+use crate::Priority;
+
+/// Sets the calling thread's I/O priority to match `priority`.
+pub(super) fn apply(priority: Priority) {
+    imp::apply(priority);
+}
+
+/// Sets the I/O priority of requests through `file` to match `priority`.
+///
+/// Windows attaches I/O priority to handles rather than threads, so this is
+/// called before each read or write through an open file. Calls that work on
+/// paths open their own short-lived handles and are not hinted. Elsewhere this
+/// does nothing; [`apply`] covers those platforms.
+pub(super) fn apply_to_file(file: &std::fs::File, priority: Priority) {
+    #[cfg(windows)]
+    {
+        use std::os::windows::io::AsRawHandle;
+        use windows_sys::Win32::Storage::FileSystem::{
+            FILE_IO_PRIORITY_HINT_INFO, FileIoPriorityHintInfo, IoPriorityHintLow,
+            IoPriorityHintNormal, IoPriorityHintVeryLow, SetFileInformationByHandle,
+        };
+        let hint = FILE_IO_PRIORITY_HINT_INFO {
+            PriorityHint: match priority {
+                Priority::Utility => IoPriorityHintLow,
+                Priority::Background => IoPriorityHintVeryLow,
+                _ => IoPriorityHintNormal,
+            },
+        };
+        // SAFETY: the handle is open for the duration of the call, and the
+        // buffer is a FILE_IO_PRIORITY_HINT_INFO of the size passed.
+        unsafe {
+            SetFileInformationByHandle(
+                file.as_raw_handle(),
+                FileIoPriorityHintInfo,
+                (&hint as *const FILE_IO_PRIORITY_HINT_INFO).cast(),
+                std::mem::size_of::<FILE_IO_PRIORITY_HINT_INFO>() as u32,
+            );
+        }
+    }
+    #[cfg(not(windows))]
+    let _ = (file, priority);
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod imp {
+    use crate::Priority;
+
+    const IOPRIO_CLASS_SHIFT: i32 = 13;
+    const IOPRIO_CLASS_BE: i32 = 2;
+    const IOPRIO_CLASS_IDLE: i32 = 3;
+    const IOPRIO_WHO_PROCESS: i32 = 1;
+
+    /// Maps to the best-effort class, whose levels run from 0 (highest) to 7,
+    /// with 4 the default, or to the idle class, which only gets disk time no
+    /// one else wants. Unprivileged threads may use either.
+    pub(super) fn ioprio(priority: Priority) -> i32 {
+        let (class, level) = match priority {
+            Priority::UserInteractive => (IOPRIO_CLASS_BE, 0),
+            Priority::UserInitiated => (IOPRIO_CLASS_BE, 2),
+            Priority::Background => (IOPRIO_CLASS_IDLE, 0),
+            _ => (IOPRIO_CLASS_BE, 4),
+        };
+        (class << IOPRIO_CLASS_SHIFT) | level
+    }
+
+    pub(super) fn apply(priority: Priority) {
+        // SAFETY: ioprio_set takes only integers. With IOPRIO_WHO_PROCESS, an
+        // id of 0 means the calling thread.
+        unsafe {
+            libc::syscall(
+                libc::SYS_ioprio_set,
+                IOPRIO_WHO_PROCESS,
+                0,
+                ioprio(priority),
+            );
+        }
+    }
+
+    #[cfg(test)]
+    pub(super) fn current() -> i32 {
+        // SAFETY: as for `apply`.
+        unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, 0) as i32 }
+    }
+}
+
+#[cfg(target_vendor = "apple")]
+mod imp {
+    use crate::Priority;
+    use libc::c_int;
+    use libc::qos_class_t::{
+        QOS_CLASS_BACKGROUND, QOS_CLASS_DEFAULT, QOS_CLASS_USER_INITIATED,
+        QOS_CLASS_USER_INTERACTIVE, QOS_CLASS_UTILITY,
+    };
+
+    // From <sys/resource.h>; libc does not bind setiopolicy_np.
+    const IOPOL_TYPE_DISK: c_int = 0;
+    const IOPOL_SCOPE_THREAD: c_int = 1;
+    const IOPOL_DEFAULT: c_int = 0;
+    const IOPOL_IMPORTANT: c_int = 1;
+    const IOPOL_THROTTLE: c_int = 3;
+    const IOPOL_UTILITY: c_int = 4;
+
+    unsafe extern "C" {
+        fn setiopolicy_np(iotype: c_int, scope: c_int, policy: c_int) -> c_int;
+    }
+
+    /// Sets both the thread's QoS class, which governs CPU scheduling and
+    /// what the system treats as user-visible work, and its disk I/O policy,
+    /// under which throttled I/O is delayed while important I/O is pending.
+    pub(super) fn apply(priority: Priority) {
+        let (qos, iopol) = match priority {
+            Priority::UserInteractive => (QOS_CLASS_USER_INTERACTIVE, IOPOL_IMPORTANT),
+            Priority::UserInitiated => (QOS_CLASS_USER_INITIATED, IOPOL_IMPORTANT),
+            Priority::Utility => (QOS_CLASS_UTILITY, IOPOL_UTILITY),
+            Priority::Background => (QOS_CLASS_BACKGROUND, IOPOL_THROTTLE),
+            _ => (QOS_CLASS_DEFAULT, IOPOL_DEFAULT),
+        };
+        // SAFETY: both calls take only integers and affect only the calling
+        // thread, which the pool owns.
+        unsafe {
+            libc::pthread_set_qos_class_self_np(qos, 0);
+            setiopolicy_np(IOPOL_TYPE_DISK, IOPOL_SCOPE_THREAD, iopol);
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_vendor = "apple")))]
+mod imp {
+    use crate::Priority;
+
+    pub(super) fn apply(_priority: Priority) {}
+}
+
+#[cfg(all(test, any(target_os = "linux", target_os = "android")))]
+mod tests {
+    use super::{Priority, apply, imp};
+
+    #[test]
+    fn test_apply_sets_thread_ioprio() {
+        // On a fresh thread, so the test's own thread is left alone.
+        std::thread::spawn(|| {
+            apply(Priority::Background);
+            assert_eq!(imp::current(), imp::ioprio(Priority::Background));
+            apply(Priority::UserInteractive);
+            assert_eq!(imp::current(), imp::ioprio(Priority::UserInteractive));
+        })
+        .join()
+        .unwrap();
+    }
+}
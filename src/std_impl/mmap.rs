@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Read-only memory maps of whole files.
+//!
+//! A [`Mapping`] lets a large file be handed out as bytes without reading it
+//! into an allocation first: pages are loaded from the page cache as they are
+//! touched, and can be dropped again under memory pressure.
+
+use std::ops::Deref;
+
+#[cfg_attr(not(any(unix, windows)), allow(dead_code))]
+pub(crate) struct Mapping {
+    /// The start of the mapped view, aligned as the OS requires.
+    base: *mut u8,
+    /// Bytes mapped from `base`.
+    len: usize,
+    /// Bytes from `base` to the first byte handed out.
+    skip: usize,
+}
+
+// SAFETY: the view is read-only and unmapped only on drop.
+unsafe impl Send for Mapping {}
+// SAFETY: shared access only hands out &[u8].
+unsafe impl Sync for Mapping {}
+
+impl Deref for Mapping {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: `len` bytes are mapped readable from `base`, and `skip` is at
+        // most `len`.
+        unsafe { std::slice::from_raw_parts(self.base.add(self.skip), self.len - self.skip) }
+    }
+}
+
+impl std::fmt::Debug for Mapping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Mapping")
+            .field("len", &(self.len - self.skip))
+            .finish()
+    }
+}
+
+/// Maps `file` from `offset` to its end.
+///
+/// Returns `None` if there is nothing to map, or if this platform can't map
+/// files; the caller reads instead.
+pub(crate) fn map(file: &std::fs::File, offset: u64) -> std::io::Result<Option<Mapping>> {
+    let len = file.metadata()?.len();
+    if offset >= len {
+        return Ok(None);
+    }
+    imp::map(file, offset, len)
+}
+
+#[cfg(unix)]
+mod imp {
+    use super::Mapping;
+    use std::os::fd::AsRawFd;
+
+    pub(super) fn map(
+        file: &std::fs::File,
+        offset: u64,
+        len: u64,
+    ) -> std::io::Result<Option<Mapping>> {
+        // SAFETY: sysconf takes only an integer.
+        let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as u64;
+        let start = offset - offset % page;
+        let (Ok(map_len), Ok(map_offset)) =
+            (usize::try_from(len - start), libc::off_t::try_from(start))
+        else {
+            return Ok(None);
+        };
+        // SAFETY: a fresh read-only private mapping of an open descriptor,
+        // which stays valid after the descriptor is closed.
+        let base = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                map_len,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                map_offset,
+            )
+        };
+        if base == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(Some(Mapping {
+            base: base.cast(),
+            len: map_len,
+            skip: (offset - start) as usize,
+        }))
+    }
+
+    impl Drop for Mapping {
+        fn drop(&mut self) {
+            // SAFETY: `base` and `len` are exactly what mmap returned.
+            unsafe {
+                libc::munmap(self.base.cast(), self.len);
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use super::Mapping;
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Memory::{
+        CreateFileMappingW, FILE_MAP_READ, MEMORY_MAPPED_VIEW_ADDRESS, MapViewOfFile,
+        PAGE_READONLY, UnmapViewOfFile,
+    };
+
+    /// Views must start at a multiple of the allocation granularity, which is
+    /// 64 KiB on every version of Windows.
+    const GRANULARITY: u64 = 64 * 1024;
+
+    pub(super) fn map(
+        file: &std::fs::File,
+        offset: u64,
+        len: u64,
+    ) -> std::io::Result<Option<Mapping>> {
+        let start = offset - offset % GRANULARITY;
+        let Ok(map_len) = usize::try_from(len - start) else {
+            return Ok(None);
+        };
+        // SAFETY: the file handle is open for the duration of the calls. The
+        // view keeps the mapping object alive, so its handle can be closed
+        // straight away.
+        unsafe {
+            let mapping = CreateFileMappingW(
+                file.as_raw_handle(),
+                std::ptr::null(),
+                PAGE_READONLY,
+                0,
+                0,
+                std::ptr::null(),
+            );
+            if mapping.is_null() {
+                return Err(std::io::Error::last_os_error());
+            }
+            let view = MapViewOfFile(
+                mapping,
+                FILE_MAP_READ,
+                (start >> 32) as u32,
+                start as u32,
+                map_len,
+            );
+            let error = std::io::Error::last_os_error();
+            CloseHandle(mapping);
+            if view.Value.is_null() {
+                return Err(error);
+            }
+            Ok(Some(Mapping {
+                base: view.Value.cast(),
+                len: map_len,
+                skip: (offset - start) as usize,
+            }))
+        }
+    }
+
+    impl Drop for Mapping {
+        fn drop(&mut self) {
+            // SAFETY: `base` is the address MapViewOfFile returned.
+            unsafe {
+                UnmapViewOfFile(MEMORY_MAPPED_VIEW_ADDRESS {
+                    Value: self.base.cast(),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod imp {
+    use super::Mapping;
+
+    pub(super) fn map(
+        _file: &std::fs::File,
+        _offset: u64,
+        _len: u64,
+    ) -> std::io::Result<Option<Mapping>> {
+        Ok(None)
+    }
+}
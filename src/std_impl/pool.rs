@@ -0,0 +1,783 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! The worker threads that run blocking filesystem calls.
+//!
+//! Queued calls run in [`Priority`] order, first-come first-served within a
+//! priority, so a read the user is waiting on starts ahead of background reads
+//! issued before it. Each priority band can also be limited to a number of
+//! calls at once, in which case its calls wait even while threads are free.
+//! Threads are started as work arrives, up to a configurable limit, and exit
+//! after sitting idle for a while. They belong to this crate alone, so blocking
+//! work from other crates never delays its calls. The queue can be bounded, in
+//! which case callers wait for room before their calls are queued.
+//!
+//! A call made with a deadline (see [`ReadOptions::deadline`]) moves up to
+//! more urgent bands as its deadline nears, and within a band goes ahead of
+//! calls without one, earliest deadline first. It still counts against its own
+//! band's limit. A call made under an [`OperationHandle`] is queued at the
+//! handle's priority as it is when a thread comes free, so raising it moves
+//! calls already waiting.
+//!
+//! With aging on (see [`Config::aging`]), a call also moves up one band for
+//! every step it has waited, so a steady stream of urgent calls can't hold back
+//! less urgent ones forever. The longest wait of each band is recorded.
+//!
+//! [`ReadOptions::deadline`]: crate::ReadOptions::deadline
+//! [`Config::aging`]: crate::Config::aging
+//! [`OperationHandle`]: crate::OperationHandle
+
+use super::io_priority;
+use crate::OperationHandle;
+use crate::stats::BandStats;
+use crate::{BANDS, Priority, rank};
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+/// The thread limit until [`set_max_threads`] is called.
+const DEFAULT_MAX_THREADS: usize = 32;
+
+/// How close to its deadline a call moves up to the
+/// [`Priority::UserInitiated`] band.
+const ESCALATE_INITIATED: Duration = Duration::from_secs(1);
+
+/// How close to its deadline a call moves up to the
+/// [`Priority::UserInteractive`] band.
+const ESCALATE_INTERACTIVE: Duration = Duration::from_millis(100);
+
+/// How much weight each new job gets in a recent latency.
+const RECENT_WEIGHT: u32 = 8;
+
+/// How long a thread waits for work before exiting.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+static GLOBAL: Pool = Pool::new();
+
+/// Runs `f` on a worker thread, queued behind more urgent work, and returns
+/// its result. A panic in `f` is resumed in the caller.
+pub(crate) async fn unblock<T: Send + 'static>(
+    priority: Priority,
+    f: impl FnOnce() -> T + Send + 'static,
+) -> T {
+    let (sender, receiver) = async_channel::bounded(1);
+    let mut job = Some(Job {
+        priority,
+        queued: Instant::now(),
+        deadline: crate::ambient::current_deadline(),
+        operation: crate::ambient::current_operation(),
+        run: Box::new(move || {
+            // A call whose caller stopped waiting before it started never runs.
+            if sender.is_closed() {
+                return;
+            }
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+            // The caller may have stopped waiting since.
+            let _ = sender.try_send(result);
+        }),
+    });
+    std::future::poll_fn(|cx| GLOBAL.poll_submit(cx, &mut job)).await;
+    match receiver.recv().await {
+        Ok(Ok(value)) => value,
+        Ok(Err(panic)) => std::panic::resume_unwind(panic),
+        Err(_) => unreachable!("worker dropped a job without running it"),
+    }
+}
+
+/// Sets the maximum number of worker threads. Values below 1 are treated as 1.
+pub(crate) fn set_max_threads(threads: usize) {
+    GLOBAL.set_max_threads(threads);
+}
+
+/// Returns the maximum number of worker threads.
+pub(crate) fn max_threads() -> usize {
+    GLOBAL.state.lock().unwrap().max_threads
+}
+
+/// Sets the most calls of each band, indexed by [`rank`], that may run at
+/// once. Limits below 1 are treated as 1.
+pub(crate) fn set_band_limits(limits: [Option<usize>; BANDS]) {
+    GLOBAL.set_band_limits(limits);
+}
+
+/// Looks again at which queued calls may start, after a change to the
+/// priority of some of them.
+pub(crate) fn reschedule() {
+    GLOBAL.reschedule();
+}
+
+/// Returns the limits set by [`set_band_limits`].
+pub(crate) fn band_limits() -> [Option<usize>; BANDS] {
+    GLOBAL.state.lock().unwrap().band_limits
+}
+
+/// Sets the most calls that may wait in the queue at once, or removes the
+/// bound. Values below 1 are treated as 1.
+pub(crate) fn set_max_queue(max: Option<usize>) {
+    GLOBAL.set_max_queue(max);
+}
+
+/// Returns the bound set by [`set_max_queue`].
+pub(crate) fn max_queue() -> Option<usize> {
+    GLOBAL.state.lock().unwrap().max_queue
+}
+
+/// Returns the load on each band, indexed by [`rank`].
+pub(crate) fn stats() -> [BandStats; BANDS] {
+    GLOBAL.state.lock().unwrap().stats()
+}
+
+/// Sets how long a queued call waits for each band it moves up, or turns
+/// aging off.
+pub(crate) fn set_aging(step: Option<Duration>) {
+    GLOBAL.set_aging(step);
+}
+
+/// Returns the step set by [`set_aging`].
+pub(crate) fn aging() -> Option<Duration> {
+    GLOBAL.state.lock().unwrap().aging
+}
+
+/// Returns the longest any call of each band, indexed by [`rank`], has waited
+/// in the queue before starting.
+pub(crate) fn max_waits() -> [Duration; BANDS] {
+    GLOBAL.state.lock().unwrap().max_waits
+}
+
+struct Job {
+    priority: Priority,
+    /// When the job was queued.
+    queued: Instant,
+    deadline: Option<Instant>,
+    operation: Option<OperationHandle>,
+    run: Box<dyn FnOnce() + Send>,
+}
+
+impl Job {
+    /// The job's priority now: its operation's, if it has one.
+    fn priority(&self) -> Priority {
+        self.operation
+            .as_ref()
+            .map_or(self.priority, OperationHandle::priority)
+    }
+
+    fn band(&self) -> usize {
+        rank(self.priority()) as usize
+    }
+}
+
+/// Returns the band a job in `band` with `deadline` is treated as being in
+/// at `now`.
+fn escalated(band: usize, deadline: Instant, now: Instant) -> usize {
+    let left = deadline.saturating_duration_since(now);
+    let escalated = if left <= ESCALATE_INTERACTIVE {
+        rank(Priority::UserInteractive)
+    } else if left <= ESCALATE_INITIATED {
+        rank(Priority::UserInitiated)
+    } else {
+        return band;
+    };
+    band.min(escalated as usize)
+}
+
+/// Folds `sample` into a recent latency, which it starts if there is none.
+fn recent(recent: Option<Duration>, sample: Duration) -> Duration {
+    recent.map_or(sample, |recent| {
+        recent - recent / RECENT_WEIGHT + sample / RECENT_WEIGHT
+    })
+}
+
+/// Returns the band a job in `band` queued at `queued` is treated as being in
+/// at `now`, moving up one band for every `step` it has waited.
+fn aged(band: usize, queued: Instant, now: Instant, step: Option<Duration>) -> usize {
+    let Some(step) = step.filter(|step| !step.is_zero()) else {
+        return band;
+    };
+    let steps = now.saturating_duration_since(queued).as_nanos() / step.as_nanos();
+    band.saturating_sub(steps.min(BANDS as u128) as usize)
+}
+
+struct State {
+    /// Queued jobs whose place doesn't change, by band, oldest first.
+    queues: [VecDeque<Job>; BANDS],
+    /// Queued jobs with a deadline or an operation handle, oldest first.
+    dynamic: Vec<Job>,
+    /// Jobs running, by band.
+    running: [usize; BANDS],
+    band_limits: [Option<usize>; BANDS],
+    /// How long a job waits for each band it moves up, if aging is on.
+    aging: Option<Duration>,
+    /// The longest any job of each band has waited to start.
+    max_waits: [Duration; BANDS],
+    /// How long recent jobs of each band waited to start.
+    recent_waits: [Option<Duration>; BANDS],
+    /// How long recent jobs of each band took to run.
+    recent_services: [Option<Duration>; BANDS],
+    /// The most jobs that may be queued at once, if bounded.
+    max_queue: Option<usize>,
+    /// Callers waiting for room in a full queue.
+    room_waiters: Vec<Waker>,
+    max_threads: usize,
+    /// Threads running, including those starting up.
+    threads: usize,
+    /// Threads spawned that have not yet taken the lock.
+    starting: usize,
+    /// Threads waiting for work.
+    idle: usize,
+}
+
+impl State {
+    /// Returns how many more jobs of `band` may start now.
+    fn room(&self, band: usize) -> usize {
+        self.band_limits[band].map_or(usize::MAX, |limit| limit.saturating_sub(self.running[band]))
+    }
+
+    /// Returns the load on each band.
+    fn stats(&self) -> [BandStats; BANDS] {
+        std::array::from_fn(|band| BandStats {
+            queued: self.queues[band].len()
+                + self.dynamic.iter().filter(|job| job.band() == band).count(),
+            in_flight: self.running[band],
+            wait: self.recent_waits[band],
+            service: self.recent_services[band],
+        })
+    }
+
+    /// Returns the number of queued jobs.
+    fn queued(&self) -> usize {
+        self.queues.iter().map(VecDeque::len).sum::<usize>() + self.dynamic.len()
+    }
+
+    /// Returns the number of queued jobs that could start now.
+    fn runnable(&self) -> usize {
+        (0..BANDS)
+            .map(|band| {
+                let dynamic = self.dynamic.iter().filter(|job| job.band() == band);
+                (self.queues[band].len() + dynamic.count()).min(self.room(band))
+            })
+            .sum()
+    }
+
+    /// Takes the most urgent job whose band has room, counting it as running.
+    fn pop(&mut self) -> Option<(usize, Job)> {
+        let now = Instant::now();
+        let dynamic = self
+            .dynamic
+            .iter()
+            .enumerate()
+            .filter_map(|(i, job)| {
+                let band = job.band();
+                if self.room(band) == 0 {
+                    return None;
+                }
+                let treated = job
+                    .deadline
+                    .map_or(band, |deadline| escalated(band, deadline, now))
+                    .min(aged(band, job.queued, now, self.aging));
+                // Earliest deadline first, then jobs without one, oldest first.
+                Some(((treated, job.deadline.is_none(), job.deadline), i, band))
+            })
+            .min();
+        // The front of each queue is its oldest job, so the most aged. Aged
+        // jobs go ahead of newer ones in the band they're treated as.
+        let queued = (0..BANDS)
+            .filter(|&band| self.room(band) > 0)
+            .filter_map(|band| {
+                let job = self.queues[band].front()?;
+                Some((aged(band, job.queued, now, self.aging), job.queued, band))
+            })
+            .min();
+        // A dynamic job goes ahead of the others in the band it's treated as.
+        let dynamic = dynamic.filter(|&((treated, _, _), _, _)| {
+            queued.is_none_or(|(queued, _, _)| treated <= queued)
+        });
+        let (band, job) = match (dynamic, queued) {
+            (Some((_, i, band)), _) => (band, self.dynamic.remove(i)),
+            (None, Some((_, _, band))) => (band, self.queues[band].pop_front()?),
+            (None, None) => return None,
+        };
+        self.running[band] += 1;
+        // Everyone waiting rechecks, so one who gave up can't swallow the wake.
+        std::mem::take(&mut self.room_waiters)
+            .into_iter()
+            .for_each(Waker::wake);
+        let waited = now.saturating_duration_since(job.queued);
+        self.max_waits[band] = self.max_waits[band].max(waited);
+        self.recent_waits[band] = Some(recent(self.recent_waits[band], waited));
+        Some((band, job))
+    }
+}
+
+struct Pool {
+    state: Mutex<State>,
+    work: Condvar,
+}
+
+impl Pool {
+    const fn new() -> Self {
+        Pool {
+            state: Mutex::new(State {
+                queues: [const { VecDeque::new() }; BANDS],
+                dynamic: Vec::new(),
+                running: [0; BANDS],
+                band_limits: [None; BANDS],
+                aging: None,
+                max_waits: [Duration::ZERO; BANDS],
+                recent_waits: [None; BANDS],
+                recent_services: [None; BANDS],
+                max_queue: None,
+                room_waiters: Vec::new(),
+                max_threads: DEFAULT_MAX_THREADS,
+                threads: 0,
+                starting: 0,
+                idle: 0,
+            }),
+            work: Condvar::new(),
+        }
+    }
+
+    /// Queues `run` without waiting for room.
+    #[cfg(test)]
+    fn submit(
+        &'static self,
+        priority: Priority,
+        deadline: Option<Instant>,
+        operation: Option<OperationHandle>,
+        run: Box<dyn FnOnce() + Send>,
+    ) {
+        let job = Job {
+            priority,
+            queued: Instant::now(),
+            deadline,
+            operation,
+            run,
+        };
+        self.enqueue(self.state.lock().unwrap(), job);
+    }
+
+    /// Queues `*job` if the queue has room, or waits for room.
+    fn poll_submit(&'static self, cx: &mut Context<'_>, job: &mut Option<Job>) -> Poll<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.max_queue.is_some_and(|max| state.queued() >= max) {
+            state.room_waiters.push(cx.waker().clone());
+            return Poll::Pending;
+        }
+        let job = job.take().expect("job is only submitted once");
+        self.enqueue(state, job);
+        Poll::Ready(())
+    }
+
+    fn enqueue(&'static self, mut state: std::sync::MutexGuard<'_, State>, job: Job) {
+        if job.deadline.is_some() || job.operation.is_some() {
+            state.dynamic.push(job);
+        } else {
+            state.queues[rank(job.priority) as usize].push_back(job);
+        }
+        self.spawn_for_queue(&mut state);
+        drop(state);
+        self.work.notify_one();
+    }
+
+    fn set_max_threads(&'static self, threads: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.max_threads = threads.max(1);
+        self.spawn_for_queue(&mut state);
+        drop(state);
+        // Surplus idle threads exit when woken.
+        self.work.notify_all();
+    }
+
+    fn set_band_limits(&'static self, limits: [Option<usize>; BANDS]) {
+        let mut state = self.state.lock().unwrap();
+        state.band_limits = limits.map(|limit| limit.map(|limit| limit.max(1)));
+        self.spawn_for_queue(&mut state);
+        drop(state);
+        // Raised limits may let waiting jobs start.
+        self.work.notify_all();
+    }
+
+    fn set_max_queue(&'static self, max: Option<usize>) {
+        let mut state = self.state.lock().unwrap();
+        state.max_queue = max.map(|max| max.max(1));
+        // A raised bound may make room.
+        std::mem::take(&mut state.room_waiters)
+            .into_iter()
+            .for_each(Waker::wake);
+    }
+
+    fn set_aging(&'static self, step: Option<Duration>) {
+        self.state.lock().unwrap().aging = step;
+    }
+
+    fn reschedule(&'static self) {
+        let mut state = self.state.lock().unwrap();
+        self.spawn_for_queue(&mut state);
+        drop(state);
+        // A job may now fit under its new band's limit.
+        self.work.notify_all();
+    }
+
+    /// Starts threads for runnable jobs that no idle or starting thread will
+    /// pick up, within the limit.
+    fn spawn_for_queue(&'static self, state: &mut State) {
+        while state.runnable() > state.idle + state.starting && state.threads < state.max_threads {
+            let spawned = std::thread::Builder::new()
+                .name("async_file worker".to_string())
+                .spawn(move || self.work_loop());
+            match spawned {
+                Ok(_) => {
+                    state.threads += 1;
+                    state.starting += 1;
+                }
+                // Existing threads will get to the work eventually.
+                Err(_) if state.threads > 0 => return,
+                Err(e) => panic!("failed to start an async_file worker thread: {e}"),
+            }
+        }
+    }
+
+    fn work_loop(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.starting -= 1;
+        let mut applied = None;
+        loop {
+            if let Some((band, job)) = state.pop() {
+                drop(state);
+                let priority = job.priority();
+                if applied != Some(priority) {
+                    io_priority::apply(priority);
+                    applied = Some(priority);
+                }
+                let started = Instant::now();
+                (job.run)();
+                let service = started.elapsed();
+                state = self.state.lock().unwrap();
+                state.running[band] -= 1;
+                state.recent_services[band] = Some(recent(state.recent_services[band], service));
+                continue;
+            }
+            if state.threads > state.max_threads {
+                break;
+            }
+            state.idle += 1;
+            let (next, wait) = self.work.wait_timeout(state, IDLE_TIMEOUT).unwrap();
+            state = next;
+            state.idle -= 1;
+            if wait.timed_out() && state.runnable() == 0 {
+                break;
+            }
+        }
+        state.threads -= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Pool, Priority};
+    use std::sync::mpsc;
+
+    /// Returns a pool of one worker thread, busy with a utility call until
+    /// the returned sender sends.
+    fn blocked_pool() -> (&'static Pool, mpsc::Sender<()>) {
+        let pool: &'static Pool = Box::leak(Box::new(Pool::new()));
+        pool.set_max_threads(1);
+        let (release, blocked) = mpsc::channel::<()>();
+        let (started, is_started) = mpsc::channel();
+        pool.submit(
+            Priority::Utility,
+            None,
+            None,
+            Box::new(move || {
+                started.send(()).unwrap();
+                blocked.recv().unwrap();
+            }),
+        );
+        is_started.recv().unwrap();
+        (pool, release)
+    }
+
+    #[test]
+    fn test_runs_urgent_work_first() {
+        let (pool, release) = blocked_pool();
+
+        let (order, ran) = mpsc::channel();
+        for (priority, name) in [
+            (Priority::Background, "background"),
+            (Priority::Utility, "utility"),
+            (Priority::UserInteractive, "interactive"),
+            (Priority::Background, "background 2"),
+        ] {
+            let order = order.clone();
+            pool.submit(
+                priority,
+                None,
+                None,
+                Box::new(move || order.send(name).unwrap()),
+            );
+        }
+        release.send(()).unwrap();
+        let ran: Vec<_> = (0..4).map(|_| ran.recv().unwrap()).collect();
+        assert_eq!(
+            ran,
+            ["interactive", "utility", "background", "background 2"]
+        );
+    }
+
+    #[test]
+    fn test_escalates_near_deadlines() {
+        use std::time::{Duration, Instant};
+        let (pool, release) = blocked_pool();
+
+        let (order, ran) = mpsc::channel();
+        let now = Instant::now();
+        for (priority, deadline, name) in [
+            (
+                Priority::Background,
+                Some(now + Duration::from_secs(60)),
+                "far",
+            ),
+            (Priority::Utility, None, "utility"),
+            (
+                Priority::Background,
+                Some(now + Duration::from_millis(500)),
+                "soon",
+            ),
+            (Priority::UserInitiated, None, "initiated"),
+            (Priority::Background, Some(now), "due"),
+            (Priority::Background, None, "background"),
+        ] {
+            let order = order.clone();
+            pool.submit(
+                priority,
+                deadline,
+                None,
+                Box::new(move || order.send(name).unwrap()),
+            );
+        }
+        release.send(()).unwrap();
+        let ran: Vec<_> = (0..6).map(|_| ran.recv().unwrap()).collect();
+        // "soon" ties with "initiated" for its band, and goes first.
+        assert_eq!(
+            ran,
+            ["due", "soon", "initiated", "utility", "far", "background"]
+        );
+    }
+
+    #[test]
+    fn test_operation_handle_moves_queued_calls() {
+        use crate::OperationHandle;
+        let (pool, release) = blocked_pool();
+
+        let (order, ran) = mpsc::channel();
+        let handle = OperationHandle::new(Priority::Background);
+        for (priority, operation, name) in [
+            (
+                Priority::Background,
+                Some(handle.clone()),
+                "scrolled into view",
+            ),
+            (Priority::UserInitiated, None, "initiated"),
+            (Priority::Background, None, "background"),
+        ] {
+            let order = order.clone();
+            pool.submit(
+                priority,
+                None,
+                operation,
+                Box::new(move || order.send(name).unwrap()),
+            );
+        }
+        handle.set_priority(Priority::UserInteractive);
+        release.send(()).unwrap();
+        let ran: Vec<_> = (0..3).map(|_| ran.recv().unwrap()).collect();
+        assert_eq!(ran, ["scrolled into view", "initiated", "background"]);
+    }
+
+    #[test]
+    fn test_ages_waiting_calls() {
+        use std::time::Duration;
+        let (pool, release) = blocked_pool();
+        pool.set_aging(Some(Duration::from_millis(10)));
+
+        let (order, ran) = mpsc::channel();
+        let submit = |priority, name| {
+            let order = order.clone();
+            pool.submit(
+                priority,
+                None,
+                None,
+                Box::new(move || order.send(name).unwrap()),
+            );
+        };
+        submit(Priority::Background, "old background");
+        // Long enough to climb all the way from background.
+        std::thread::sleep(Duration::from_millis(50));
+        submit(Priority::UserInteractive, "interactive");
+        submit(Priority::Background, "new background");
+        release.send(()).unwrap();
+        let ran: Vec<_> = (0..3).map(|_| ran.recv().unwrap()).collect();
+        assert_eq!(ran, ["old background", "interactive", "new background"]);
+        let waits = pool.state.lock().unwrap().max_waits;
+        assert!(waits[super::rank(Priority::Background) as usize] >= Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_bounded_queue() {
+        use super::Job;
+        use std::task::{Context, Waker};
+        use std::time::Instant;
+        let (pool, release) = blocked_pool();
+        pool.set_max_queue(Some(1));
+
+        let (order, ran) = mpsc::channel();
+        let job = |name| {
+            let order = order.clone();
+            Some(Job {
+                priority: Priority::Utility,
+                queued: Instant::now(),
+                deadline: None,
+                operation: None,
+                run: Box::new(move || order.send(name).unwrap()),
+            })
+        };
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut first = job("first");
+        let mut second = job("second");
+        assert!(pool.poll_submit(&mut cx, &mut first).is_ready());
+        // The queue is full until the first call starts.
+        assert!(pool.poll_submit(&mut cx, &mut second).is_pending());
+        release.send(()).unwrap();
+        assert_eq!(ran.recv().unwrap(), "first");
+        assert!(pool.poll_submit(&mut cx, &mut second).is_ready());
+        assert_eq!(ran.recv().unwrap(), "second");
+    }
+
+    #[test]
+    fn test_stats() {
+        use std::time::Duration;
+        let (pool, release) = blocked_pool();
+        let (done, finished) = mpsc::channel();
+        for _ in 0..3 {
+            let done = done.clone();
+            pool.submit(
+                Priority::Background,
+                None,
+                None,
+                Box::new(move || done.send(()).unwrap()),
+            );
+        }
+        let background = super::rank(Priority::Background) as usize;
+        let utility = super::rank(Priority::Utility) as usize;
+        let stats = pool.state.lock().unwrap().stats();
+        assert_eq!(stats[background].queued, 3);
+        assert_eq!(stats[background].in_flight, 0);
+        assert_eq!(stats[background].wait, None);
+        assert_eq!(stats[utility].in_flight, 1);
+
+        std::thread::sleep(Duration::from_millis(20));
+        release.send(()).unwrap();
+        (0..3).for_each(|_| finished.recv().unwrap());
+        let stats = pool.state.lock().unwrap().stats();
+        assert_eq!(stats[background].queued, 0);
+        assert!(stats[background].wait.unwrap() >= Duration::from_millis(20));
+        assert!(stats[utility].service.unwrap() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_band_limit() {
+        let pool: &'static Pool = Box::leak(Box::new(Pool::new()));
+        pool.set_max_threads(4);
+        let mut limits = [None; super::BANDS];
+        limits[super::rank(Priority::Background) as usize] = Some(1);
+        pool.set_band_limits(limits);
+
+        let (events, log) = mpsc::channel();
+        let (release, blocked) = mpsc::channel::<()>();
+        let blocked = std::sync::Arc::new(std::sync::Mutex::new(blocked));
+        for i in 0..3 {
+            let events = events.clone();
+            let blocked = blocked.clone();
+            pool.submit(
+                Priority::Background,
+                None,
+                None,
+                Box::new(move || {
+                    events.send(format!("background {i}")).unwrap();
+                    blocked.lock().unwrap().recv().unwrap();
+                }),
+            );
+        }
+        assert_eq!(log.recv().unwrap(), "background 0");
+        // Threads are free, so other bands aren't held up...
+        let utility = events.clone();
+        pool.submit(
+            Priority::Utility,
+            None,
+            None,
+            Box::new(move || utility.send("utility".to_string()).unwrap()),
+        );
+        assert_eq!(log.recv().unwrap(), "utility");
+        // ...but the next background call waits for the first.
+        assert!(
+            log.recv_timeout(std::time::Duration::from_millis(50))
+                .is_err()
+        );
+        release.send(()).unwrap();
+        assert_eq!(log.recv().unwrap(), "background 1");
+        release.send(()).unwrap();
+        assert_eq!(log.recv().unwrap(), "background 2");
+        release.send(()).unwrap();
+    }
+
+    #[test]
+    fn test_reorders_deep_queue_under_load() {
+        const THREADS: usize = 4;
+        let pool: &'static Pool = Box::leak(Box::new(Pool::new()));
+        pool.set_max_threads(THREADS);
+        // Occupy every worker, each until its own release.
+        let (started, is_started) = mpsc::channel();
+        let releases: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let (release, blocked) = mpsc::channel::<()>();
+                let started = started.clone();
+                pool.submit(
+                    Priority::Utility,
+                    None,
+                    None,
+                    Box::new(move || {
+                        started.send(()).unwrap();
+                        blocked.recv().unwrap();
+                    }),
+                );
+                release
+            })
+            .collect();
+        (0..THREADS).for_each(|_| is_started.recv().unwrap());
+
+        let (order, ran) = mpsc::channel();
+        let submit = |priority, name: String| {
+            let order = order.clone();
+            pool.submit(
+                priority,
+                None,
+                None,
+                Box::new(move || order.send(name).unwrap()),
+            );
+        };
+        for i in 0..50 {
+            submit(Priority::Background, format!("background {i}"));
+        }
+        submit(Priority::UserInitiated, "initiated".to_string());
+        submit(Priority::UserInteractive, "interactive".to_string());
+
+        // One freed worker drains the queue alone, so the order is exact.
+        releases[0].send(()).unwrap();
+        let ran: Vec<_> = (0..52).map(|_| ran.recv().unwrap()).collect();
+        let mut expected = vec!["interactive".to_string(), "initiated".to_string()];
+        expected.extend((0..50).map(|i| format!("background {i}")));
+        assert_eq!(ran, expected);
+        releases[1..]
+            .iter()
+            .for_each(|release| release.send(()).unwrap());
+    }
+}
@@ -0,0 +1,209 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Platform queries behind [`probe`](crate::probe) and [`fs_stats`](crate::fs_stats).
+
+use crate::{FsCapabilities, FsStats};
+use std::io;
+use std::path::Path;
+
+#[cfg(unix)]
+fn c_path(path: &Path) -> io::Result<std::ffi::CString> {
+    use std::os::unix::ffi::OsStrExt;
+    std::ffi::CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+/// Whether the filesystem supports extended attributes at all. Listing the
+/// attributes of `path` fails with `ENOTSUP` when it doesn't.
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+fn supports_xattr(path: &std::ffi::CStr) -> bool {
+    // SAFETY: a null buffer of size 0 only asks for the required length.
+    #[cfg(target_vendor = "apple")]
+    let listed = unsafe { libc::listxattr(path.as_ptr(), std::ptr::null_mut(), 0, 0) };
+    #[cfg(not(target_vendor = "apple"))]
+    let listed = unsafe { libc::listxattr(path.as_ptr(), std::ptr::null_mut(), 0) };
+    listed >= 0 || io::Error::last_os_error().raw_os_error() != Some(libc::ENOTSUP)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn probe(path: &Path) -> io::Result<FsCapabilities> {
+    const MSDOS: u32 = 0x4d44;
+    const EXFAT: u32 = 0x2011_bab0;
+    const NTFS: u32 = 0x5346_544e;
+    const SMB: u32 = 0x517b;
+    const CIFS: u32 = 0xff53_4d42;
+    const SMB2: u32 = 0xfe53_4d42;
+    const ISOFS: u32 = 0x9660;
+    const SQUASHFS: u32 = 0x7371_7368;
+    const BTRFS: u32 = 0x9123_683e;
+    const XFS: u32 = 0x5846_5342;
+    const BCACHEFS: u32 = 0xca45_1a4e;
+
+    let c_path = c_path(path)?;
+    // SAFETY: statfs is plain old data, and is only read after a successful call.
+    let mut stats: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stats) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // f_type is signed on some targets; the magic numbers are 32-bit patterns.
+    let magic = stats.f_type as u32;
+    Ok(FsCapabilities {
+        case_sensitive: !matches!(magic, MSDOS | EXFAT | NTFS | SMB | CIFS | SMB2),
+        max_filename_len: stats.f_namelen as usize,
+        reflink: matches!(magic, BTRFS | XFS | BCACHEFS),
+        sparse: !matches!(magic, MSDOS | EXFAT | ISOFS | SQUASHFS),
+        xattr: supports_xattr(&c_path),
+    })
+}
+
+#[cfg(target_vendor = "apple")]
+pub(super) fn probe(path: &Path) -> io::Result<FsCapabilities> {
+    let c_path = c_path(path)?;
+    // SAFETY: statfs is plain old data, and is only read after a successful call.
+    let mut stats: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stats) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: the kernel NUL-terminates f_fstypename.
+    let fs_type = unsafe { std::ffi::CStr::from_ptr(stats.f_fstypename.as_ptr()) };
+    let fs_type = fs_type.to_bytes();
+    let name_max = unsafe { libc::pathconf(c_path.as_ptr(), libc::_PC_NAME_MAX) };
+    if name_max < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // -1 means the filesystem doesn't say; macOS volumes are insensitive by default.
+    let case_sensitive = unsafe { libc::pathconf(c_path.as_ptr(), libc::_PC_CASE_SENSITIVE) } == 1;
+    Ok(FsCapabilities {
+        case_sensitive,
+        max_filename_len: name_max as usize,
+        reflink: fs_type == b"apfs",
+        sparse: matches!(fs_type, b"apfs" | b"zfs" | b"nfs"),
+        xattr: supports_xattr(&c_path),
+    })
+}
+
+#[cfg(all(
+    unix,
+    not(any(target_os = "linux", target_os = "android", target_vendor = "apple"))
+))]
+pub(super) fn probe(path: &Path) -> io::Result<FsCapabilities> {
+    let c_path = c_path(path)?;
+    let name_max = unsafe { libc::pathconf(c_path.as_ptr(), libc::_PC_NAME_MAX) };
+    if name_max < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(FsCapabilities {
+        case_sensitive: true,
+        max_filename_len: name_max as usize,
+        reflink: false,
+        sparse: true,
+        xattr: false,
+    })
+}
+
+/// Returns the NUL-terminated root of the volume containing `path`.
+#[cfg(windows)]
+fn volume_root(path: &Path) -> io::Result<Vec<u16>> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetVolumePathNameW;
+
+    const MAX_PATH: usize = 260;
+
+    // GetVolumePathNameW doesn't check that the path exists.
+    std::fs::symlink_metadata(path)?;
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain([0]).collect();
+    let mut root = vec![0u16; wide.len().max(MAX_PATH)];
+    // SAFETY: both buffers are valid for the lengths passed, and `wide` is NUL-terminated.
+    if unsafe { GetVolumePathNameW(wide.as_ptr(), root.as_mut_ptr(), root.len() as u32) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(root)
+}
+
+#[cfg(windows)]
+pub(super) fn probe(path: &Path) -> io::Result<FsCapabilities> {
+    use windows_sys::Win32::Storage::FileSystem::GetVolumeInformationW;
+
+    const FILE_SUPPORTS_SPARSE_FILES: u32 = 0x0000_0040;
+    const FILE_NAMED_STREAMS: u32 = 0x0004_0000;
+    const FILE_SUPPORTS_BLOCK_REFCOUNTING: u32 = 0x0800_0000;
+
+    let root = volume_root(path)?;
+    let mut max_component = 0u32;
+    let mut flags = 0u32;
+    // SAFETY: the optional name buffers are null with zero length; the out
+    // parameters point to valid u32s.
+    let ok = unsafe {
+        GetVolumeInformationW(
+            root.as_ptr(),
+            std::ptr::null_mut(),
+            0,
+            std::ptr::null_mut(),
+            &mut max_component,
+            &mut flags,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(FsCapabilities {
+        // NTFS can be case-sensitive per directory, but Windows APIs are not by default.
+        case_sensitive: false,
+        max_filename_len: max_component as usize,
+        reflink: flags & FILE_SUPPORTS_BLOCK_REFCOUNTING != 0,
+        sparse: flags & FILE_SUPPORTS_SPARSE_FILES != 0,
+        xattr: flags & FILE_NAMED_STREAMS != 0,
+    })
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(super) fn probe(_path: &Path) -> io::Result<FsCapabilities> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+#[cfg(unix)]
+pub(super) fn fs_stats(path: &Path) -> io::Result<FsStats> {
+    let c_path = c_path(path)?;
+    // SAFETY: statvfs is plain old data, and is only read after a successful call.
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stats) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // Field widths vary by target.
+    #[allow(clippy::unnecessary_cast)]
+    let (block, blocks, free, available) = (
+        stats.f_frsize as u64,
+        stats.f_blocks as u64,
+        stats.f_bfree as u64,
+        stats.f_bavail as u64,
+    );
+    Ok(FsStats {
+        total: blocks.saturating_mul(block),
+        free: free.saturating_mul(block),
+        available: available.saturating_mul(block),
+    })
+}
+
+#[cfg(windows)]
+pub(super) fn fs_stats(path: &Path) -> io::Result<FsStats> {
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let root = volume_root(path)?;
+    let (mut available, mut total, mut free) = (0u64, 0u64, 0u64);
+    // SAFETY: `root` is NUL-terminated and the out parameters point to valid u64s.
+    if unsafe { GetDiskFreeSpaceExW(root.as_ptr(), &mut available, &mut total, &mut free) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(FsStats {
+        total,
+        free,
+        available,
+    })
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(super) fn fs_stats(_path: &Path) -> io::Result<FsStats> {
+    Err(io::ErrorKind::Unsupported.into())
+}
@@ -0,0 +1,120 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! A timer for executors that bring none of their own.
+//!
+//! One thread, started on first use, sleeps until the earliest registered
+//! deadline and wakes the tasks waiting for it. A [`Sleep`] that is dropped
+//! before its deadline leaves its entry behind; waking it later is harmless.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+static GLOBAL: Timer = Timer {
+    deadlines: Mutex::new(BinaryHeap::new()),
+    changed: Condvar::new(),
+    started: OnceLock::new(),
+};
+
+/// Returns a future that completes once `duration` has passed.
+pub(crate) fn sleep(duration: Duration) -> Sleep {
+    Sleep {
+        deadline: Instant::now() + duration,
+    }
+}
+
+pub(crate) struct Sleep {
+    deadline: Instant,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(());
+        }
+        GLOBAL.register(self.deadline, cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+struct Entry {
+    deadline: Instant,
+    waker: Waker,
+}
+
+// Entries are ordered by deadline alone.
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.deadline.cmp(&other.deadline)
+    }
+}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Entry {}
+
+struct Timer {
+    /// The earliest deadline is popped first.
+    deadlines: Mutex<BinaryHeap<Reverse<Entry>>>,
+    changed: Condvar,
+    started: OnceLock<()>,
+}
+
+impl Timer {
+    fn register(&'static self, deadline: Instant, waker: Waker) {
+        self.started.get_or_init(|| {
+            std::thread::Builder::new()
+                .name("async_file timer".to_string())
+                .spawn(move || self.run())
+                .expect("failed to start the async_file timer thread");
+        });
+        self.deadlines
+            .lock()
+            .unwrap()
+            .push(Reverse(Entry { deadline, waker }));
+        self.changed.notify_one();
+    }
+
+    fn run(&self) {
+        let mut deadlines = self.deadlines.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            let mut due = Vec::new();
+            while deadlines
+                .peek()
+                .is_some_and(|Reverse(entry)| entry.deadline <= now)
+            {
+                due.push(deadlines.pop().unwrap().0.waker);
+            }
+            if !due.is_empty() {
+                drop(deadlines);
+                due.into_iter().for_each(Waker::wake);
+                deadlines = self.deadlines.lock().unwrap();
+                continue;
+            }
+            deadlines = match deadlines.peek() {
+                Some(Reverse(entry)) => {
+                    let wait = entry.deadline - now;
+                    self.changed.wait_timeout(deadlines, wait).unwrap().0
+                }
+                None => self.changed.wait(deadlines).unwrap(),
+            };
+        }
+    }
+}
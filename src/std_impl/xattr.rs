@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Extended attribute syscalls behind [`get_xattr`](crate::get_xattr) and friends.
+//!
+//! Linux, Android and Apple platforms share the `*xattr` family, with Apple
+//! adding position and option arguments. Elsewhere every call fails with
+//! `ErrorKind::Unsupported`.
+
+use std::path::Path;
+
+/// What an attribute call operates on.
+#[cfg_attr(
+    not(any(target_os = "linux", target_os = "android", target_vendor = "apple")),
+    allow(dead_code)
+)]
+pub(super) enum Target<'a> {
+    Path(&'a Path),
+    #[cfg(unix)]
+    Fd(std::os::fd::RawFd),
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+mod imp {
+    use super::Target;
+    use std::ffi::{CStr, CString, OsStr, OsString};
+    use std::io;
+    use std::os::unix::ffi::{OsStrExt, OsStringExt};
+
+    #[cfg(target_vendor = "apple")]
+    const NO_ATTR: i32 = libc::ENOATTR;
+    #[cfg(not(target_vendor = "apple"))]
+    const NO_ATTR: i32 = libc::ENODATA;
+
+    fn c_string(s: &OsStr) -> io::Result<CString> {
+        CString::new(s.as_bytes()).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+    }
+
+    /// A target resolved to what the syscalls take.
+    enum Raw {
+        Path(CString),
+        Fd(std::os::fd::RawFd),
+    }
+
+    fn raw(target: &Target<'_>) -> io::Result<Raw> {
+        Ok(match target {
+            Target::Path(path) => Raw::Path(c_string(path.as_os_str())?),
+            Target::Fd(fd) => Raw::Fd(*fd),
+        })
+    }
+
+    fn check(ret: isize) -> io::Result<usize> {
+        if ret < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(ret as usize)
+        }
+    }
+
+    /// Calls `op` with a buffer sized by a preceding size query, retrying if
+    /// the value grew in between.
+    fn read_sized(mut op: impl FnMut(*mut u8, usize) -> isize) -> io::Result<Vec<u8>> {
+        loop {
+            let len = check(op(std::ptr::null_mut(), 0))?;
+            let mut buf = vec![0u8; len];
+            match check(op(buf.as_mut_ptr(), buf.len())) {
+                Ok(len) => {
+                    buf.truncate(len);
+                    return Ok(buf);
+                }
+                Err(e) if e.raw_os_error() == Some(libc::ERANGE) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    // SAFETY (for every call below): paths and names are NUL-terminated
+    // CStrings that outlive the call, and buffers are valid for the length
+    // passed (a null buffer only with length 0).
+
+    #[cfg(not(target_vendor = "apple"))]
+    fn list_raw(raw: &Raw, buf: *mut u8, len: usize) -> isize {
+        match raw {
+            Raw::Path(p) => unsafe { libc::listxattr(p.as_ptr(), buf.cast(), len) },
+            Raw::Fd(fd) => unsafe { libc::flistxattr(*fd, buf.cast(), len) },
+        }
+    }
+
+    #[cfg(target_vendor = "apple")]
+    fn list_raw(raw: &Raw, buf: *mut u8, len: usize) -> isize {
+        match raw {
+            Raw::Path(p) => unsafe { libc::listxattr(p.as_ptr(), buf.cast(), len, 0) },
+            Raw::Fd(fd) => unsafe { libc::flistxattr(*fd, buf.cast(), len, 0) },
+        }
+    }
+
+    #[cfg(not(target_vendor = "apple"))]
+    fn get_raw(raw: &Raw, name: &CStr, buf: *mut u8, len: usize) -> isize {
+        match raw {
+            Raw::Path(p) => unsafe { libc::getxattr(p.as_ptr(), name.as_ptr(), buf.cast(), len) },
+            Raw::Fd(fd) => unsafe { libc::fgetxattr(*fd, name.as_ptr(), buf.cast(), len) },
+        }
+    }
+
+    #[cfg(target_vendor = "apple")]
+    fn get_raw(raw: &Raw, name: &CStr, buf: *mut u8, len: usize) -> isize {
+        match raw {
+            Raw::Path(p) => unsafe {
+                libc::getxattr(p.as_ptr(), name.as_ptr(), buf.cast(), len, 0, 0)
+            },
+            Raw::Fd(fd) => unsafe { libc::fgetxattr(*fd, name.as_ptr(), buf.cast(), len, 0, 0) },
+        }
+    }
+
+    #[cfg(not(target_vendor = "apple"))]
+    fn set_raw(raw: &Raw, name: &CStr, value: &[u8]) -> i32 {
+        let (ptr, len) = (value.as_ptr().cast(), value.len());
+        match raw {
+            Raw::Path(p) => unsafe { libc::setxattr(p.as_ptr(), name.as_ptr(), ptr, len, 0) },
+            Raw::Fd(fd) => unsafe { libc::fsetxattr(*fd, name.as_ptr(), ptr, len, 0) },
+        }
+    }
+
+    #[cfg(target_vendor = "apple")]
+    fn set_raw(raw: &Raw, name: &CStr, value: &[u8]) -> i32 {
+        let (ptr, len) = (value.as_ptr().cast(), value.len());
+        match raw {
+            Raw::Path(p) => unsafe { libc::setxattr(p.as_ptr(), name.as_ptr(), ptr, len, 0, 0) },
+            Raw::Fd(fd) => unsafe { libc::fsetxattr(*fd, name.as_ptr(), ptr, len, 0, 0) },
+        }
+    }
+
+    #[cfg(not(target_vendor = "apple"))]
+    fn remove_raw(raw: &Raw, name: &CStr) -> i32 {
+        match raw {
+            Raw::Path(p) => unsafe { libc::removexattr(p.as_ptr(), name.as_ptr()) },
+            Raw::Fd(fd) => unsafe { libc::fremovexattr(*fd, name.as_ptr()) },
+        }
+    }
+
+    #[cfg(target_vendor = "apple")]
+    fn remove_raw(raw: &Raw, name: &CStr) -> i32 {
+        match raw {
+            Raw::Path(p) => unsafe { libc::removexattr(p.as_ptr(), name.as_ptr(), 0) },
+            Raw::Fd(fd) => unsafe { libc::fremovexattr(*fd, name.as_ptr(), 0) },
+        }
+    }
+
+    pub(crate) fn list(target: &Target<'_>) -> io::Result<Vec<OsString>> {
+        let raw = raw(target)?;
+        let names = read_sized(|buf, len| list_raw(&raw, buf, len))?;
+        Ok(names
+            .split(|&b| b == 0)
+            .filter(|name| !name.is_empty())
+            .map(|name| OsString::from_vec(name.to_vec()))
+            .collect())
+    }
+
+    pub(crate) fn get(target: &Target<'_>, name: &OsStr) -> io::Result<Option<Vec<u8>>> {
+        let raw = raw(target)?;
+        let name = c_string(name)?;
+        match read_sized(|buf, len| get_raw(&raw, &name, buf, len)) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if e.raw_os_error() == Some(NO_ATTR) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub(crate) fn set(target: &Target<'_>, name: &OsStr, value: &[u8]) -> io::Result<()> {
+        let raw = raw(target)?;
+        let name = c_string(name)?;
+        check(set_raw(&raw, &name, value) as isize).map(drop)
+    }
+
+    pub(crate) fn remove(target: &Target<'_>, name: &OsStr) -> io::Result<()> {
+        let raw = raw(target)?;
+        let name = c_string(name)?;
+        check(remove_raw(&raw, &name) as isize).map(drop)
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_vendor = "apple")))]
+mod imp {
+    use super::Target;
+    use std::ffi::{OsStr, OsString};
+    use std::io;
+
+    pub(crate) fn list(_target: &Target<'_>) -> io::Result<Vec<OsString>> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    pub(crate) fn get(_target: &Target<'_>, _name: &OsStr) -> io::Result<Option<Vec<u8>>> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    pub(crate) fn set(_target: &Target<'_>, _name: &OsStr, _value: &[u8]) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    pub(crate) fn remove(_target: &Target<'_>, _name: &OsStr) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+pub(super) use imp::{get, list, remove, set};
@@ -0,0 +1,265 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Reading files as text for display.
+//!
+//! Editors and viewers have to show *something* for every file a user opens:
+//! files with a byte-order mark, legacy 8-bit encodings, stray invalid bytes,
+//! or logs far too large to load. [`File::text`](crate::File::text) handles all
+//! of these in one call, guided by [`TextOptions`].
+
+use crate::{Error, sys};
+
+/// What [`File::text_with`](crate::File::text_with) does with bytes that are
+/// not valid UTF-8.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum TextFallback {
+    /// Replace each invalid sequence with U+FFFD REPLACEMENT CHARACTER.
+    #[default]
+    Lossy,
+    /// Decode the whole file as Latin-1 (ISO 8859-1), where every byte is a
+    /// character. Suits legacy Western European text.
+    Latin1,
+    /// Fail with an error.
+    Strict,
+}
+
+/// The encoding a file was decoded from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TextEncoding {
+    /// UTF-8, with or without a byte-order mark.
+    Utf8,
+    /// UTF-16, little-endian, detected by its byte-order mark.
+    Utf16Le,
+    /// UTF-16, big-endian, detected by its byte-order mark.
+    Utf16Be,
+    /// Latin-1, used by [`TextFallback::Latin1`] for invalid UTF-8.
+    Latin1,
+}
+
+/// Options controlling [`File::text_with`](crate::File::text_with).
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{File, Priority, TextFallback, TextOptions};
+///
+/// let file = File::open("server.log", Priority::unit_test()).await?;
+/// let options = TextOptions::new()
+///     .max_len(16 * 1024 * 1024)
+///     .fallback(TextFallback::Latin1);
+/// let text = file.text_with(&options, Priority::unit_test()).await?;
+/// if text.is_truncated() {
+///     println!("(showing the first 16 MiB)");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TextOptions {
+    max_len: Option<u64>,
+    fallback: TextFallback,
+}
+
+impl TextOptions {
+    /// Creates options with default values: no size limit, and invalid UTF-8
+    /// replaced with U+FFFD.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of bytes to read. Longer files are truncated,
+    /// and the result reports [`is_truncated`](Text::is_truncated).
+    pub fn max_len(mut self, max_len: u64) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Sets what to do with bytes that are not valid UTF-8.
+    pub fn fallback(mut self, fallback: TextFallback) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Returns the configured size limit, if any.
+    pub fn get_max_len(&self) -> Option<u64> {
+        self.max_len
+    }
+
+    /// Returns the configured fallback.
+    pub fn get_fallback(&self) -> TextFallback {
+        self.fallback
+    }
+}
+
+/// The contents of a file decoded as text, returned by
+/// [`File::text`](crate::File::text).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Text {
+    text: String,
+    encoding: TextEncoding,
+    lossy: bool,
+    truncated: bool,
+}
+
+impl Text {
+    /// Returns the text.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Consumes the result, returning the text.
+    pub fn into_string(self) -> String {
+        self.text
+    }
+
+    /// Returns the encoding the file was decoded from.
+    pub fn encoding(&self) -> TextEncoding {
+        self.encoding
+    }
+
+    /// Returns `true` if invalid sequences were replaced with U+FFFD.
+    pub fn is_lossy(&self) -> bool {
+        self.lossy
+    }
+
+    /// Returns `true` if the file was longer than
+    /// [`TextOptions::max_len`] and only its beginning was read.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+impl std::fmt::Display for Text {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Decodes `bytes`, which were cut short at the size limit if `truncated`.
+pub(crate) fn decode(bytes: &[u8], truncated: bool, fallback: TextFallback) -> Result<Text, Error> {
+    let text = |text, encoding, lossy| Text {
+        text,
+        encoding,
+        lossy,
+        truncated,
+    };
+    if let Some(rest) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        return decode_utf8(rest, truncated, fallback).map(|(s, e, l)| text(s, e, l));
+    }
+    if let Some(rest) = bytes.strip_prefix(b"\xFF\xFE") {
+        let (s, lossy) = decode_utf16(rest, truncated, u16::from_le_bytes);
+        return Ok(text(s, TextEncoding::Utf16Le, lossy));
+    }
+    if let Some(rest) = bytes.strip_prefix(b"\xFE\xFF") {
+        let (s, lossy) = decode_utf16(rest, truncated, u16::from_be_bytes);
+        return Ok(text(s, TextEncoding::Utf16Be, lossy));
+    }
+    decode_utf8(bytes, truncated, fallback).map(|(s, e, l)| text(s, e, l))
+}
+
+fn decode_utf8(
+    bytes: &[u8],
+    truncated: bool,
+    fallback: TextFallback,
+) -> Result<(String, TextEncoding, bool), Error> {
+    let bytes = match std::str::from_utf8(bytes) {
+        Ok(s) => return Ok((s.to_owned(), TextEncoding::Utf8, false)),
+        // A character split by the size limit isn't an encoding error.
+        Err(e) if truncated && e.error_len().is_none() => &bytes[..e.valid_up_to()],
+        Err(_) => bytes,
+    };
+    match std::str::from_utf8(bytes) {
+        Ok(s) => Ok((s.to_owned(), TextEncoding::Utf8, false)),
+        Err(e) => match fallback {
+            TextFallback::Lossy => Ok((
+                String::from_utf8_lossy(bytes).into_owned(),
+                TextEncoding::Utf8,
+                true,
+            )),
+            TextFallback::Latin1 => Ok((
+                bytes.iter().map(|&b| char::from(b)).collect(),
+                TextEncoding::Latin1,
+                false,
+            )),
+            TextFallback::Strict => Err(Error(sys::Error::InvalidUtf8(e))),
+        },
+    }
+}
+
+/// Decodes UTF-16, always replacing unpaired surrogates.
+fn decode_utf16(bytes: &[u8], truncated: bool, unit: fn([u8; 2]) -> u16) -> (String, bool) {
+    let mut units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| unit([pair[0], pair[1]]))
+        .collect();
+    // Half of a surrogate pair split by the size limit isn't an encoding error.
+    if truncated && units.last().is_some_and(|u| (0xD800..0xDC00).contains(u)) {
+        units.pop();
+    }
+    let mut lossy = false;
+    let mut text: String = char::decode_utf16(units)
+        .map(|c| {
+            c.unwrap_or_else(|_| {
+                lossy = true;
+                char::REPLACEMENT_CHARACTER
+            })
+        })
+        .collect();
+    if bytes.len() % 2 == 1 && !truncated {
+        text.push(char::REPLACEMENT_CHARACTER);
+        lossy = true;
+    }
+    (text, lossy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TextEncoding, TextFallback, decode};
+
+    #[test]
+    fn test_decode_utf8() {
+        let text = decode("héllo".as_bytes(), false, TextFallback::Strict).unwrap();
+        assert_eq!(text.as_str(), "héllo");
+        assert_eq!(text.encoding(), TextEncoding::Utf8);
+        assert!(!text.is_lossy() && !text.is_truncated());
+
+        let text = decode(b"\xEF\xBB\xBFbom", false, TextFallback::Strict).unwrap();
+        assert_eq!(text.as_str(), "bom");
+    }
+
+    #[test]
+    fn test_decode_fallbacks() {
+        let bytes = b"caf\xE9";
+        let lossy = decode(bytes, false, TextFallback::Lossy).unwrap();
+        assert_eq!(lossy.as_str(), "caf\u{FFFD}");
+        assert!(lossy.is_lossy());
+        let latin1 = decode(bytes, false, TextFallback::Latin1).unwrap();
+        assert_eq!(latin1.as_str(), "café");
+        assert_eq!(latin1.encoding(), TextEncoding::Latin1);
+        assert!(decode(bytes, false, TextFallback::Strict).is_err());
+    }
+
+    #[test]
+    fn test_decode_truncated_mid_character() {
+        // "é" is two bytes; the limit cut it in half.
+        let text = decode(b"caf\xC3", true, TextFallback::Strict).unwrap();
+        assert_eq!(text.as_str(), "caf");
+        assert!(text.is_truncated() && !text.is_lossy());
+    }
+
+    #[test]
+    fn test_decode_utf16() {
+        let le = decode(b"\xFF\xFEh\0i\0", false, TextFallback::Strict).unwrap();
+        assert_eq!(le.as_str(), "hi");
+        assert_eq!(le.encoding(), TextEncoding::Utf16Le);
+        let be = decode(b"\xFE\xFF\0h\0i", false, TextFallback::Strict).unwrap();
+        assert_eq!(be.as_str(), "hi");
+        assert_eq!(be.encoding(), TextEncoding::Utf16Be);
+        // An odd trailing byte cut off by the limit is dropped.
+        let cut = decode(b"\xFF\xFEh\0i", true, TextFallback::Strict).unwrap();
+        assert_eq!(cut.as_str(), "h");
+    }
+}
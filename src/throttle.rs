@@ -0,0 +1,221 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Rate limits for less urgent I/O.
+//!
+//! Throttled operations share one schedule: each one admitted pushes the time
+//! the next may start later by its share of the limits, so over time they
+//! average out to the configured rates however many tasks issue them. More
+//! urgent operations don't consult the schedule at all.
+
+use crate::{Priority, rank, sys};
+use std::sync::Mutex;
+use std::time::Duration;
+
+static GLOBAL: Mutex<State> = Mutex::new(State {
+    throttle: None,
+    next_start: None,
+});
+
+/// Rate limits for operations at or below a priority, set with
+/// [`set_throttle`].
+///
+/// Operations at [`get_priority`](Throttle::get_priority) or a less urgent
+/// priority are limited to [`ops_per_sec`](Throttle::ops_per_sec) operations
+/// and [`bytes_per_sec`](Throttle::bytes_per_sec) bytes, averaged over time;
+/// more urgent operations are never delayed. With neither limit set, nothing
+/// is throttled. [`Priority::Unknown`] is treated as [`Priority::Utility`], as
+/// the worker pool treats it.
+///
+/// # Examples
+///
+/// ```
+/// use async_file::{Priority, Throttle, set_throttle};
+///
+/// // Keep background indexing to 20 MiB/s and 200 reads a second.
+/// set_throttle(Some(
+///     Throttle::new(Priority::Background)
+///         .bytes_per_sec(20 * 1024 * 1024)
+///         .ops_per_sec(200),
+/// ));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Throttle {
+    priority: Priority,
+    bytes_per_sec: Option<u64>,
+    ops_per_sec: Option<u32>,
+}
+
+impl Throttle {
+    /// Creates a throttle, with no limits yet, for operations at `priority`
+    /// or a less urgent one.
+    pub fn new(priority: Priority) -> Self {
+        Throttle {
+            priority,
+            bytes_per_sec: None,
+            ops_per_sec: None,
+        }
+    }
+
+    /// Sets how many bytes a second throttled operations may read or write.
+    /// Zero is treated as one.
+    pub fn bytes_per_sec(mut self, bytes: u64) -> Self {
+        self.bytes_per_sec = Some(bytes.max(1));
+        self
+    }
+
+    /// Sets how many throttled operations may start each second. Zero is
+    /// treated as one.
+    pub fn ops_per_sec(mut self, ops: u32) -> Self {
+        self.ops_per_sec = Some(ops.max(1));
+        self
+    }
+
+    /// Returns the most urgent priority that is throttled.
+    pub fn get_priority(&self) -> Priority {
+        self.priority
+    }
+
+    /// Returns the byte rate limit, if any.
+    pub fn get_bytes_per_sec(&self) -> Option<u64> {
+        self.bytes_per_sec
+    }
+
+    /// Returns the operation rate limit, if any.
+    pub fn get_ops_per_sec(&self) -> Option<u32> {
+        self.ops_per_sec
+    }
+
+    fn applies_to(&self, priority: Priority) -> bool {
+        rank(priority) >= rank(self.priority)
+    }
+}
+
+/// Limits the rate of less urgent I/O, or removes the limits with `None`.
+///
+/// Reads through a [`File`](crate::File), [`read_once`](crate::read_once), and
+/// the writes of [`copy`](crate::copy) and [`io_copy`](crate::io_copy) count
+/// towards the limits. A throttled operation waits before it starts until the
+/// schedule allows it; its bytes are counted once it finishes, delaying the
+/// throttled operations after it. Operations already waiting keep to the
+/// schedule they joined when the throttle changes.
+///
+/// Because bytes are counted after the fact, throttled operations that start
+/// together can briefly exceed the byte rate; the ones after them wait longer
+/// to make up for it. Pages of a memory-mapped
+/// [`read_all_with`](crate::File::read_all_with) are read by the operating
+/// system on access and are not counted.
+///
+/// # Examples
+///
+/// ```
+/// use async_file::{Priority, Throttle, set_throttle, throttle};
+///
+/// set_throttle(Some(Throttle::new(Priority::Background).bytes_per_sec(1 << 20)));
+/// assert_eq!(throttle().unwrap().get_bytes_per_sec(), Some(1 << 20));
+/// set_throttle(None);
+/// ```
+pub fn set_throttle(throttle: Option<Throttle>) {
+    let mut state = GLOBAL.lock().unwrap();
+    state.throttle = throttle;
+    state.next_start = None;
+}
+
+/// Returns the throttle set with [`set_throttle`], if any.
+pub fn throttle() -> Option<Throttle> {
+    GLOBAL.lock().unwrap().throttle.clone()
+}
+
+struct State {
+    throttle: Option<Throttle>,
+    /// When the next throttled operation may start; `None` for now.
+    next_start: Option<sys::Instant>,
+}
+
+impl State {
+    /// Moves the next start `cost` later, counting from now if it has passed.
+    fn push_back(&mut self, cost: Duration) {
+        let now = sys::Instant::now();
+        let from = self.next_start.filter(|&next| next > now).unwrap_or(now);
+        self.next_start = Some(from + cost);
+    }
+}
+
+/// Waits until an operation at `priority` may start.
+pub(crate) async fn admit(priority: Priority) {
+    loop {
+        let wait = {
+            let mut state = GLOBAL.lock().unwrap();
+            let Some(throttle) = state.throttle.clone() else {
+                return;
+            };
+            if !throttle.applies_to(priority) {
+                return;
+            }
+            let now = sys::Instant::now();
+            match state.next_start {
+                Some(next) if next > now => next - now,
+                _ => {
+                    if let Some(ops) = throttle.ops_per_sec {
+                        state.push_back(Duration::from_secs(1) / ops);
+                    }
+                    return;
+                }
+            }
+        };
+        sys::delay(wait).await;
+    }
+}
+
+/// Counts `bytes` read or written by an operation at `priority`.
+pub(crate) fn charge(priority: Priority, bytes: u64) {
+    let mut state = GLOBAL.lock().unwrap();
+    let Some(throttle) = &state.throttle else {
+        return;
+    };
+    if !throttle.applies_to(priority) || bytes == 0 {
+        return;
+    }
+    if let Some(rate) = throttle.bytes_per_sec {
+        let cost = Duration::from_secs_f64(bytes as f64 / rate as f64);
+        state.push_back(cost);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::{Throttle, admit, charge, set_throttle};
+    use crate::Priority;
+    use std::time::{Duration, Instant};
+
+    // The throttle is global, so its tests run as one.
+    #[test_executors::async_test]
+    async fn test_throttle() {
+        set_throttle(Some(
+            Throttle::new(Priority::Utility)
+                .ops_per_sec(50)
+                .bytes_per_sec(1000),
+        ));
+
+        // 50 ops a second: the fifth waits for the first four.
+        let started = Instant::now();
+        for _ in 0..5 {
+            admit(Priority::Background).await;
+        }
+        assert!(started.elapsed() >= Duration::from_millis(75));
+
+        // 100 bytes at 1000 a second delay the next op by about 100ms, but
+        // not a more urgent one.
+        charge(Priority::Utility, 100);
+        let started = Instant::now();
+        admit(Priority::UserInitiated).await;
+        assert!(started.elapsed() < Duration::from_millis(50));
+        admit(Priority::Unknown).await;
+        assert!(started.elapsed() >= Duration::from_millis(90));
+
+        set_throttle(None);
+        charge(Priority::Background, 1_000_000);
+        let started = Instant::now();
+        admit(Priority::Background).await;
+        assert!(started.elapsed() < Duration::from_millis(50));
+    }
+}
@@ -0,0 +1,140 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Giving up on operations that take too long.
+
+use crate::{Error, sys};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// Adds [`timeout`](TimeoutExt::timeout) to the crate's operations.
+///
+/// Implemented for every future that resolves to `Result<T, Error>`, which
+/// covers [`File::open`](crate::File::open), [`File::read`](crate::File::read)
+/// and the rest of the crate's async functions.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{File, Priority, TimeoutExt};
+/// use std::time::Duration;
+///
+/// let file = File::open("/dev/zero", Priority::unit_test())
+///     .timeout(Duration::from_secs(5))
+///     .await?;
+/// match file.read(1024, Priority::unit_test()).timeout(Duration::from_secs(1)).await {
+///     Ok(data) => println!("read {} bytes", data.len()),
+///     Err(e) if e.is_timed_out() => println!("gave up"),
+///     Err(e) => return Err(e),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub trait TimeoutExt: Future + Sized {
+    /// Fails the operation with an error for which
+    /// [`Error::is_timed_out`] is true if it hasn't finished within
+    /// `duration`.
+    ///
+    /// The time starts when the returned future is first polled. When it runs
+    /// out, the operation's future is dropped, which cancels it the same way
+    /// dropping it yourself would: a native read that already reached the
+    /// worker threads runs to completion in the background, and a WASM fetch
+    /// is aborted.
+    fn timeout(self, duration: Duration) -> Timeout<Self>;
+}
+
+impl<F, T> TimeoutExt for F
+where
+    F: Future<Output = Result<T, Error>>,
+{
+    fn timeout(self, duration: Duration) -> Timeout<Self> {
+        Timeout {
+            future: Some(Box::pin(self)),
+            duration,
+            timer: None,
+        }
+    }
+}
+
+/// The future returned by [`TimeoutExt::timeout`].
+#[must_use = "futures do nothing unless awaited"]
+pub struct Timeout<F> {
+    /// `None` once the operation has finished or been dropped.
+    future: Option<Pin<Box<F>>>,
+    duration: Duration,
+    /// Started on the first poll.
+    timer: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+}
+
+impl<F> std::fmt::Debug for Timeout<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Timeout")
+            .field("duration", &self.duration)
+            .field("finished", &self.future.is_none())
+            .finish()
+    }
+}
+
+impl<F, T> Future for Timeout<F>
+where
+    F: Future<Output = Result<T, Error>>,
+{
+    type Output = Result<T, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let future = self
+            .future
+            .as_mut()
+            .expect("Timeout polled after completion");
+        if let Poll::Ready(result) = future.as_mut().poll(cx) {
+            self.future = None;
+            return Poll::Ready(result);
+        }
+        let duration = self.duration;
+        let timer = self
+            .timer
+            .get_or_insert_with(|| Box::pin(sys::delay(duration)));
+        match timer.as_mut().poll(cx) {
+            Poll::Ready(()) => {
+                self.future = None;
+                Poll::Ready(Err(Error(sys::Error::timed_out())))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::TimeoutExt;
+    use crate::Error;
+    use std::time::Duration;
+
+    #[test_executors::async_test]
+    async fn test_timeout_expires() {
+        let held = std::sync::Arc::new(());
+        let pending = {
+            let held = held.clone();
+            async move {
+                std::future::pending::<()>().await;
+                drop(held);
+                Ok::<(), Error>(())
+            }
+        };
+        let error = pending
+            .timeout(Duration::from_millis(20))
+            .await
+            .unwrap_err();
+        assert!(error.is_timed_out());
+        // The operation was dropped, not left running.
+        assert_eq!(std::sync::Arc::strong_count(&held), 1);
+    }
+
+    #[test_executors::async_test]
+    async fn test_timeout_passes_result() {
+        let ready = async { Ok::<u32, Error>(7) };
+        assert_eq!(ready.timeout(Duration::from_secs(60)).await.unwrap(), 7);
+    }
+}
@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Setting file timestamps.
+
+use crate::{Error, Priority, sys};
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Timestamps to apply with [`set_times`] or [`File::set_times`](crate::File::set_times).
+///
+/// Times that are not set are left unchanged.
+///
+/// # Examples
+///
+/// Preserving timestamps across a copy:
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{copy, metadata, set_times, FileTimes, Priority};
+///
+/// let source = metadata("photos/cat.jpg", Priority::unit_test()).await?;
+/// copy("photos/cat.jpg", "backup/cat.jpg", Priority::unit_test()).await?;
+/// let times = FileTimes::new()
+///     .set_modified(source.modified()?)
+///     .set_accessed(source.accessed()?);
+/// set_times("backup/cat.jpg", times, Priority::unit_test()).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileTimes {
+    accessed: Option<SystemTime>,
+    modified: Option<SystemTime>,
+}
+
+impl FileTimes {
+    /// Creates a value that changes nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the last access time.
+    pub fn set_accessed(mut self, accessed: SystemTime) -> Self {
+        self.accessed = Some(accessed);
+        self
+    }
+
+    /// Sets the last modification time.
+    pub fn set_modified(mut self, modified: SystemTime) -> Self {
+        self.modified = Some(modified);
+        self
+    }
+
+    /// Returns the access time to apply, if any.
+    pub fn accessed(&self) -> Option<SystemTime> {
+        self.accessed
+    }
+
+    /// Returns the modification time to apply, if any.
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+}
+
+/// Sets the access and modification times of a file or directory.
+///
+/// Symlinks are followed. This works on files the caller owns even when they
+/// are read-only.
+///
+/// # Arguments
+///
+/// * `path` - The file or directory to change
+/// * `times` - The times to apply
+/// * `priority` - The priority for this operation
+///
+/// # Errors
+///
+/// Returns an error if `path` does not exist, the caller may not change its
+/// times, or a time cannot be represented by the filesystem. On WASM, where
+/// files are served over HTTP, this always fails.
+pub async fn set_times(
+    path: impl AsRef<Path>,
+    times: FileTimes,
+    priority: Priority,
+) -> Result<(), Error> {
+    sys::set_times(path, times, priority).await.map_err(Error)
+}
+
+/// Sets the modification time of a file or directory.
+///
+/// This is shorthand for [`set_times`] with only a modification time.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{set_modified, Priority};
+/// use std::time::SystemTime;
+///
+/// // Mark the output as fresh so the build tool doesn't regenerate it
+/// set_modified("out/bundle.js", SystemTime::now(), Priority::unit_test()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn set_modified(
+    path: impl AsRef<Path>,
+    modified: SystemTime,
+    priority: Priority,
+) -> Result<(), Error> {
+    set_times(path, FileTimes::new().set_modified(modified), priority).await
+}
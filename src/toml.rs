@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Deserializing whole TOML documents from files.
+
+use crate::{Data, Error, File, Priority, sys};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+impl Data {
+    /// Deserializes the bytes as a TOML document.
+    ///
+    /// The result may borrow from the buffer, so `&str` fields are read
+    /// without copying (as long as they contain no escapes).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not UTF-8, or not a valid TOML `T`.
+    /// The error names the line it was found on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, Priority};
+    /// use std::collections::HashMap;
+    ///
+    /// // A table of asset names, such as hero = "hero.png"
+    /// let file = File::open("assets.toml", Priority::unit_test()).await?;
+    /// let data = file.read_all(Priority::unit_test()).await?;
+    /// let assets: HashMap<&str, &str> = data.deserialize_toml()?;
+    /// println!("{} assets", assets.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn deserialize_toml<'de, T: Deserialize<'de>>(&'de self) -> Result<T, Error> {
+        let text = std::str::from_utf8(self).map_err(|e| Error(sys::Error::InvalidUtf8(e)))?;
+        toml::from_str(text).map_err(|source| toml_error(text, source))
+    }
+}
+
+impl File {
+    /// Reads the rest of the file and deserializes it as a TOML document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails, or if the contents are not a valid
+    /// TOML `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, Priority};
+    /// use std::collections::HashMap;
+    ///
+    /// let file = File::open("ports.toml", Priority::unit_test()).await?;
+    /// let ports: HashMap<String, u16> = file.read_toml(Priority::unit_test()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_toml<T: DeserializeOwned>(&self, priority: Priority) -> Result<T, Error> {
+        self.read_all(priority).await?.deserialize_toml()
+    }
+}
+
+/// Errors without a span, such as a missing field, are reported on line 1.
+fn toml_error(text: &str, source: toml::de::Error) -> Error {
+    let start = source.span().map_or(0, |span| span.start);
+    let line = text.as_bytes()[..start.min(text.len())]
+        .iter()
+        .filter(|&&b| b == b'\n')
+        .count() as u64
+        + 1;
+    Error(sys::Error::Toml {
+        line,
+        source: Box::new(source),
+    })
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use crate::{Data, File, Priority};
+
+    #[test]
+    fn test_deserialize_toml_borrows() {
+        let data = Data::from_boxed_slice(Box::from(&b"name = \"atlas\""[..]));
+        let parsed: std::collections::HashMap<&str, &str> = data.deserialize_toml().unwrap();
+        let name = parsed["name"];
+        assert_eq!(name, "atlas");
+        assert!(data.as_ptr_range().contains(&name.as_ptr()));
+    }
+
+    #[test_executors::async_test]
+    async fn test_read_toml() {
+        logwise::context::Context::reset("test_read_toml".to_string());
+        let path = crate::tests::temp_path("read_toml.toml");
+        std::fs::write(&path, "numbers = [1, 2,\n 3]").unwrap();
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        let parsed: std::collections::HashMap<String, Vec<u32>> =
+            file.read_toml(Priority::unit_test()).await.unwrap();
+        assert_eq!(parsed["numbers"], [1, 2, 3]);
+
+        std::fs::write(&path, "numbers = [1, 2,\n oops]").unwrap();
+        let file = File::open(&path, Priority::unit_test()).await.unwrap();
+        let error = file
+            .read_toml::<std::collections::HashMap<String, Vec<u32>>>(Priority::unit_test())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("line 2"), "{error}");
+    }
+}
@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Unix-specific extensions.
+//!
+//! [`File`] implements [`AsFd`] and [`AsRawFd`], and converts from an
+//! [`OwnedFd`], so its descriptor can be used with calls this crate doesn't
+//! wrap, such as `flock`.
+
+use crate::{File, Metadata};
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, OwnedFd, RawFd};
+
+/// Unix-specific fields of [`Metadata`], mirroring
+/// `std::os::unix::fs::MetadataExt`.
+///
+/// Hardlinks to the same file share a device and inode number, so tools that
+/// copy or deduplicate a tree can use [`dev`](MetadataExt::dev) and
+/// [`ino`](MetadataExt::ino) to recognize a file they have already seen.
+///
+/// This trait is sealed; it is only implemented for [`Metadata`].
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::unix::MetadataExt;
+/// use async_file::{symlink_metadata, Priority};
+///
+/// let a = symlink_metadata("backup/a", Priority::unit_test()).await?;
+/// let b = symlink_metadata("backup/b", Priority::unit_test()).await?;
+/// if (a.dev(), a.ino()) == (b.dev(), b.ino()) {
+///     println!("a and b are the same file");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub trait MetadataExt: sealed::Sealed {
+    /// Returns the ID of the device containing the file.
+    fn dev(&self) -> u64;
+    /// Returns the inode number.
+    fn ino(&self) -> u64;
+    /// Returns the number of hard links to the file.
+    fn nlink(&self) -> u64;
+    /// Returns the user ID of the owner.
+    fn uid(&self) -> u32;
+    /// Returns the group ID of the owner.
+    fn gid(&self) -> u32;
+    /// Returns the number of 512-byte blocks allocated to the file.
+    fn blocks(&self) -> u64;
+}
+
+impl MetadataExt for Metadata {
+    fn dev(&self) -> u64 {
+        std::os::unix::fs::MetadataExt::dev(self.0.as_std())
+    }
+
+    fn ino(&self) -> u64 {
+        std::os::unix::fs::MetadataExt::ino(self.0.as_std())
+    }
+
+    fn nlink(&self) -> u64 {
+        std::os::unix::fs::MetadataExt::nlink(self.0.as_std())
+    }
+
+    fn uid(&self) -> u32 {
+        std::os::unix::fs::MetadataExt::uid(self.0.as_std())
+    }
+
+    fn gid(&self) -> u32 {
+        std::os::unix::fs::MetadataExt::gid(self.0.as_std())
+    }
+
+    fn blocks(&self) -> u64 {
+        std::os::unix::fs::MetadataExt::blocks(self.0.as_std())
+    }
+}
+
+/// Borrows the descriptor, for calls such as `flock` or `posix_fadvise`.
+///
+/// Reads don't use the descriptor's offset, so moving it has no effect on
+/// them, and closing the descriptor through another handle makes them fail.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{File, Priority};
+/// use std::os::fd::AsRawFd;
+///
+/// let file = File::open("/dev/zero", Priority::unit_test()).await?;
+/// println!("reading from descriptor {}", file.as_raw_fd());
+/// # Ok(())
+/// # }
+/// ```
+impl AsFd for File {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_std().as_fd()
+    }
+}
+
+impl AsRawFd for File {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_std().as_raw_fd()
+    }
+}
+
+/// Wraps the descriptor as [`File::from_owned_fd`] does.
+impl From<OwnedFd> for File {
+    fn from(fd: OwnedFd) -> Self {
+        File::from_owned_fd(fd)
+    }
+}
+
+impl File {
+    /// Wraps an open descriptor, such as one passed in by systemd, as
+    /// [`File::from_std`] wraps a `std::fs::File`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() -> Result<(), async_file::Error> {
+    /// use async_file::{File, Priority};
+    /// use std::os::fd::{FromRawFd, OwnedFd};
+    ///
+    /// // SAFETY: descriptor 3 was passed to this process open, and nothing
+    /// // else owns it.
+    /// let fd = unsafe { OwnedFd::from_raw_fd(3) };
+    /// let file = File::from_owned_fd(fd);
+    /// let data = file.read(4096, Priority::unit_test()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_owned_fd(fd: OwnedFd) -> Self {
+        File::from_std(std::fs::File::from(fd))
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for crate::Metadata {}
+}
@@ -0,0 +1,259 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Recursive directory traversal.
+//!
+//! Symlinks (and, on Windows, junctions) are where naive walkers go wrong: a
+//! link back to an ancestor loops forever, and a link to another drive quietly
+//! turns a walk of a project into a walk of a disk. The walker here applies a
+//! [`SymlinkPolicy`] to every link, never visits a directory twice when following
+//! links, and can be kept to the volume it started on.
+
+use crate::{BulkResult, Error, Priority, sys};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// How a directory walk treats symlinks and junctions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum SymlinkPolicy {
+    /// Report links as [`EntryKind::Symlink`] without following them.
+    #[default]
+    NoFollow,
+    /// Follow links, reporting them as the kind of entry they point to and
+    /// descending into linked directories. Dangling links are reported as
+    /// [`EntryKind::Symlink`].
+    Follow,
+    /// Leave links out of the walk entirely.
+    Skip,
+}
+
+/// The kind of an entry found by [`walk_dir`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EntryKind {
+    /// A regular file, or anything else that is neither a directory nor a link.
+    File,
+    /// A directory.
+    Dir,
+    /// A symlink or junction that was not followed.
+    Symlink,
+}
+
+/// Options controlling [`walk_dir`] and [`copy_dir_all_with`](crate::copy_dir_all_with).
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() {
+/// use async_file::{walk_dir, Priority, SymlinkPolicy, WalkOptions};
+///
+/// let options = WalkOptions::new()
+///     .symlinks(SymlinkPolicy::Follow)
+///     .same_volume(true);
+/// let entries = walk_dir("assets", &options, Priority::unit_test()).await;
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WalkOptions {
+    symlinks: SymlinkPolicy,
+    same_volume: bool,
+}
+
+impl WalkOptions {
+    /// Creates options with default values: links are not followed, and the
+    /// walk may cross volumes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how symlinks and junctions are treated.
+    pub fn symlinks(mut self, policy: SymlinkPolicy) -> Self {
+        self.symlinks = policy;
+        self
+    }
+
+    /// Sets whether to stay on the volume of the root.
+    ///
+    /// When set, directories on other volumes (mount points, or linked
+    /// directories on other drives) are reported but not descended into.
+    ///
+    /// On Windows, volumes are distinguished by drive or UNC share, so a volume
+    /// mounted into a folder is not detected.
+    pub fn same_volume(mut self, same_volume: bool) -> Self {
+        self.same_volume = same_volume;
+        self
+    }
+
+    /// Returns the configured symlink policy.
+    pub fn get_symlinks(&self) -> SymlinkPolicy {
+        self.symlinks
+    }
+
+    /// Returns whether the walk stays on the root's volume.
+    pub fn get_same_volume(&self) -> bool {
+        self.same_volume
+    }
+}
+
+/// Lists every entry below `root`, recursively.
+///
+/// The root itself is not reported. Entries of a directory are reported in name
+/// order, before the contents of any of its subdirectories.
+///
+/// A directory that cannot be listed is reported as a failure and the walk
+/// continues elsewhere. When following links, a linked directory that has
+/// already been visited is reported as a failure instead of being walked again,
+/// which is what keeps cycles from looping forever.
+///
+/// On WASM, where there are no directory listings, the walk always fails.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() {
+/// use async_file::{walk_dir, EntryKind, Priority, WalkOptions};
+///
+/// let result = walk_dir("src", &WalkOptions::new(), Priority::unit_test()).await;
+/// for (path, kind) in result.succeeded() {
+///     if *kind == EntryKind::File {
+///         println!("{}", path.display());
+///     }
+/// }
+/// # }
+/// ```
+pub async fn walk_dir(
+    root: impl AsRef<Path>,
+    options: &WalkOptions,
+    priority: Priority,
+) -> BulkResult<EntryKind> {
+    let mut result = BulkResult::new();
+    let mut walker = Walker::new(root.as_ref(), options, priority);
+    while let Some(next) = walker.next().await {
+        match next {
+            Ok((path, kind)) => result.push(path, Ok(kind)),
+            Err((path, e)) => result.push(path, Err(e)),
+        }
+    }
+    result
+}
+
+/// An incremental walk, for operations that act on each entry as it is found.
+pub(crate) struct Walker<'a> {
+    root: PathBuf,
+    options: &'a WalkOptions,
+    priority: Priority,
+    started: bool,
+    root_volume: Option<sys::VolumeId>,
+    visited: HashSet<PathBuf>,
+    pending: Vec<PathBuf>,
+    current: std::vec::IntoIter<sys::DirEntry>,
+    descend: Vec<PathBuf>,
+}
+
+impl<'a> Walker<'a> {
+    pub(crate) fn new(root: &Path, options: &'a WalkOptions, priority: Priority) -> Self {
+        Walker {
+            root: root.to_owned(),
+            options,
+            priority,
+            started: false,
+            root_volume: None,
+            visited: HashSet::new(),
+            pending: Vec::new(),
+            current: Vec::new().into_iter(),
+            descend: Vec::new(),
+        }
+    }
+
+    /// Returns the next entry, or the path that could not be walked.
+    pub(crate) async fn next(&mut self) -> Option<Result<(PathBuf, EntryKind), (PathBuf, Error)>> {
+        if !self.started {
+            self.started = true;
+            if let Err(e) = self.start().await {
+                return Some(Err((self.root.clone(), e)));
+            }
+        }
+        loop {
+            if let Some(entry) = self.current.next() {
+                match self.classify(entry).await {
+                    Some(next) => return Some(next),
+                    None => continue,
+                }
+            }
+            // Subdirectories are visited in name order once their parent's
+            // entries have all been reported.
+            self.pending.extend(self.descend.drain(..).rev());
+            let dir = self.pending.pop()?;
+            match sys::read_dir(&dir, self.priority).await {
+                Ok(mut entries) => {
+                    entries.sort_by(|a, b| a.path.cmp(&b.path));
+                    self.current = entries.into_iter();
+                }
+                Err(e) => return Some(Err((dir, Error(e)))),
+            }
+        }
+    }
+
+    async fn start(&mut self) -> Result<(), Error> {
+        if self.options.symlinks == SymlinkPolicy::Follow || self.options.same_volume {
+            let resolved = sys::resolve(&self.root, self.priority)
+                .await
+                .map_err(Error)?;
+            self.root_volume = Some(resolved.volume);
+            self.visited.insert(resolved.canonical);
+        }
+        self.pending.push(self.root.clone());
+        Ok(())
+    }
+
+    async fn classify(
+        &mut self,
+        entry: sys::DirEntry,
+    ) -> Option<Result<(PathBuf, EntryKind), (PathBuf, Error)>> {
+        let path = entry.path;
+        if entry.is_symlink {
+            match self.options.symlinks {
+                SymlinkPolicy::Skip => return None,
+                SymlinkPolicy::NoFollow => return Some(Ok((path, EntryKind::Symlink))),
+                SymlinkPolicy::Follow => {}
+            }
+            let Ok(resolved) = sys::resolve(&path, self.priority).await else {
+                return Some(Ok((path, EntryKind::Symlink)));
+            };
+            if !resolved.is_dir {
+                return Some(Ok((path, EntryKind::File)));
+            }
+            return Some(self.enter(path, resolved));
+        }
+        if !entry.is_dir {
+            return Some(Ok((path, EntryKind::File)));
+        }
+        if self.root_volume.is_none() {
+            self.descend.push(path.clone());
+            return Some(Ok((path, EntryKind::Dir)));
+        }
+        match sys::resolve(&path, self.priority).await {
+            Ok(resolved) => Some(self.enter(path, resolved)),
+            Err(e) => Some(Err((path, Error(e)))),
+        }
+    }
+
+    /// Reports a directory, scheduling it to be walked if it is new and on an
+    /// allowed volume.
+    fn enter(
+        &mut self,
+        path: PathBuf,
+        resolved: sys::Resolved,
+    ) -> Result<(PathBuf, EntryKind), (PathBuf, Error)> {
+        if self.options.same_volume && self.root_volume.as_ref() != Some(&resolved.volume) {
+            return Ok((path, EntryKind::Dir));
+        }
+        if self.options.symlinks == SymlinkPolicy::Follow
+            && !self.visited.insert(resolved.canonical)
+        {
+            return Err((path, Error(sys::Error::FilesystemLoop)));
+        }
+        self.descend.push(path.clone());
+        Ok((path, EntryKind::Dir))
+    }
+}
@@ -8,7 +8,8 @@
 //! # Architecture
 //!
 //! The WASM implementation treats files as HTTP resources:
-//! - File paths are converted to URLs relative to the origin
+//! - File paths are converted to URLs relative to the origin, unless they are already
+//!   absolute `http(s)://` URLs
 //! - File reading uses HTTP GET requests with Range headers
 //! - File metadata uses HTTP HEAD requests
 //! - Seeking is simulated by adjusting the Range header for subsequent reads
@@ -24,8 +25,9 @@
 //!
 //! - Files must be served over HTTP/HTTPS from the same origin or with proper CORS headers
 //! - Write operations are not supported (read-only access)
-//! - `SeekFrom::End` is not supported as it would require knowing the file size first
-//! - File paths are interpreted as URLs relative to the origin
+//! - `SeekFrom::End` relies on the length from a HEAD request, made at open or on the first
+//!   such seek and then cached; a file that changes on the server afterwards seeks stale
+//! - Relative file paths are interpreted as URLs relative to the origin
 //!
 //! # Origin Configuration
 //!
@@ -33,19 +35,37 @@
 //! for main thread, self.origin for workers). In environments where this cannot be determined
 //! (like Node.js), use [`set_default_origin`] to configure a fallback.
 //!
+//! [`FetchOptions::origin`] overrides the origin, globally or for one file, so files can be
+//! read from several servers at once.
+//!
+//! # Node.js
+//!
+//! With the `node` feature, a module running under Node reads paths from the local disk with
+//! `fs.promises` instead, as native platforms do; see the `node` submodule.
+//!
 //!
 
 //SPDX-License-Identifier: MIT OR Apache-2.0
-use crate::Priority;
+use crate::{FetchOptions, Priority};
 use js_sys::Reflect;
 use js_sys::wasm_bindgen::JsValue;
 use some_executor::task::{Configuration, Task};
 use std::ops::Deref;
 use std::path::Path;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 use wasm_bindgen_futures::JsFuture;
 use web_sys::wasm_bindgen::JsCast;
-use web_sys::{ReadableStreamDefaultReader, Request, RequestInit, Response, WorkerGlobalScope};
+use web_sys::{
+    AbortController, AbortSignal, ReadableStreamDefaultReader, Request, RequestCache,
+    RequestCredentials, RequestInit, RequestMode, Response, WorkerGlobalScope,
+};
+
+pub use web_time::Instant;
+
+mod cache_storage;
+mod local;
+mod node;
 
 /// Global fallback origin URL for environments where it cannot be automatically determined.
 ///
@@ -72,6 +92,21 @@ pub fn set_default_origin(or: &'static str) {
     *FALLBACK_WASM_ORIGIN.lock().unwrap() = Some(or);
 }
 
+/// Options applied to every request, set with [`set_fetch_options`].
+static FETCH_OPTIONS: Mutex<Option<FetchOptions>> = Mutex::new(None);
+
+/// Sets the options applied to every request.
+///
+/// Files already open keep the options they were opened with.
+pub fn set_fetch_options(options: FetchOptions) {
+    *FETCH_OPTIONS.lock().unwrap() = Some(options);
+}
+
+/// Returns the options set with [`set_fetch_options`].
+fn fetch_options() -> FetchOptions {
+    FETCH_OPTIONS.lock().unwrap().clone().unwrap_or_default()
+}
+
 /// A WASM file handle for asynchronous I/O operations over HTTP.
 ///
 /// `File` represents a remote file accessed via HTTP requests. It maintains
@@ -85,10 +120,103 @@ pub fn set_default_origin(or: &'static str) {
 ///
 #[derive(Debug)]
 pub struct File {
-    /// The path/URL of the file relative to the origin
+    /// The path of the file relative to the origin, or its absolute URL
     path: String,
     /// Current seek position in bytes from the start of the file
-    seek_pos: u64,
+    seek_pos: AtomicU64,
+    /// The length reported by a HEAD request, at open or on the first
+    /// `SeekFrom::End`
+    len: OnceLock<u64>,
+    /// The version of the file first seen, at open or on the first read;
+    /// later reads fail if the file changes from it
+    version: OnceLock<Version>,
+    /// The global options merged with those given at open, used for every
+    /// request for this file, shared with the tasks that make them
+    fetch: Arc<FetchOptions>,
+    /// Where reads come from
+    source: Source,
+    /// Called as response bodies stream in
+    progress: Progress,
+}
+
+/// The hook set with [`File::set_progress`].
+#[derive(Default)]
+struct Progress(Mutex<Option<crate::ProgressHook>>);
+
+impl std::fmt::Debug for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Progress")
+            .field(&self.0.lock().unwrap().is_some())
+            .finish()
+    }
+}
+
+/// What identifies one version of a file served over HTTP: its ETag or,
+/// where the server sends none, its Last-Modified date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Version {
+    ETag(String),
+    LastModified(String),
+}
+
+impl Version {
+    /// Returns the version `response` is of, if it says.
+    ///
+    /// Compressed responses may carry an ETag for the compressed copy (Apache
+    /// appends `-gzip`, for one), which no uncompressed response would match,
+    /// so they give none.
+    fn of(response: &Response) -> Option<Version> {
+        if is_encoded(response) {
+            return None;
+        }
+        let headers = response.headers();
+        if let Some(etag) = headers.get("etag").ok().flatten() {
+            return Some(Version::ETag(etag));
+        }
+        let modified = headers.get("last-modified").ok().flatten()?;
+        Some(Version::LastModified(modified))
+    }
+
+    /// Returns the value to send as `If-Range`, so that a server whose file
+    /// no longer matches sends all of it (with its new version) instead of
+    /// the range. Weak ETags are not allowed there.
+    fn if_range(&self) -> Option<&str> {
+        match self {
+            Version::ETag(etag) if etag.starts_with("W/") => None,
+            Version::ETag(etag) | Version::LastModified(etag) => Some(etag),
+        }
+    }
+
+    /// Whether `response` could be of this version: it is unless it names
+    /// another one. ETags are compared weakly.
+    fn matches(&self, response: &Response) -> bool {
+        let headers = response.headers();
+        match self {
+            // A compressed response's ETag can differ for the same file.
+            Version::ETag(_) if is_encoded(response) => true,
+            Version::ETag(etag) => {
+                headers.get("etag").ok().flatten().is_none_or(|seen| {
+                    seen.trim_start_matches("W/") == etag.trim_start_matches("W/")
+                })
+            }
+            Version::LastModified(modified) => headers
+                .get("last-modified")
+                .ok()
+                .flatten()
+                .is_none_or(|seen| seen == *modified),
+        }
+    }
+}
+
+/// Where a [`File`]'s bytes come from.
+#[derive(Debug)]
+enum Source {
+    /// Requests to the file's URL
+    Http,
+    /// A local file the user picked
+    Local(local::Local),
+    /// A file opened with Node's `fs.promises`
+    Node(node::Handle),
 }
 
 /// Errors that can occur during WASM file operations.
@@ -111,6 +239,122 @@ pub enum Error {
     /// File was not found (404 or failed HEAD request)
     #[error("Not found")]
     NotFound,
+    /// The enclosing `PriorityScope` ran out of its byte or time budget
+    #[error("priority scope budget exceeded")]
+    BudgetExceeded,
+    /// Bytes read for a text operation were not valid UTF-8
+    #[error("invalid UTF-8: {0}")]
+    InvalidUtf8(#[from] std::str::Utf8Error),
+    /// The operation is not available when files are served over HTTP
+    #[error("operation not supported on WASM")]
+    Unsupported,
+    /// A directory walk following symlinks reached a directory it had already visited
+    #[error("filesystem loop")]
+    FilesystemLoop,
+    /// A `CacheDir` key was empty or too long
+    #[error("invalid cache key")]
+    InvalidCacheKey,
+    /// A file was too large to read into memory on this target
+    #[error("file of {0} bytes is too large to read into memory")]
+    TooLarge(u64),
+    /// Requests did not finish within the timeout set in `FetchOptions`
+    #[error("timed out")]
+    TimedOut,
+    /// The file on the server changed between two reads of it
+    #[error("file changed on the server while it was being read")]
+    Changed,
+    /// The path is not valid UTF-8, or does not make a valid URL
+    #[error("invalid path {}", .0.display())]
+    InvalidPath(std::path::PathBuf),
+    /// A header set in `FetchOptions` has an invalid name or value
+    #[error("invalid header {0:?}")]
+    InvalidHeader(String),
+    /// A JSON document, or a line of newline-delimited JSON, could not be
+    /// deserialized
+    #[cfg(feature = "serde")]
+    #[error("invalid JSON on line {line}: {source}")]
+    Json {
+        line: u64,
+        #[source]
+        source: serde_json::Error,
+    },
+    /// A TOML document could not be deserialized
+    #[cfg(feature = "toml")]
+    #[error("invalid TOML on line {line}: {}", source.message())]
+    Toml {
+        line: u64,
+        #[source]
+        source: Box<toml::de::Error>,
+    },
+}
+
+impl Error {
+    /// Whether the server reported no such file.
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Error::NotFound | Error::HttpStatus(404))
+    }
+
+    /// Whether the operation was aborted by its fetch timeout.
+    pub fn is_timed_out(&self) -> bool {
+        matches!(self, Error::TimedOut)
+    }
+
+    /// Classifies the error by what the server answered, where it answered.
+    pub fn kind(&self) -> crate::ErrorKind {
+        match self {
+            Error::NotFound | Error::HttpStatus(404 | 410) => crate::ErrorKind::NotFound,
+            Error::HttpStatus(401 | 403) => crate::ErrorKind::PermissionDenied,
+            Error::HttpStatus(status) => crate::ErrorKind::HttpStatus(*status),
+            Error::TimedOut => crate::ErrorKind::TimedOut,
+            _ => crate::ErrorKind::Other,
+        }
+    }
+
+    /// Always fails: requests fail with their own errors, not I/O errors.
+    pub fn into_io(self) -> Result<std::io::Error, Self> {
+        Err(self)
+    }
+
+    /// The I/O error kind closest to this error.
+    pub fn closest_io_kind(&self) -> std::io::ErrorKind {
+        use std::io::ErrorKind as Io;
+        match self {
+            Error::NotFound | Error::HttpStatus(404 | 410) => Io::NotFound,
+            Error::HttpStatus(401 | 403) => Io::PermissionDenied,
+            Error::TimedOut => Io::TimedOut,
+            Error::InvalidUtf8(_) | Error::Changed => Io::InvalidData,
+            #[cfg(feature = "serde")]
+            Error::Json { .. } => Io::InvalidData,
+            #[cfg(feature = "toml")]
+            Error::Toml { .. } => Io::InvalidData,
+            Error::NoBody => Io::UnexpectedEof,
+            Error::Unsupported => Io::Unsupported,
+            Error::InvalidCacheKey | Error::InvalidPath(_) | Error::InvalidHeader(_) => {
+                Io::InvalidInput
+            }
+            Error::TooLarge(_) => Io::OutOfMemory,
+            Error::Wasm(_)
+            | Error::HttpStatus(_)
+            | Error::BudgetExceeded
+            | Error::FilesystemLoop => Io::Other,
+        }
+    }
+
+    /// Always `None`: requests fail with their own errors, and are retried
+    /// as their [`FetchOptions`] say.
+    pub fn io_kind(&self) -> Option<std::io::ErrorKind> {
+        None
+    }
+
+    /// The error for an operation cut short by a timeout.
+    pub fn timed_out() -> Self {
+        Error::TimedOut
+    }
+
+    /// Whether the file on the server changed between reads.
+    pub fn is_changed(&self) -> bool {
+        matches!(self, Error::Changed)
+    }
 }
 
 impl From<JsValue> for Error {
@@ -127,6 +371,23 @@ impl From<JsValue> for Error {
 #[derive(Debug)]
 pub struct Data(Box<[u8]>);
 
+/// Permissions of a file served over HTTP, which is always read-only.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Permissions {
+    readonly: bool,
+}
+
+impl Permissions {
+    pub fn readonly(&self) -> bool {
+        self.readonly
+    }
+
+    /// Records the flag; [`set_permissions`] cannot apply it.
+    pub fn set_readonly(&mut self, readonly: bool) {
+        self.readonly = readonly;
+    }
+}
+
 /// Metadata about a WASM file obtained from HTTP headers.
 ///
 /// `Metadata` contains information about a file retrieved via HTTP HEAD request,
@@ -136,16 +397,56 @@ pub struct Data(Box<[u8]>);
 pub struct Metadata {
     /// The size of the file in bytes (from Content-Length header)
     len: u64,
+    /// Milliseconds since the Unix epoch (from Last-Modified header), if sent
+    modified_ms: Option<f64>,
+    /// Always a file, except for paths stat'ed under Node
+    file_type: crate::FileType,
 }
 
 impl Metadata {
     /// Returns the size of the file in bytes.
     ///
-    /// This value is obtained from the Content-Length HTTP header.
+    /// This value is obtained from the Content-Length HTTP header. If the
+    /// response was compressed, or had no Content-Length, it is the length
+    /// reads see, found with a ranged request instead.
     ///
     pub fn len(&self) -> u64 {
         self.len
     }
+
+    /// Returns the modification time from the Last-Modified HTTP header.
+    ///
+    /// Fails with [`Error::Unsupported`] if the server did not send one.
+    pub fn modified(&self) -> Result<std::time::SystemTime, Error> {
+        let ms = self.modified_ms.ok_or(Error::Unsupported)?;
+        let offset = std::time::Duration::from_secs_f64(ms.abs() / 1000.0);
+        Ok(if ms >= 0.0 {
+            std::time::UNIX_EPOCH + offset
+        } else {
+            std::time::UNIX_EPOCH - offset
+        })
+    }
+
+    /// HTTP does not report access times; always fails with [`Error::Unsupported`].
+    pub fn accessed(&self) -> Result<std::time::SystemTime, Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// HTTP does not report creation times; always fails with [`Error::Unsupported`].
+    pub fn created(&self) -> Result<std::time::SystemTime, Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Returns read-only permissions: HTTP offers no way to write.
+    pub fn permissions(&self) -> Permissions {
+        Permissions { readonly: true }
+    }
+
+    /// Returns [`FileType::File`](crate::FileType::File): HTTP serves only
+    /// files. Under Node, returns the type `fs.stat` reports.
+    pub fn file_type(&self) -> crate::FileType {
+        self.file_type
+    }
 }
 
 impl AsRef<[u8]> for Data {
@@ -170,6 +471,19 @@ impl Data {
     pub fn into_boxed_slice(self) -> Box<[u8]> {
         self.0
     }
+
+    /// Wraps an existing boxed byte slice.
+    ///
+    /// Used by crate-level helpers that assemble a buffer from bytes that were
+    /// already fetched through this backend.
+    pub fn from_boxed_slice(slice: Box<[u8]>) -> Self {
+        Data(slice)
+    }
+
+    /// Converts this `Data` into a vector without copying.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0.into_vec()
+    }
 }
 
 impl File {
@@ -177,11 +491,13 @@ impl File {
     ///
     /// This method performs an HTTP HEAD request to verify the file exists before
     /// returning a `File` handle. The path is interpreted as a URL relative to
-    /// the current origin.
+    /// the current origin, unless it is an absolute `http://` or `https://`
+    /// URL.
     ///
     /// # Arguments
     ///
-    /// * `path` - The path to the file, relative to the origin
+    /// * `path` - The path to the file, relative to the origin, or an absolute
+    ///   `http://` or `https://` URL
     /// * `priority` - The priority for this operation
     ///
     /// # Returns
@@ -191,25 +507,131 @@ impl File {
     /// - Network error occurs
     /// - CORS restrictions prevent access
     pub async fn open(path: impl AsRef<Path>, priority: Priority) -> Result<Self, Error> {
+        Self::open_with(path, &crate::OpenOptions::new(), priority).await
+    }
+
+    /// Opens a file with options. Direct I/O and advice have no meaning for
+    /// files fetched over HTTP; the fetch options are merged over the global
+    /// ones and used for every request for this file, starting with the HEAD
+    /// request made here. Under Node with the `node` feature, the file is
+    /// opened on the local disk instead.
+    pub async fn open_with(
+        path: impl AsRef<Path>,
+        options: &crate::OpenOptions,
+        _priority: Priority,
+    ) -> Result<Self, Error> {
         let path = path.as_ref().to_owned();
+        let fetch = Arc::new(fetch_options().merged_with(options.get_fetch()));
+        if node::available() {
+            let path = path_str(&path)?.to_owned();
+            let handle = Task::without_notifications(
+                "File::open".to_string(),
+                Configuration::default(),
+                node::open(path.clone()),
+            )
+            .pin_current()
+            .await?;
+            return Ok(Self {
+                path,
+                seek_pos: AtomicU64::new(0),
+                len: OnceLock::new(),
+                version: OnceLock::new(),
+                fetch,
+                source: Source::Node(handle),
+                progress: Progress::default(),
+            });
+        }
         let move_path = path.clone();
-        let exists = Task::without_notifications(
+        let move_fetch = fetch.clone();
+        let head = Task::without_notifications(
             "File::open".to_string(),
             Configuration::default(),
-            async move { exists(move_path, priority).await },
+            async move { head(move_path, &move_fetch).await },
         )
         .pin_current()
         .await;
-        if !exists {
-            Err(Error::NotFound)
-        } else {
-            Ok(Self {
-                path: path.to_str().unwrap().to_owned(),
-                seek_pos: 0,
-            })
+        match head {
+            Err(e @ (Error::TimedOut | Error::InvalidPath(_) | Error::InvalidHeader(_))) => Err(e),
+            Err(_) => Err(Error::NotFound),
+            Ok((len, version)) => Ok(Self {
+                path: path_str(&path)?.to_owned(),
+                seek_pos: AtomicU64::new(0),
+                len: len.map(OnceLock::from).unwrap_or_default(),
+                version: version.map(OnceLock::from).unwrap_or_default(),
+                fetch,
+                source: Source::Http,
+                progress: Progress::default(),
+            }),
+        }
+    }
+
+    /// Wraps a `File` object, such as one from an `<input type="file">`.
+    pub fn from_web_file(file: &web_sys::File) -> Self {
+        Self::from_local(local::Local::new(file))
+    }
+
+    /// Wraps a `Blob`, such as a fetched response body or generated data.
+    pub fn from_blob(blob: &web_sys::Blob) -> Self {
+        Self::from_local(local::Local::from_blob(blob))
+    }
+
+    /// Opens the file behind a handle from the File System Access API.
+    ///
+    /// The handle is only used before the returned future is first polled, so
+    /// the future is `Send`, but it must be polled on the thread that called
+    /// this.
+    pub fn open_file_handle(
+        handle: &web_sys::FileSystemFileHandle,
+    ) -> impl Future<Output = Result<Self, Error>> + Send + use<> {
+        let pending = local::Entry::new(handle.get_file().into());
+        async move {
+            let resolve = local::resolve(&pending);
+            let local = Task::without_notifications(
+                "File::open_file_handle".to_string(),
+                Configuration::default(),
+                async move { Ok::<_, Error>(local::Local::new(&resolve.await?)) },
+            )
+            .pin_current()
+            .await?;
+            Ok(Self::from_local(local))
+        }
+    }
+
+    fn from_local(local: local::Local) -> Self {
+        Self {
+            path: local.key(),
+            seek_pos: AtomicU64::new(0),
+            len: OnceLock::from(local.len),
+            version: OnceLock::new(),
+            fetch: Arc::default(),
+            source: Source::Local(local),
+            progress: Progress::default(),
         }
     }
 
+    /// Returns the path the file was opened with; for a local file, a key
+    /// unique to this `File`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Always returns `None`: there is no file to map, so the caller reads.
+    pub async fn map(&self, _offset: u64, _priority: Priority) -> Result<Option<Data>, Error> {
+        Ok(None)
+    }
+
+    /// Reads as [`read`](File::read) does. Response bodies arrive in buffers
+    /// the browser allocates, so there is nothing to reuse and `recycled` is
+    /// dropped.
+    pub async fn read_into(
+        &self,
+        _recycled: Option<Data>,
+        buf_size: usize,
+        priority: Priority,
+    ) -> Result<Data, Error> {
+        self.read(buf_size, priority).await
+    }
+
     /// Reads up to `buf_size` bytes from the file at the current position.
     ///
     /// This method performs an HTTP GET request with a Range header to fetch
@@ -224,34 +646,74 @@ impl File {
     ///
     /// Returns a `Data` object containing the bytes read. The actual number
     /// of bytes may be less than `buf_size` if:
-    /// - End of file is reached
-    /// - The server doesn't support range requests
+    /// - End of file is reached (a 416 response reads as empty, and a 206
+    ///   covering less than was requested as a short read)
     /// - Network interruption occurs
     ///
+    /// # Errors
+    ///
+    /// Returns [`Error::HttpStatus`] for other error responses, or an error if
+    /// the request fails.
+    ///
     /// # Implementation Details
     ///
     /// - Uses HTTP Range headers (e.g., `Range: bytes=0-1023`)
     /// - Reads from a `ReadableStream` using the Streams API
     /// - Accumulates chunks until `buf_size` is reached or stream ends
+    /// - If the server ignores the Range header and sends the whole file,
+    ///   discards the bytes before the position and stops after `buf_size`
+    /// - Sends the ETag (or Last-Modified date) seen at open or on the first
+    ///   read as `If-Range`, and fails with [`Error::Changed`] if the file has
+    ///   since changed, rather than mix bytes from two versions. Servers on
+    ///   another origin must allow the header in `Access-Control-Allow-Headers`.
+    /// - Advances the seek position by the number of bytes read
     pub async fn read(&self, buf_size: usize, _priority: Priority) -> Result<Data, Error> {
-        let seek_pos = self.seek_pos;
-        let full_path = full_path(&self.path);
+        // A file the HEAD at open reported as empty has nothing to GET.
+        if self.len.get() == Some(&0) || buf_size == 0 {
+            return Ok(Data(Box::new([])));
+        }
+        let seek_pos = self.seek_pos.load(Ordering::Relaxed);
+        match &self.source {
+            Source::Http => {}
+            Source::Local(local) => {
+                return self
+                    .read_from(seek_pos, local.read(seek_pos, buf_size))
+                    .await;
+            }
+            Source::Node(handle) => {
+                return self
+                    .read_from(seek_pos, handle.read(seek_pos, buf_size))
+                    .await;
+            }
+        }
+        let full_path = full_path(&self.path, &self.fetch)?;
+        let expected = self.version.get().cloned();
+        let fetch = match expected.as_ref().and_then(Version::if_range) {
+            Some(version) => Arc::new((*self.fetch).clone().header("If-Range", version)),
+            None => self.fetch.clone(),
+        };
+        let progress = self.progress.0.lock().unwrap().clone();
+        let (abort, cancel) = abortable(&self.fetch);
         let r = Task::without_notifications(
             "File::read".to_string(),
             Configuration::default(),
             async move {
-                let request_init = RequestInit::new();
-                request_init.set_method("GET");
-                //need to set Range: bytes=0- to read the whole file
-                let map = js_sys::Map::new();
-                let max_byte = seek_pos + buf_size as u64;
-                map.set(
-                    &"Range".into(),
-                    &JsValue::from_str(&format!("bytes={}-{}", seek_pos, max_byte)),
-                );
-                request_init.set_headers(&map.into());
-                let request = Request::new_with_str_and_init(&full_path, &request_init).unwrap();
-                let response = fetch_with_request(request).await?;
+                // Range is inclusive of its last byte.
+                let last_byte = seek_pos + buf_size as u64 - 1;
+                let range = format!("bytes={}-{}", seek_pos, last_byte);
+                let response = send(
+                    &full_path,
+                    "GET",
+                    &fetch,
+                    Some(&range),
+                    &abort_signal(cancel),
+                )
+                .await?;
+                // 416 means the range starts at or past the end: end of file,
+                // as a read there is on a native file.
+                if response.status() == 416 {
+                    return Ok((Vec::new(), None));
+                }
                 if !response.ok() {
                     logwise::error_sync!(
                         "Got response {status} for url {url}",
@@ -260,41 +722,105 @@ impl File {
                     );
                     return Err(Error::HttpStatus(response.status()));
                 }
-                let body = response.body().ok_or(Error::NoBody)?;
-                let reader = body.get_reader();
-                let default_reader: ReadableStreamDefaultReader = reader.dyn_into().unwrap();
-                let mut data = Vec::with_capacity(buf_size);
-
-                //get the 'value' property if defined
-                loop {
-                    let read_promise = default_reader.read();
-                    let read_result = JsFuture::from(read_promise).await?;
-                    if let Ok(value) = Reflect::get(&read_result, &JsValue::from_str("value")) {
-                        if value.is_undefined() {
-                            // No more data to read
-                            break;
-                        }
-                        //convert from Uint8Array to Vec<u8>
-                        let uint8_array: js_sys::Uint8Array = value.dyn_into().unwrap();
-                        let read_more = buf_size - data.len();
-
-                        let read_more_src = uint8_array.length().min(read_more.try_into().unwrap());
-                        data.extend(uint8_array.slice(0, read_more_src).to_vec());
-                    } else {
-                        // No 'value' property, we assume no more data
-                        break;
-                    }
+                // After If-Range, a changed file comes back whole, with its
+                // new version; bytes from it must not be stitched onto those
+                // read before.
+                if expected
+                    .as_ref()
+                    .is_some_and(|expected| !expected.matches(&response))
+                {
+                    return Err(Error::Changed);
                 }
-                Ok(data)
+                let version = Version::of(&response);
+                // A 206 covering less than was asked for (near the end of the
+                // file) has a shorter body, which reads as a short read.
+                // 206 means the server honored the range; 200 is the whole
+                // file, from which the bytes before the position are dropped.
+                let skip = if response.status() == 200 {
+                    seek_pos
+                } else {
+                    0
+                };
+                let data = read_body(response, skip, buf_size, progress.as_deref()).await?;
+                Ok((data, version))
             },
         )
         .pin_current()
-        .await
-        .unwrap();
+        .await;
+        let (r, version) = abort.finish(r)?;
+        if let Some(version) = version {
+            let _ = self.version.set(version);
+        }
 
+        self.seek_pos
+            .store(seek_pos + r.len() as u64, Ordering::Relaxed);
         Ok(Data(r.into_boxed_slice()))
     }
 
+    /// Returns a file for the same source whose reads start from `pos`. HTTP
+    /// files share what was learned at open; a Node file is opened again.
+    pub async fn try_clone(&self, pos: u64, _priority: Priority) -> Result<Self, Error> {
+        let source = match &self.source {
+            Source::Http => Source::Http,
+            Source::Local(local) => Source::Local(local.try_clone()?),
+            Source::Node(_) => Source::Node(
+                Task::without_notifications(
+                    "File::try_clone".to_string(),
+                    Configuration::default(),
+                    node::open(self.path.clone()),
+                )
+                .pin_current()
+                .await?,
+            ),
+        };
+        Ok(Self {
+            path: self.path.clone(),
+            seek_pos: AtomicU64::new(pos),
+            len: self.len.clone(),
+            version: self.version.clone(),
+            fetch: self.fetch.clone(),
+            source,
+            progress: Progress::default(),
+        })
+    }
+
+    /// Always fails with [`Error::Unsupported`]; files are read-only.
+    pub async fn write_all(&self, _data: Data, _priority: Priority) -> Result<(), Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Sets a hook called with the bytes received so far as each piece of a
+    /// response body arrives, or removes it.
+    pub fn set_progress(&self, progress: Option<crate::ProgressHook>) {
+        *self.progress.0.lock().unwrap() = progress;
+    }
+
+    /// Runs `read`, a read of a local file from `seek_pos`, and advances the
+    /// position past what it returned.
+    ///
+    /// `read` is moved into the task here, before anything is awaited, so the
+    /// returned future is `Send` though `read` is not.
+    fn read_from(
+        &self,
+        seek_pos: u64,
+        read: impl Future<Output = Result<Vec<u8>, Error>> + 'static,
+    ) -> impl Future<Output = Result<Data, Error>> + Send + '_ {
+        let task =
+            Task::without_notifications("File::read".to_string(), Configuration::default(), read)
+                .pin_current();
+        async move {
+            let r = task.await?;
+            self.seek_pos
+                .store(seek_pos + r.len() as u64, Ordering::Relaxed);
+            Ok(Data(r.into_boxed_slice()))
+        }
+    }
+
+    /// Moves the position reads start from to `pos`.
+    pub fn set_position(&self, pos: u64) {
+        self.seek_pos.store(pos, Ordering::Relaxed);
+    }
+
     /// Seeks to a position in the file.
     ///
     /// This method updates the internal seek position that will be used for
@@ -312,30 +838,103 @@ impl File {
     ///
     /// # Limitations
     ///
-    /// - `SeekFrom::End` is not supported and will panic
-    /// - `SeekFrom::Current` with negative offset may cause overflow
+    /// - `SeekFrom::End` uses the Content-Length from the HEAD request made at
+    ///   open. If that response had none, or was compressed, the first such
+    ///   seek looks up the length as [`metadata`](Self::metadata) does;
+    ///   either way the length is cached for the life of the `File`.
+    /// - `SeekFrom::End` and `SeekFrom::Current` fail if the result would be
+    ///   negative or overflow
     ///
-    pub async fn seek(
-        &mut self,
-        pos: std::io::SeekFrom,
-        _priority: Priority,
-    ) -> Result<u64, Error> {
-        match pos {
-            std::io::SeekFrom::Start(offset) => {
-                self.seek_pos = offset;
-                Ok(self.seek_pos)
-            }
-            std::io::SeekFrom::End(_offset) => {
-                panic!("SeekFrom::End is not supported in WASM");
-            }
-            std::io::SeekFrom::Current(offset) => {
-                self.seek_pos = self
-                    .seek_pos
-                    .checked_add(offset as u64)
-                    .ok_or_else(|| Error::Wasm("SeekFrom::Current overflow".to_string()))?;
-                Ok(self.seek_pos)
+    pub async fn seek(&self, pos: std::io::SeekFrom, _priority: Priority) -> Result<u64, Error> {
+        let new_pos = match pos {
+            std::io::SeekFrom::Start(offset) => offset,
+            std::io::SeekFrom::End(offset) => {
+                let len = match self.len.get() {
+                    Some(&len) => len,
+                    // A file on the local disk may have grown since it was
+                    // last seen, as native files do.
+                    None if matches!(self.source, Source::Node(_)) => {
+                        self.metadata(Priority::unit_test()).await?.len
+                    }
+                    None => {
+                        let len = path_metadata(&self.path, &self.fetch).await?.len;
+                        *self.len.get_or_init(|| len)
+                    }
+                };
+                len.checked_add_signed(offset)
+                    .ok_or_else(|| Error::Wasm("SeekFrom::End overflow".to_string()))?
             }
-        }
+            std::io::SeekFrom::Current(offset) => self
+                .seek_pos
+                .load(Ordering::Relaxed)
+                .checked_add_signed(offset)
+                .ok_or_else(|| Error::Wasm("SeekFrom::Current overflow".to_string()))?,
+        };
+        self.seek_pos.store(new_pos, Ordering::Relaxed);
+        Ok(new_pos)
+    }
+
+    /// Always fails with [`Error::Unsupported`]: files served over HTTP
+    /// cannot be changed.
+    pub async fn set_times(
+        &self,
+        _path: &Path,
+        _times: crate::FileTimes,
+        _priority: Priority,
+    ) -> Result<(), Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Does nothing: there is no page cache to advise.
+    pub async fn advise(
+        &self,
+        _advice: crate::Advice,
+        _offset: u64,
+        _len: Option<u64>,
+        _priority: Priority,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Always fails with [`Error::Unsupported`]: HTTP has no extended
+    /// attributes.
+    pub async fn list_xattrs(
+        &self,
+        _path: &Path,
+        _priority: Priority,
+    ) -> Result<Vec<std::ffi::OsString>, Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Always fails with [`Error::Unsupported`].
+    pub async fn get_xattr(
+        &self,
+        _path: &Path,
+        _name: &std::ffi::OsStr,
+        _priority: Priority,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Always fails with [`Error::Unsupported`].
+    pub async fn set_xattr(
+        &self,
+        _path: &Path,
+        _name: &std::ffi::OsStr,
+        _value: &[u8],
+        _priority: Priority,
+    ) -> Result<(), Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Always fails with [`Error::Unsupported`].
+    pub async fn remove_xattr(
+        &self,
+        _path: &Path,
+        _name: &std::ffi::OsStr,
+        _priority: Priority,
+    ) -> Result<(), Error> {
+        Err(Error::Unsupported)
     }
 
     /// Returns metadata about the file.
@@ -356,37 +955,66 @@ impl File {
     /// Returns an error if:
     /// - The HTTP request fails
     /// - The server returns an error status
-    /// - Content-Length header is missing or invalid
     ///
     pub async fn metadata(&self, _priority: Priority) -> Result<Metadata, Error> {
-        let full_path = full_path(&self.path);
-        let full_path_move = full_path.clone();
-        Task::without_notifications(
-            "File::metadata".to_string(),
-            Configuration::default(),
-            async move {
-                let request_init = RequestInit::new();
-                request_init.set_method("HEAD");
-                let request =
-                    Request::new_with_str_and_init(&full_path_move, &request_init).unwrap();
-
-                let response = fetch_with_request(request).await.unwrap();
-                if !response.ok() {
-                    // logwise::debuginternal_sync!("Got response {status} for url {url}", status=response.status_text(), url=logwise::privacy::LogIt(full_path));
-                    return Err(Error::HttpStatus(response.status()));
-                }
-                let headers = response.headers().get("content-length").unwrap();
-                let content_length = headers.map(|s| s.parse::<u64>().unwrap()).unwrap();
-                Ok(Metadata {
-                    len: content_length,
-                })
-            },
-        )
-        .pin_current()
-        .await
+        match &self.source {
+            Source::Http => path_metadata(&self.path, &self.fetch).await,
+            Source::Local(local) => Ok(Metadata {
+                len: local.len,
+                modified_ms: local.modified_ms,
+                file_type: crate::FileType::File,
+            }),
+            Source::Node(handle) => {
+                Task::without_notifications(
+                    "File::metadata".to_string(),
+                    Configuration::default(),
+                    handle.stat(),
+                )
+                .pin_current()
+                .await
+            }
+        }
     }
 }
 
+/// Retrieves metadata for `path` with an HTTP HEAD request.
+async fn path_metadata(path: impl AsRef<Path>, fetch: &FetchOptions) -> Result<Metadata, Error> {
+    let full_path = full_path(path, fetch)?;
+    let fetch = fetch.clone();
+    let (abort, cancel) = abortable(&fetch);
+    let metadata = Task::without_notifications(
+        "File::metadata".to_string(),
+        Configuration::default(),
+        async move {
+            let signal = abort_signal(cancel);
+            let response = send(&full_path, "HEAD", &fetch, None, &signal).await?;
+            if !response.ok() {
+                // logwise::debuginternal_sync!("Got response {status} for url {url}", status=response.status_text(), url=logwise::privacy::LogIt(full_path));
+                return Err(Error::HttpStatus(response.status()));
+            }
+            let content_length = match identity_len(&response) {
+                Some(len) => len,
+                None => decoded_len(&full_path, &fetch, &signal).await?,
+            };
+            let modified_ms = response
+                .headers()
+                .get("last-modified")
+                .ok()
+                .flatten()
+                .map(|date| js_sys::Date::parse(&date))
+                .filter(|ms| ms.is_finite());
+            Ok(Metadata {
+                len: content_length,
+                modified_ms,
+                file_type: crate::FileType::File,
+            })
+        },
+    )
+    .pin_current()
+    .await;
+    abort.finish(metadata)
+}
+
 //boilerplate impls
 
 impl PartialEq for Data {
@@ -430,6 +1058,333 @@ fn origin() -> String {
     }
 }
 
+/// Reads up to `want` bytes of a response body, after discarding the first
+/// `skip` bytes, calling `progress` with the bytes kept so far as each piece
+/// arrives.
+///
+/// Servers that ignore the Range header send the whole file with status 200;
+/// callers pass the requested start as `skip` for those. Reading stops as soon
+/// as `want` bytes have arrived; the caller's [`AbortOnDrop`] then cancels the
+/// rest of the download.
+async fn read_body(
+    response: Response,
+    mut skip: u64,
+    want: usize,
+    progress: Option<&(dyn Fn(u64) + Send + Sync)>,
+) -> Result<Vec<u8>, Error> {
+    let body = response.body().ok_or(Error::NoBody)?;
+    let reader: ReadableStreamDefaultReader = body.get_reader().unchecked_into();
+    let mut data = Vec::new();
+    while data.len() < want {
+        let read_result = JsFuture::from(reader.read()).await?;
+        let Ok(value) = Reflect::get(&read_result, &JsValue::from_str("value")) else {
+            break;
+        };
+        if value.is_undefined() {
+            break;
+        }
+        let chunk = value.dyn_into::<js_sys::Uint8Array>()?.to_vec();
+        let from = (skip.min(chunk.len() as u64)) as usize;
+        skip -= from as u64;
+        let take = (chunk.len() - from).min(want - data.len());
+        data.extend_from_slice(&chunk[from..from + take]);
+        if let Some(progress) = progress {
+            progress(data.len() as u64);
+        }
+    }
+    Ok(data)
+}
+
+/// Aborts a fetch when dropped.
+///
+/// Fetches run in a task pinned to the JavaScript thread, so that the futures
+/// wrapping them stay `Send`; an `AbortController` is not `Send` and cannot
+/// live in those futures. Instead the wrapping future holds this guard, and
+/// the task holds the [`Cancel`] from [`abortable`], passing it to
+/// [`abort_signal`]. Dropping the guard closes the channel, which aborts the
+/// request if it is still running (and is harmless if it has finished).
+struct AbortOnDrop {
+    _sender: async_channel::Sender<()>,
+    timed_out: Arc<AtomicBool>,
+}
+
+impl AbortOnDrop {
+    /// Reports a failed `result` as [`Error::TimedOut`] if the request was
+    /// aborted by its timeout, whatever error the abort surfaced as.
+    fn finish<T>(&self, result: Result<T, Error>) -> Result<T, Error> {
+        match result {
+            Err(_) if self.timed_out.load(Ordering::Relaxed) => Err(Error::TimedOut),
+            result => result,
+        }
+    }
+}
+
+/// The fetch task's half of an [`AbortOnDrop`].
+struct Cancel {
+    receiver: async_channel::Receiver<()>,
+    timeout: Option<std::time::Duration>,
+    timed_out: Arc<AtomicBool>,
+}
+
+/// Returns a guard for the calling future and a [`Cancel`] for the fetch
+/// task, which also aborts after the timeout in `fetch`, if any.
+fn abortable(fetch: &FetchOptions) -> (AbortOnDrop, Cancel) {
+    let (sender, receiver) = async_channel::bounded(1);
+    let timed_out = Arc::new(AtomicBool::new(false));
+    let guard = AbortOnDrop {
+        _sender: sender,
+        timed_out: timed_out.clone(),
+    };
+    let cancel = Cancel {
+        receiver,
+        timeout: fetch.get_timeout(),
+        timed_out,
+    };
+    (guard, cancel)
+}
+
+/// Returns a signal that aborts once the [`AbortOnDrop`] guard paired with
+/// `cancel` is dropped, or once its timeout elapses.
+fn abort_signal(cancel: Cancel) -> AbortSignal {
+    let controller = AbortController::new().unwrap();
+    let signal = controller.signal();
+    wasm_bindgen_futures::spawn_local(async move {
+        // Nothing is ever sent; this returns when the guard is dropped.
+        let dropped = std::pin::pin!(cancel.receiver.recv());
+        match cancel.timeout {
+            None => {
+                let _ = dropped.await;
+            }
+            Some(timeout) => {
+                let mut dropped = dropped;
+                let mut timer = std::pin::pin!(sleep(timeout));
+                let timed_out = std::future::poll_fn(|cx| {
+                    if dropped.as_mut().poll(cx).is_ready() {
+                        return std::task::Poll::Ready(false);
+                    }
+                    timer.as_mut().poll(cx).map(|()| true)
+                })
+                .await;
+                if timed_out {
+                    cancel.timed_out.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+        controller.abort();
+    });
+    signal
+}
+
+/// Sends a request for `full_path`, through the Cache Storage if `fetch`
+/// names a cache, and otherwise over the network. The request is aborted once
+/// `signal` aborts.
+async fn send(
+    full_path: &str,
+    method: &str,
+    fetch: &FetchOptions,
+    range: Option<&str>,
+    signal: &AbortSignal,
+) -> Result<Response, Error> {
+    match fetch.get_cache_storage() {
+        Some((name, policy)) => {
+            cache_storage::send(name, policy, full_path, method, fetch, range, signal).await
+        }
+        None => send_network(full_path, method, fetch, range, signal).await,
+    }
+}
+
+/// Returns the length of the file `response` describes, from its
+/// Content-Length, if the body is sent as it is.
+///
+/// A compressed response (`Content-Encoding: gzip`, `br`, ...) gives the
+/// length of the compressed body, while reads see the body decompressed, so
+/// for those this returns `None`, as it does when there is no Content-Length.
+fn identity_len(response: &Response) -> Option<u64> {
+    if is_encoded(response) {
+        return None;
+    }
+    let headers = response.headers();
+    headers.get("content-length").ok().flatten()?.parse().ok()
+}
+
+/// Whether `response` has a `Content-Encoding` other than `identity`.
+fn is_encoded(response: &Response) -> bool {
+    let encoding = response.headers().get("content-encoding").ok().flatten();
+    encoding.is_some_and(|encoding| !encoding.trim().eq_ignore_ascii_case("identity"))
+}
+
+/// Finds the length of `full_path` as reads see it, where the HEAD response
+/// gave none that can be trusted.
+///
+/// A ranged request for the first byte is sent uncompressed, and the total in
+/// its Content-Range is the length. A server that ignores the range sends the
+/// whole file, whose length is its Content-Length if it is sent uncompressed,
+/// and is otherwise found by counting the bytes of the body.
+async fn decoded_len(
+    full_path: &str,
+    fetch: &FetchOptions,
+    signal: &AbortSignal,
+) -> Result<u64, Error> {
+    let response = send(full_path, "GET", fetch, Some("bytes=0-0"), signal).await?;
+    let headers = response.headers();
+    let total = headers
+        .get("content-range")
+        .ok()
+        .flatten()
+        .and_then(|range| range.rsplit_once('/')?.1.trim().parse().ok());
+    match (response.status(), total) {
+        (206 | 416, Some(total)) => Ok(total),
+        // An unsatisfiable range with no total: the file is empty.
+        (416, None) => Ok(0),
+        (status, _) if !response.ok() => Err(Error::HttpStatus(status)),
+        (status, _) => match identity_len(&response) {
+            Some(len) if status == 200 => Ok(len),
+            _ => body_len(response).await,
+        },
+    }
+}
+
+/// Counts the bytes of a response body, as they arrive, without keeping them.
+async fn body_len(response: Response) -> Result<u64, Error> {
+    let body = response.body().ok_or(Error::NoBody)?;
+    let reader: ReadableStreamDefaultReader = body.get_reader().unchecked_into();
+    let mut len = 0;
+    loop {
+        let read_result = JsFuture::from(reader.read()).await?;
+        let Ok(value) = Reflect::get(&read_result, &JsValue::from_str("value")) else {
+            break;
+        };
+        if value.is_undefined() {
+            break;
+        }
+        len += value.dyn_into::<js_sys::Uint8Array>()?.length() as u64;
+    }
+    Ok(len)
+}
+
+/// Sends a request for `full_path`, built by [`new_request`], retrying as
+/// the [`RetryPolicy`](crate::RetryPolicy) in `fetch` allows. Once `signal`
+/// aborts, the request is not retried.
+async fn send_network(
+    full_path: &str,
+    method: &str,
+    fetch: &FetchOptions,
+    range: Option<&str>,
+    signal: &AbortSignal,
+) -> Result<Response, Error> {
+    let mut attempt = 0;
+    loop {
+        let request = new_request(full_path, method, fetch, range, signal)?;
+        let result = fetch_with_request(request).await;
+        let delay = fetch
+            .get_retry()
+            .filter(|_| !signal.aborted())
+            .and_then(|retry| {
+                let transient = match &result {
+                    Ok(response) => retry.get_statuses().contains(&response.status()),
+                    Err(_) => retry.get_network_errors(),
+                };
+                if transient {
+                    retry.delay_before(attempt + 1)
+                } else {
+                    None
+                }
+            });
+        let Some(delay) = delay else {
+            return result;
+        };
+        logwise::debuginternal_sync!(
+            "Retrying {url} in {delay}",
+            url = logwise::privacy::LogIt(full_path),
+            delay = logwise::privacy::LogIt(delay)
+        );
+        attempt += 1;
+        sleep(delay).await;
+    }
+}
+
+/// Waits for `duration` with the global `setTimeout`.
+async fn sleep(duration: std::time::Duration) {
+    let ms = duration.as_millis().min(i32::MAX as u128) as i32;
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let set_timeout = Reflect::get(&js_sys::global(), &"setTimeout".into())
+            .ok()
+            .and_then(|f| f.dyn_into::<js_sys::Function>().ok());
+        match set_timeout {
+            Some(set_timeout) => {
+                let _ = set_timeout.call2(&JsValue::UNDEFINED, &resolve, &ms.into());
+            }
+            None => {
+                let _ = resolve.call0(&JsValue::UNDEFINED);
+            }
+        }
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+/// Builds a request for `full_path` with the mode, credentials, cache mode
+/// and headers from `fetch`, then `range` as the Range header if given,
+/// aborted with `signal`.
+///
+/// Fails with [`Error::InvalidHeader`] for a header the browser rejects, and
+/// with [`Error::InvalidPath`] if `full_path` is not a valid URL.
+fn new_request(
+    full_path: &str,
+    method: &str,
+    fetch: &FetchOptions,
+    range: Option<&str>,
+    signal: &AbortSignal,
+) -> Result<Request, Error> {
+    let request_init = RequestInit::new();
+    request_init.set_method(method);
+    request_init.set_signal(Some(signal));
+    if let Some(mode) = fetch.get_mode() {
+        request_init.set_mode(match mode {
+            crate::FetchMode::Cors => RequestMode::Cors,
+            crate::FetchMode::NoCors => RequestMode::NoCors,
+            crate::FetchMode::SameOrigin => RequestMode::SameOrigin,
+        });
+    }
+    if let Some(credentials) = fetch.get_credentials() {
+        request_init.set_credentials(match credentials {
+            crate::FetchCredentials::Omit => RequestCredentials::Omit,
+            crate::FetchCredentials::SameOrigin => RequestCredentials::SameOrigin,
+            crate::FetchCredentials::Include => RequestCredentials::Include,
+        });
+    }
+    if let Some(cache) = fetch.get_cache() {
+        request_init.set_cache(match cache {
+            crate::FetchCache::Default => RequestCache::Default,
+            crate::FetchCache::NoStore => RequestCache::NoStore,
+            crate::FetchCache::Reload => RequestCache::Reload,
+            crate::FetchCache::NoCache => RequestCache::NoCache,
+            crate::FetchCache::ForceCache => RequestCache::ForceCache,
+            crate::FetchCache::OnlyIfCached => RequestCache::OnlyIfCached,
+        });
+    }
+    let headers = web_sys::Headers::new()?;
+    for (name, value) in fetch.get_headers() {
+        if range.is_some()
+            && (name.eq_ignore_ascii_case("range") || name.eq_ignore_ascii_case("accept-encoding"))
+        {
+            continue;
+        }
+        headers
+            .set(name, value)
+            .map_err(|_| Error::InvalidHeader(name.clone()))?;
+    }
+    if let Some(range) = range {
+        headers.set("Range", range)?;
+        // Byte ranges are offsets into the file as it is, not into a
+        // compressed copy. Browsers already ask for that on ranged requests
+        // and ignore this header; other runtimes, such as Node.js, need it.
+        headers.set("Accept-Encoding", "identity")?;
+    }
+    request_init.set_headers(&headers);
+    Request::new_with_str_and_init(full_path, &request_init)
+        .map_err(|_| Error::InvalidPath(full_path.into()))
+}
+
 /// Performs a fetch operation in the current WASM environment.
 ///
 /// This function abstracts over different JavaScript contexts (window, worker, global)
@@ -457,18 +1412,18 @@ async fn fetch_with_request(request: Request) -> Result<Response, Error> {
     let global = js_sys::global();
     if let Some(window) = web_sys::window() {
         let resp_value = JsFuture::from(window.fetch_with_request(&request)).await?;
-        let response: Response = resp_value.dyn_into().unwrap();
+        let response: Response = resp_value.dyn_into()?;
         Ok(response)
     } else if let Ok(scope) = js_sys::global().dyn_into::<WorkerGlobalScope>() {
         let resp_value = JsFuture::from(scope.fetch_with_request(&request)).await?;
-        let response: Response = resp_value.dyn_into().unwrap();
+        let response: Response = resp_value.dyn_into()?;
         Ok(response)
     } else if let Ok(s) = js_sys::Reflect::get(&global, &JsValue::from_str("fetch")) {
-        let into = s.dyn_into::<js_sys::Function>().unwrap();
-        let resp_value = into.call1(&JsValue::undefined(), &request).unwrap();
+        let into = s.dyn_into::<js_sys::Function>()?;
+        let resp_value = into.call1(&JsValue::undefined(), &request)?;
         let js_promise = resp_value.dyn_into::<js_sys::Promise>()?;
         let promise = JsFuture::from(js_promise).await?;
-        let response: Response = promise.dyn_into().unwrap();
+        let response: Response = promise.dyn_into()?;
         Ok(response)
     } else {
         panic!("Could not find fetch in global scope");
@@ -485,11 +1440,464 @@ async fn fetch_with_request(request: Request) -> Result<Response, Error> {
 ///
 /// A complete URL string combining the origin and path.
 ///
-fn full_path(path: impl AsRef<Path>) -> String {
-    let path_str = path.as_ref().to_str().unwrap();
-    let origin = origin();
-    let full_path = format!("{origin}/{path_str}");
-    full_path
+/// Returns the URL to fetch for `path`.
+///
+/// Absolute `http://` and `https://` URLs are used as they are. Other paths
+/// are file names, not URLs: each segment is percent-encoded by
+/// [`encode_path`], and the result is resolved against the origin from
+/// `fetch`, if it has one, and otherwise against [`origin`].
+fn full_path(path: impl AsRef<Path>, fetch: &FetchOptions) -> Result<String, Error> {
+    let path_str = path_str(path.as_ref())?;
+    if is_absolute_url(path_str) {
+        return Ok(path_str.to_owned());
+    }
+    let origin = match fetch.get_origin() {
+        Some(origin) => origin.to_owned(),
+        None => origin(),
+    };
+    let full_path = format!("{}/{}", origin.trim_end_matches('/'), encode_path(path_str));
+    Ok(full_path)
+}
+
+/// Returns `path` as a string, failing with [`Error::InvalidPath`] if it is
+/// not valid UTF-8, which URLs and Node's APIs need.
+fn path_str(path: &Path) -> Result<&str, Error> {
+    path.to_str()
+        .ok_or_else(|| Error::InvalidPath(path.to_owned()))
+}
+
+/// Percent-encodes the segments of `path`, keeping the `/` between them.
+///
+/// Bytes other than those RFC 3986 allows in a path segment are encoded, so
+/// names with spaces, `#`, `?`, `%` or non-ASCII characters reach the server
+/// as the name they are, rather than as a fragment, a query, or an escape.
+fn encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' => encoded.push(byte as char),
+            b'-' | b'.' | b'_' | b'~' | b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+'
+            | b',' | b';' | b'=' | b':' | b'@' | b'/' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// Whether `path` is an `http://` or `https://` URL, compared
+/// case-insensitively as URL schemes are.
+fn is_absolute_url(path: &str) -> bool {
+    ["http://", "https://"].iter().any(|scheme| {
+        path.get(..scheme.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme))
+    })
+}
+
+/// A file opened for writing.
+///
+/// Files served over HTTP are read-only, so creating one always fails with
+/// [`Error::Unsupported`]. The type exists so crate-level helpers such as
+/// [`copy`](crate::copy) compile on every platform.
+#[derive(Debug)]
+pub struct WriteFile(());
+
+impl WriteFile {
+    /// Always fails with [`Error::Unsupported`].
+    pub async fn create(_path: impl AsRef<Path>, _priority: Priority) -> Result<Self, Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Always fails with [`Error::Unsupported`].
+    pub async fn write_all(&self, _data: Data, _priority: Priority) -> Result<(), Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Always fails with [`Error::Unsupported`].
+    pub async fn sync_all(&self, _priority: Priority) -> Result<(), Error> {
+        Err(Error::Unsupported)
+    }
+}
+
+/// Syncs a directory.
+///
+/// Files served over HTTP cannot be written, so this always fails with
+/// [`Error::Unsupported`].
+pub async fn sync_dir(_path: impl AsRef<Path>, _priority: Priority) -> Result<(), Error> {
+    Err(Error::Unsupported)
+}
+
+/// Renames a file.
+///
+/// Files served over HTTP cannot be renamed, so this always fails with
+/// [`Error::Unsupported`].
+pub async fn rename(
+    _from: impl AsRef<Path>,
+    _to: impl AsRef<Path>,
+    _priority: Priority,
+) -> Result<(), Error> {
+    Err(Error::Unsupported)
+}
+
+/// Creates a hard link.
+///
+/// Always fails with [`Error::Unsupported`].
+pub async fn hard_link(
+    _original: impl AsRef<Path>,
+    _link: impl AsRef<Path>,
+    _priority: Priority,
+) -> Result<(), Error> {
+    Err(Error::Unsupported)
+}
+
+/// Reads the target of a symlink.
+///
+/// HTTP has no symlinks, so this always fails with [`Error::Unsupported`].
+pub async fn read_link(
+    _path: impl AsRef<Path>,
+    _priority: Priority,
+) -> Result<std::path::PathBuf, Error> {
+    Err(Error::Unsupported)
+}
+
+/// Changes the timestamps of a file.
+///
+/// Files served over HTTP cannot be changed, so this always fails with
+/// [`Error::Unsupported`].
+pub async fn set_times(
+    _path: impl AsRef<Path>,
+    _times: crate::FileTimes,
+    _priority: Priority,
+) -> Result<(), Error> {
+    Err(Error::Unsupported)
+}
+
+/// Reads `len` bytes (or to the end) from `start` with a single ranged GET,
+/// skipping the HEAD request that [`File::open`] makes.
+///
+/// Servers that ignore the Range header send the whole file, in which case the
+/// leading bytes are discarded here. A range starting past the end of the file
+/// (status 416) reads as empty.
+pub async fn read_once(
+    path: impl AsRef<Path>,
+    start: u64,
+    len: Option<usize>,
+    _priority: Priority,
+) -> Result<Data, Error> {
+    if node::available() {
+        let path = path_str(path.as_ref())?.to_owned();
+        let r = Task::without_notifications(
+            "read_once".to_string(),
+            Configuration::default(),
+            node::read_once(path, start, len),
+        )
+        .pin_current()
+        .await?;
+        return Ok(Data(r.into_boxed_slice()));
+    }
+    let fetch = fetch_options();
+    let full_path = full_path(path, &fetch)?;
+    let (abort, cancel) = abortable(&fetch);
+    let r = Task::without_notifications(
+        "read_once".to_string(),
+        Configuration::default(),
+        async move {
+            let range = match len {
+                Some(len) => format!("bytes={}-{}", start, start + len as u64 - 1),
+                None => format!("bytes={}-", start),
+            };
+            let response = send(
+                &full_path,
+                "GET",
+                &fetch,
+                Some(&range),
+                &abort_signal(cancel),
+            )
+            .await?;
+            match response.status() {
+                404 => return Err(Error::NotFound),
+                416 => return Ok(Vec::new()),
+                _ if !response.ok() => return Err(Error::HttpStatus(response.status())),
+                _ => {}
+            }
+            // 206 means the server honored the range; 200 is the whole file.
+            let skip = if response.status() == 200 { start } else { 0 };
+            read_body(response, skip, len.unwrap_or(usize::MAX), None).await
+        },
+    )
+    .pin_current()
+    .await;
+    let r = abort.finish(r)?;
+    Ok(Data(r.into_boxed_slice()))
+}
+
+/// Lists extended attributes.
+///
+/// Files served over HTTP have no extended attributes, so this always fails
+/// with [`Error::Unsupported`].
+pub async fn list_xattrs(
+    _path: impl AsRef<Path>,
+    _priority: Priority,
+) -> Result<Vec<std::ffi::OsString>, Error> {
+    Err(Error::Unsupported)
+}
+
+/// Always fails with [`Error::Unsupported`]; see [`list_xattrs`].
+pub async fn get_xattr(
+    _path: impl AsRef<Path>,
+    _name: &std::ffi::OsStr,
+    _priority: Priority,
+) -> Result<Option<Vec<u8>>, Error> {
+    Err(Error::Unsupported)
+}
+
+/// Always fails with [`Error::Unsupported`]; see [`list_xattrs`].
+pub async fn set_xattr(
+    _path: impl AsRef<Path>,
+    _name: &std::ffi::OsStr,
+    _value: &[u8],
+    _priority: Priority,
+) -> Result<(), Error> {
+    Err(Error::Unsupported)
+}
+
+/// Always fails with [`Error::Unsupported`]; see [`list_xattrs`].
+pub async fn remove_xattr(
+    _path: impl AsRef<Path>,
+    _name: &std::ffi::OsStr,
+    _priority: Priority,
+) -> Result<(), Error> {
+    Err(Error::Unsupported)
+}
+
+/// Retrieves metadata for a path with an HTTP HEAD request, without
+/// downloading or opening anything.
+pub async fn metadata(path: impl AsRef<Path>, _priority: Priority) -> Result<Metadata, Error> {
+    if node::available() {
+        return node_stat(path, false).await;
+    }
+    path_metadata(path, &fetch_options()).await
+}
+
+/// Retrieves metadata without following symlinks.
+///
+/// HTTP has no symlinks, so this is the metadata reported for the URL. Under
+/// Node, this is `fs.lstat`.
+pub async fn symlink_metadata(
+    path: impl AsRef<Path>,
+    _priority: Priority,
+) -> Result<Metadata, Error> {
+    if node::available() {
+        return node_stat(path, true).await;
+    }
+    path_metadata(path, &fetch_options()).await
+}
+
+/// Stats `path` with Node's `fs.promises`.
+async fn node_stat(path: impl AsRef<Path>, lstat: bool) -> Result<Metadata, Error> {
+    let path = path_str(path.as_ref())?.to_owned();
+    Task::without_notifications(
+        "metadata".to_string(),
+        Configuration::default(),
+        node::stat(path, lstat),
+    )
+    .pin_current()
+    .await
+}
+
+/// Returns the canonical form of a path.
+///
+/// Resolving a path means asking the server about each component, which HTTP
+/// cannot do, so this always fails with [`Error::Unsupported`].
+pub async fn canonicalize(
+    _path: impl AsRef<Path>,
+    _priority: Priority,
+) -> Result<std::path::PathBuf, Error> {
+    Err(Error::Unsupported)
+}
+
+/// Changes the permissions of a file.
+///
+/// Files served over HTTP cannot be changed, so this always fails with
+/// [`Error::Unsupported`].
+pub async fn set_permissions(
+    _path: impl AsRef<Path>,
+    _permissions: Permissions,
+    _priority: Priority,
+) -> Result<(), Error> {
+    Err(Error::Unsupported)
+}
+
+/// Removes a file.
+///
+/// Files served over HTTP cannot be removed, so this always fails with
+/// [`Error::Unsupported`].
+pub async fn remove_file(_path: impl AsRef<Path>, _priority: Priority) -> Result<(), Error> {
+    Err(Error::Unsupported)
+}
+
+/// Removes an empty directory.
+///
+/// Always fails with [`Error::Unsupported`].
+pub async fn remove_dir(_path: impl AsRef<Path>, _priority: Priority) -> Result<(), Error> {
+    Err(Error::Unsupported)
+}
+
+/// Removes a non-directory entry on behalf of `remove_dir_all`.
+///
+/// Always fails with [`Error::Unsupported`].
+pub async fn remove_tree_file(_path: impl AsRef<Path>, _priority: Priority) -> Result<(), Error> {
+    Err(Error::Unsupported)
+}
+
+/// Removes an emptied directory on behalf of `remove_dir_all`.
+///
+/// Always fails with [`Error::Unsupported`].
+pub async fn remove_tree_dir(_path: impl AsRef<Path>, _priority: Priority) -> Result<(), Error> {
+    Err(Error::Unsupported)
+}
+
+/// Does nothing: operations are HTTP requests, not calls on worker threads.
+pub fn set_worker_threads(_threads: usize) {}
+
+/// Returns 0: there are no worker threads.
+pub fn worker_threads() -> usize {
+    0
+}
+
+/// Does nothing: there are no worker threads to limit.
+pub fn set_band_limits(_limits: [Option<usize>; crate::BANDS]) {}
+
+/// Returns no limits.
+pub fn band_limits() -> [Option<usize>; crate::BANDS] {
+    [None; crate::BANDS]
+}
+
+/// Does nothing: there is no queue to reorder.
+pub fn reschedule() {}
+
+/// Returns empty figures: there is no queue.
+pub fn scheduler_stats() -> [crate::stats::BandStats; crate::BANDS] {
+    Default::default()
+}
+
+/// Does nothing: there is no queue to bound.
+pub fn set_max_queue(_max: Option<usize>) {}
+
+/// Returns no bound.
+pub fn max_queue() -> Option<usize> {
+    None
+}
+
+/// Does nothing: there is no queue for calls to age in.
+pub fn set_aging(_step: Option<std::time::Duration>) {}
+
+/// Returns no aging.
+pub fn aging() -> Option<std::time::Duration> {
+    None
+}
+
+/// Returns zero waits: nothing is queued.
+pub fn max_queue_waits() -> [std::time::Duration; crate::BANDS] {
+    [std::time::Duration::ZERO; crate::BANDS]
+}
+
+/// Returns a future that completes once `duration` has passed, woken by the
+/// global `setTimeout`.
+pub fn delay(duration: std::time::Duration) -> impl Future<Output = ()> + Send + 'static {
+    let task = Task::without_notifications(
+        "async_file::delay".to_string(),
+        Configuration::default(),
+        sleep(duration),
+    )
+    .pin_current();
+    async move {
+        task.await;
+    }
+}
+
+/// Reports filesystem capabilities.
+///
+/// Files served over HTTP have no filesystem to probe, so this always fails
+/// with [`Error::Unsupported`].
+pub async fn probe(
+    _path: impl AsRef<Path>,
+    _priority: Priority,
+) -> Result<crate::FsCapabilities, Error> {
+    Err(Error::Unsupported)
+}
+
+/// Reports space on the filesystem containing a path.
+///
+/// Files served over HTTP have no local filesystem, so this always fails with
+/// [`Error::Unsupported`].
+pub async fn fs_stats(
+    _path: impl AsRef<Path>,
+    _priority: Priority,
+) -> Result<crate::FsStats, Error> {
+    Err(Error::Unsupported)
+}
+
+/// Creates a directory and its parents.
+///
+/// Always fails with [`Error::Unsupported`].
+pub async fn create_dir_all(_path: impl AsRef<Path>, _priority: Priority) -> Result<(), Error> {
+    Err(Error::Unsupported)
+}
+
+/// An entry returned by [`read_dir`].
+#[derive(Debug)]
+pub struct DirEntry {
+    pub path: std::path::PathBuf,
+    pub is_dir: bool,
+    pub is_symlink: bool,
+}
+
+/// Identifies the volume a path lives on. Never produced on WASM.
+pub type VolumeId = u64;
+
+/// Where a path leads once all symlinks are followed.
+#[derive(Debug)]
+pub struct Resolved {
+    pub canonical: std::path::PathBuf,
+    pub volume: VolumeId,
+    pub is_dir: bool,
+}
+
+/// Follows `path` to its final target.
+///
+/// Always fails with [`Error::Unsupported`].
+pub async fn resolve(_path: impl AsRef<Path>, _priority: Priority) -> Result<Resolved, Error> {
+    Err(Error::Unsupported)
+}
+
+/// The size of one entry, on behalf of `dir_size`.
+#[derive(Debug)]
+pub struct Usage {
+    pub len: u64,
+    pub allocated: u64,
+    pub id: Option<(u64, u64)>,
+}
+
+/// Measures one entry.
+///
+/// Always fails with [`Error::Unsupported`].
+pub async fn usage(
+    _path: impl AsRef<Path>,
+    _follow: bool,
+    _priority: Priority,
+) -> Result<Usage, Error> {
+    Err(Error::Unsupported)
+}
+
+/// Lists a directory.
+///
+/// HTTP has no notion of directory listings, so this always fails with
+/// [`Error::Unsupported`].
+pub async fn read_dir(
+    _path: impl AsRef<Path>,
+    _priority: Priority,
+) -> Result<Vec<DirEntry>, Error> {
+    Err(Error::Unsupported)
 }
 
 /// Tests if a file exists at the given path.
@@ -513,24 +1921,34 @@ fn full_path(path: impl AsRef<Path>) -> String {
 /// - Returns `false` for any error (network, CORS, 404, etc.)
 /// - Does not distinguish between different types of failures
 pub async fn exists(path: impl AsRef<Path>, _priority: Priority) -> bool {
+    if node::available() {
+        return node_stat(path, false).await.is_ok();
+    }
+    head(path, &fetch_options()).await.is_ok()
+}
+
+/// Issues a HEAD request, returning the Content-Length and the version of the
+/// file if it exists and the server sent them, or an error if the file does
+/// not exist or the request fails.
+async fn head(
+    path: impl AsRef<Path>,
+    fetch: &FetchOptions,
+) -> Result<(Option<u64>, Option<Version>), Error> {
     // logwise::info_sync!("afile:a");
-    let full_path = full_path(path);
-    Task::without_notifications(
+    let full_path = full_path(path, fetch)?;
+    let fetch = fetch.clone();
+    let (abort, cancel) = abortable(&fetch);
+    let head = Task::without_notifications(
         "File::exists".to_string(),
         Configuration::default(),
         async move {
-            let opts = RequestInit::new();
-
-            opts.set_method("HEAD");
-            let request = Request::new_with_str_and_init(&full_path, &opts).unwrap();
-
-            match fetch_with_request(request).await {
+            match send(&full_path, "HEAD", &fetch, None, &abort_signal(cancel)).await {
                 Ok(response) => {
                     if response.ok() {
-                        true
+                        Ok((identity_len(&response), Version::of(&response)))
                     } else {
                         // logwise::debuginternal_sync!("Got response {status} for url {url}", status=response.status_text(), url=logwise::privacy::LogIt(full_path));
-                        false
+                        Err(Error::HttpStatus(response.status()))
                     }
                 }
                 Err(e) => {
@@ -538,13 +1956,14 @@ pub async fn exists(path: impl AsRef<Path>, _priority: Priority) -> bool {
                     logwise::debuginternal_sync!(
                         "File::exists failed for url {url}; {e}",
                         url = logwise::privacy::LogIt(full_path),
-                        e = logwise::privacy::LogIt(e)
+                        e = logwise::privacy::LogIt(&e)
                     );
-                    false
+                    Err(e)
                 }
             }
         },
     )
     .pin_current()
-    .await
+    .await;
+    abort.finish(head)
 }
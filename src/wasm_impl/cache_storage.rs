@@ -0,0 +1,154 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Requests answered from the browser's Cache Storage, for apps that work
+//! offline.
+//!
+//! The Cache API stores whole responses only; a partial (206) response can't
+//! be put. So a file is cached by fetching all of it without a Range header,
+//! and ranged requests are answered by slicing the stored body. The answers
+//! are built as the `Response` the server would have sent (206 with the
+//! slice, 416 past the end, 200 with a Content-Length for HEAD), so callers
+//! treat cached and network responses alike.
+
+use super::{Error, send_network};
+use crate::{CachePolicy, FetchOptions};
+use js_sys::Reflect;
+use js_sys::wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::wasm_bindgen::JsCast;
+use web_sys::{AbortSignal, Blob, Cache, CacheStorage, Response, ResponseInit};
+
+/// Sends a request for `full_path` through the cache named `name`.
+///
+/// Where Cache Storage is unavailable (outside secure contexts, and in some
+/// runtimes), the request goes to the network as usual.
+pub(super) async fn send(
+    name: &str,
+    policy: CachePolicy,
+    full_path: &str,
+    method: &str,
+    fetch: &FetchOptions,
+    range: Option<&str>,
+    signal: &AbortSignal,
+) -> Result<Response, Error> {
+    let Some(cache) = open(name).await? else {
+        return send_network(full_path, method, fetch, range, signal).await;
+    };
+    match policy {
+        CachePolicy::CacheFirst => {
+            if let Some(cached) = lookup(&cache, full_path).await? {
+                return answer(cached, method, range).await;
+            }
+            // Caching means downloading the whole file, which a HEAD request
+            // shouldn't do.
+            if method == "HEAD" {
+                return send_network(full_path, method, fetch, range, signal).await;
+            }
+            let response = send_network(full_path, "GET", fetch, None, signal).await?;
+            if response.status() != 200 {
+                return Ok(response);
+            }
+            store(&cache, full_path, &response).await;
+            answer(response, method, range).await
+        }
+        CachePolicy::NetworkFirst => {
+            match send_network(full_path, method, fetch, range, signal).await {
+                Ok(response) => {
+                    // Only a whole file can be stored.
+                    if method == "GET" && response.status() == 200 {
+                        store(&cache, full_path, &response).await;
+                    }
+                    Ok(response)
+                }
+                Err(error) => match lookup(&cache, full_path).await? {
+                    Some(cached) => answer(cached, method, range).await,
+                    None => Err(error),
+                },
+            }
+        }
+    }
+}
+
+/// Opens the cache named `name`, or returns `None` if there is no Cache
+/// Storage.
+async fn open(name: &str) -> Result<Option<Cache>, Error> {
+    let caches = Reflect::get(&js_sys::global(), &"caches".into())?;
+    if caches.is_undefined() || caches.is_null() {
+        return Ok(None);
+    }
+    let caches: CacheStorage = caches.unchecked_into();
+    Ok(Some(
+        JsFuture::from(caches.open(name)).await?.unchecked_into(),
+    ))
+}
+
+/// Returns the stored response for `full_path`, if any.
+async fn lookup(cache: &Cache, full_path: &str) -> Result<Option<Response>, Error> {
+    let found = JsFuture::from(cache.match_with_str(full_path)).await?;
+    Ok(found.dyn_into().ok())
+}
+
+/// Stores a copy of `response`. Failing to store, for example when over
+/// quota, only means the next request goes to the network, so errors are
+/// logged and otherwise ignored.
+async fn store(cache: &Cache, full_path: &str, response: &Response) {
+    let stored = match response.clone() {
+        Ok(copy) => JsFuture::from(cache.put_with_str(full_path, &copy)).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = stored {
+        logwise::warn_sync!(
+            "Could not cache {url}: {e}",
+            url = logwise::privacy::LogIt(full_path),
+            e = logwise::privacy::LogIt(e)
+        );
+    }
+}
+
+/// Answers a `method` request for `range` from `response`, a whole file.
+async fn answer(response: Response, method: &str, range: Option<&str>) -> Result<Response, Error> {
+    let blob: Blob = JsFuture::from(response.blob()?).await?.unchecked_into();
+    let size = blob.size() as u64;
+    let headers = js_sys::Map::new();
+    if let Ok(Some(modified)) = response.headers().get("last-modified") {
+        headers.set(&"Last-Modified".into(), &JsValue::from_str(&modified));
+    }
+    let init = ResponseInit::new();
+    let body = if method == "HEAD" {
+        headers.set(
+            &"Content-Length".into(),
+            &JsValue::from_str(&size.to_string()),
+        );
+        init.set_status(200);
+        None
+    } else {
+        match range.and_then(parse_range) {
+            None => {
+                init.set_status(200);
+                Some(blob)
+            }
+            Some((start, _)) if start >= size => {
+                init.set_status(416);
+                None
+            }
+            Some((start, last)) => {
+                let end = last.map_or(size, |last| (last + 1).min(size));
+                init.set_status(206);
+                Some(blob.slice_with_f64_and_f64(start as f64, end as f64)?)
+            }
+        }
+    };
+    init.set_headers(&headers.into());
+    Ok(Response::new_with_opt_blob_and_init(body.as_ref(), &init)?)
+}
+
+/// Parses a Range header of the forms this crate sends, `bytes=a-b` and
+/// `bytes=a-`, into the first byte and the last, if given.
+fn parse_range(range: &str) -> Option<(u64, Option<u64>)> {
+    let (start, last) = range.strip_prefix("bytes=")?.split_once('-')?;
+    let last = match last {
+        "" => None,
+        last => Some(last.parse().ok()?),
+    };
+    Some((start.parse().ok()?, last))
+}
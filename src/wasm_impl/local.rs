@@ -0,0 +1,152 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Local files the user picked, and other in-memory data, read through the
+//! browser's `Blob` API.
+//!
+//! A `FileSystemFileHandle` from `showOpenFilePicker`, a `File` from an
+//! `<input type="file">` or a drop, or any other `Blob`, is a JavaScript
+//! object. JavaScript objects can't leave
+//! the thread that created them, but [`File`](super::File) and the futures that
+//! open it must be `Send`. So the objects are kept in a per-thread table, and
+//! what crosses `await` points and threads is only their key.
+
+use super::Error;
+use js_sys::wasm_bindgen::JsValue;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::wasm_bindgen::JsCast;
+
+thread_local! {
+    static OBJECTS: RefCell<HashMap<u64, JsValue>> = RefCell::new(HashMap::new());
+}
+
+static NEXT_KEY: AtomicU64 = AtomicU64::new(0);
+
+/// A JavaScript object in this thread's table, removed when this is dropped.
+#[derive(Debug)]
+pub(super) struct Entry(u64);
+
+impl Entry {
+    /// Adds `object` to the table for the current thread.
+    pub(super) fn new(object: JsValue) -> Entry {
+        let key = NEXT_KEY.fetch_add(1, Ordering::Relaxed);
+        OBJECTS.with(|objects| objects.borrow_mut().insert(key, object));
+        Entry(key)
+    }
+
+    /// Returns the object, if it was added on this thread.
+    pub(super) fn object(&self) -> Option<JsValue> {
+        lookup(self.0)
+    }
+
+    /// Returns a future that looks the object up when first polled.
+    pub(super) fn get(&self) -> impl Future<Output = Result<JsValue, Error>> + Send + 'static {
+        let key = self.0;
+        async move {
+            lookup(key).ok_or_else(|| {
+                Error::Wasm("a local file can only be used on the thread that opened it".into())
+            })
+        }
+    }
+}
+
+fn lookup(key: u64) -> Option<JsValue> {
+    OBJECTS
+        .try_with(|objects| objects.borrow().get(&key).cloned())
+        .ok()
+        .flatten()
+}
+
+impl Drop for Entry {
+    fn drop(&mut self) {
+        // On another thread, or during thread teardown, there is nothing to
+        // remove (or nothing left to remove it from).
+        let _ = OBJECTS.try_with(|objects| objects.borrow_mut().remove(&self.0));
+    }
+}
+
+/// A local file or `Blob`, with the properties it reports synchronously.
+#[derive(Debug)]
+pub(super) struct Local {
+    entry: Entry,
+    /// The file name, without any directory; `blob` for a `Blob`
+    pub(super) name: String,
+    /// The size of the file in bytes
+    pub(super) len: u64,
+    /// Milliseconds since the Unix epoch, for a `File`
+    pub(super) modified_ms: Option<f64>,
+}
+
+impl Local {
+    /// Returns another reference to the same file or `Blob`, which must be
+    /// made on the thread that added it.
+    pub(super) fn try_clone(&self) -> Result<Local, Error> {
+        let object = self.entry.object().ok_or_else(|| {
+            Error::Wasm("a local file can only be used on the thread that opened it".into())
+        })?;
+        Ok(Local {
+            entry: Entry::new(object),
+            name: self.name.clone(),
+            len: self.len,
+            modified_ms: self.modified_ms,
+        })
+    }
+
+    pub(super) fn new(file: &web_sys::File) -> Local {
+        Local {
+            entry: Entry::new(file.clone().into()),
+            name: file.name(),
+            len: file.size() as u64,
+            modified_ms: Some(file.last_modified()),
+        }
+    }
+
+    pub(super) fn from_blob(blob: &web_sys::Blob) -> Local {
+        Local {
+            entry: Entry::new(blob.clone().into()),
+            name: "blob".to_string(),
+            len: blob.size() as u64,
+            modified_ms: None,
+        }
+    }
+
+    /// A key for the coalescing table, unique to this `Local`.
+    pub(super) fn key(&self) -> String {
+        format!("local:{}/{}", self.entry.0, self.name)
+    }
+
+    /// Returns a future that reads up to `len` bytes from `start`, to be run
+    /// on the thread that opened the file. Reads past the end are short, or
+    /// empty.
+    pub(super) fn read(
+        &self,
+        start: u64,
+        len: usize,
+    ) -> impl Future<Output = Result<Vec<u8>, Error>> + 'static {
+        let blob = self.entry.get();
+        let end = start.saturating_add(len as u64).min(self.len);
+        async move {
+            let blob: web_sys::Blob = blob.await?.unchecked_into();
+            if start >= end {
+                return Ok(Vec::new());
+            }
+            let blob = blob.slice_with_f64_and_f64(start as f64, end as f64)?;
+            let buffer = JsFuture::from(blob.array_buffer()).await?;
+            Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+        }
+    }
+}
+
+/// Returns a future that resolves the `getFile()` promise held in `pending`,
+/// to be run on the thread that added it.
+pub(super) fn resolve(
+    pending: &Entry,
+) -> impl Future<Output = Result<web_sys::File, Error>> + 'static {
+    let promise = pending.get();
+    async move {
+        let promise: js_sys::Promise = promise.await?.unchecked_into();
+        Ok(JsFuture::from(promise).await?.unchecked_into())
+    }
+}
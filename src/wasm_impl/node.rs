@@ -0,0 +1,183 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Local files under Node.js, read with `fs.promises` instead of fetch.
+//!
+//! With the `node` feature, when the module is running under Node, paths name
+//! files on the local disk, as they do on native platforms, so command-line
+//! tools compiled to wasm32 don't need an HTTP server. The `fs` module is
+//! looked up at runtime, with `process.getBuiltinModule` (Node 20.16 and
+//! later) or else a global `require`; where neither exists, requests are made
+//! over HTTP as usual.
+//!
+//! Node's `FileHandle` is a JavaScript object, so open files are kept in the
+//! same per-thread table as [`local`](super::local) files.
+
+use super::local::Entry;
+use super::{Error, Metadata};
+use js_sys::wasm_bindgen::JsValue;
+use js_sys::{Function, Reflect};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::wasm_bindgen::JsCast;
+
+/// Whether paths are read with `fs.promises`: the `node` feature is enabled,
+/// and the module is running under Node.
+pub(super) fn available() -> bool {
+    cfg!(feature = "node") && fs().is_some()
+}
+
+/// Returns `fs.promises`, if running under Node.
+fn fs() -> Option<JsValue> {
+    let global = js_sys::global();
+    let process = Reflect::get(&global, &"process".into()).ok()?;
+    let versions = Reflect::get(&process, &"versions".into()).ok()?;
+    if !Reflect::get(&versions, &"node".into()).ok()?.is_string() {
+        return None;
+    }
+    let fs = match function(&process, "getBuiltinModule") {
+        Some(get) => get.call1(&process, &"fs".into()).ok()?,
+        None => function(&global, "require")?
+            .call1(&JsValue::UNDEFINED, &"fs".into())
+            .ok()?,
+    };
+    Some(Reflect::get(&fs, &"promises".into()).ok()?).filter(JsValue::is_object)
+}
+
+fn function(target: &JsValue, name: &str) -> Option<Function> {
+    Reflect::get(target, &name.into()).ok()?.dyn_into().ok()
+}
+
+/// Calls `target.name(...args)` and awaits the promise it returns.
+async fn call(target: &JsValue, name: &str, args: &[JsValue]) -> Result<JsValue, Error> {
+    let method = function(target, name)
+        .ok_or_else(|| Error::Wasm(format!("Node fs has no function {name}")))?;
+    let args: js_sys::Array = args.iter().collect();
+    let promise: js_sys::Promise = method.apply(target, &args).map_err(error)?.unchecked_into();
+    JsFuture::from(promise).await.map_err(error)
+}
+
+/// Converts a Node error, reporting `ENOENT` as [`Error::NotFound`].
+fn error(value: JsValue) -> Error {
+    let code = Reflect::get(&value, &"code".into())
+        .ok()
+        .and_then(|code| code.as_string());
+    match code.as_deref() {
+        Some("ENOENT") => Error::NotFound,
+        _ => Error::from(value),
+    }
+}
+
+fn fs_or_error() -> Result<JsValue, Error> {
+    fs().ok_or_else(|| Error::Wasm("not running under Node".to_string()))
+}
+
+/// Converts an `fs.Stats`.
+fn metadata(stats: &JsValue) -> Metadata {
+    let number = |name: &str| {
+        Reflect::get(stats, &name.into())
+            .ok()
+            .and_then(|value| value.as_f64())
+    };
+    let is = |name: &str| {
+        function(stats, name)
+            .and_then(|f| f.call0(stats).ok())
+            .is_some_and(|value| value.is_truthy())
+    };
+    let file_type = if is("isFile") {
+        crate::FileType::File
+    } else if is("isDirectory") {
+        crate::FileType::Dir
+    } else if is("isSymbolicLink") {
+        crate::FileType::Symlink
+    } else {
+        crate::FileType::Other
+    };
+    Metadata {
+        len: number("size").unwrap_or(0.0) as u64,
+        modified_ms: number("mtimeMs"),
+        file_type,
+    }
+}
+
+/// An open `FileHandle`, closed when this is dropped.
+#[derive(Debug)]
+pub(super) struct Handle(Entry);
+
+impl Handle {
+    /// Returns a future that reads up to `len` bytes from `position`.
+    pub(super) fn read(
+        &self,
+        position: u64,
+        len: usize,
+    ) -> impl Future<Output = Result<Vec<u8>, Error>> + 'static {
+        let handle = self.0.get();
+        async move { read(&handle.await?, position, len).await }
+    }
+
+    /// Returns a future that stats the open file.
+    pub(super) fn stat(&self) -> impl Future<Output = Result<Metadata, Error>> + 'static {
+        let handle = self.0.get();
+        async move { Ok(metadata(&call(&handle.await?, "stat", &[]).await?)) }
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        if let Some(handle) = self.0.object() {
+            // The promise settles on its own; a failure to close has no one
+            // to report to.
+            if let Some(close) = function(&handle, "close") {
+                let _ = close.call0(&handle);
+            }
+        }
+    }
+}
+
+/// Reads up to `len` bytes of an open `FileHandle` from `position`.
+async fn read(handle: &JsValue, position: u64, len: usize) -> Result<Vec<u8>, Error> {
+    let len = len.min(u32::MAX as usize);
+    let buffer = js_sys::Uint8Array::new_with_length(len as u32);
+    let result = call(
+        handle,
+        "read",
+        &[
+            buffer.clone().into(),
+            0.into(),
+            (len as u32).into(),
+            (position as f64).into(),
+        ],
+    )
+    .await?;
+    let read = Reflect::get(&result, &"bytesRead".into())
+        .ok()
+        .and_then(|n| n.as_f64())
+        .unwrap_or(0.0) as u32;
+    Ok(buffer.subarray(0, read).to_vec())
+}
+
+/// Opens `path` for reading.
+pub(super) async fn open(path: String) -> Result<Handle, Error> {
+    let handle = call(&fs_or_error()?, "open", &[path.into(), "r".into()]).await?;
+    Ok(Handle(Entry::new(handle)))
+}
+
+/// Returns the metadata of `path`, following symlinks unless `lstat`.
+pub(super) async fn stat(path: String, lstat: bool) -> Result<Metadata, Error> {
+    let name = if lstat { "lstat" } else { "stat" };
+    Ok(metadata(
+        &call(&fs_or_error()?, name, &[path.into()]).await?,
+    ))
+}
+
+/// Reads `len` bytes (or to the end) of `path` from `start`.
+pub(super) async fn read_once(
+    path: String,
+    start: u64,
+    len: Option<usize>,
+) -> Result<Vec<u8>, Error> {
+    let handle = open(path).await?;
+    let len = match len {
+        Some(len) => len,
+        None => handle.stat().await?.len.saturating_sub(start) as usize,
+    };
+    handle.read(start, len).await
+}
@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Browser-specific extensions, for reading local files the user picked.
+//!
+//! On the web, files are normally fetched over HTTP. A page can also read
+//! files from the user's disk, once the user picks them: with
+//! `showOpenFilePicker` from the File System Access API, which gives a
+//! `FileSystemFileHandle`, or with an `<input type="file">` element or a
+//! drop, which give `File` objects. Data already in memory may be held in a
+//! `Blob`. The functions here open those as a [`File`], with the same reads,
+//! seeks and metadata as any other.
+//!
+//! # Threads
+//!
+//! JavaScript objects belong to the thread that created them. The [`File`]
+//! returned here is `Send` like any other, but it can only be read on the
+//! thread that opened it; reads on another thread fail.
+
+use crate::{Error, File, Priority, sys};
+use std::path::PathBuf;
+
+/// Opens the file behind a handle from `showOpenFilePicker`.
+///
+/// The file is read as it was when opened. If it changes on disk afterwards,
+/// reads fail; open the handle again to see the new contents.
+///
+/// The returned future is `Send`, but must be polled on the calling thread.
+///
+/// # Errors
+///
+/// Returns an error if the browser can't read the file, for example because
+/// permission to it was revoked.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn example(handle: web_sys::FileSystemFileHandle) -> Result<(), async_file::Error> {
+/// use async_file::{Priority, web};
+///
+/// // `handle` came from `window.showOpenFilePicker()`
+/// let file = web::open_file_handle(&handle, Priority::highest_async()).await?;
+/// let document = file.read_all(Priority::highest_async()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn open_file_handle(
+    handle: &web_sys::FileSystemFileHandle,
+    _priority: Priority,
+) -> impl Future<Output = Result<File, Error>> + Send + use<> {
+    let open = sys::File::open_file_handle(handle);
+    async move { Ok(from_inner(open.await.map_err(Error)?)) }
+}
+
+/// Opens a `File` object, such as one from an `<input type="file">` element's
+/// `files` list.
+///
+/// As with [`open_file_handle`], the file is read as it was when picked.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn example(picked: web_sys::File) -> Result<(), async_file::Error> {
+/// use async_file::{Priority, web};
+///
+/// // `picked` came from `input.files()`
+/// let file = web::open_web_file(&picked);
+/// let header = file.read(64, Priority::highest_async()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn open_web_file(file: &web_sys::File) -> File {
+    from_inner(sys::File::from_web_file(file))
+}
+
+/// Opens a `Blob`, such as a response body or data generated in the page.
+///
+/// Reads slice the blob, so only the bytes asked for are copied into
+/// WebAssembly memory. A blob has no modification time, so
+/// [`Metadata::modified`](crate::Metadata::modified) fails on its metadata.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn example(blob: web_sys::Blob) -> Result<(), async_file::Error> {
+/// use async_file::{Priority, web};
+/// use std::io::SeekFrom;
+///
+/// let mut file = web::open_blob(&blob);
+/// file.seek(SeekFrom::End(-16), Priority::highest_async()).await?;
+/// let trailer = file.read(16, Priority::highest_async()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn open_blob(blob: &web_sys::Blob) -> File {
+    from_inner(sys::File::from_blob(blob))
+}
+
+fn from_inner(inner: sys::File) -> File {
+    let path = PathBuf::from(inner.path());
+    File::from_inner(inner, path)
+}
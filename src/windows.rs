@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Windows-specific extensions.
+//!
+//! [`File`] implements [`AsHandle`] and [`AsRawHandle`], and converts from an
+//! [`OwnedHandle`], so its handle can be used with Win32 calls this crate
+//! doesn't wrap, such as `LockFileEx`.
+
+use crate::File;
+use std::os::windows::io::{AsHandle, AsRawHandle, BorrowedHandle, OwnedHandle, RawHandle};
+
+/// Borrows the handle, for calls such as `LockFileEx` or setting the sparse
+/// attribute.
+///
+/// Reads move the handle's file pointer, but the crate tracks its own
+/// position, so moving the pointer has no effect on them. Closing the handle
+/// through another copy makes them fail.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{File, Priority};
+/// use std::os::windows::io::AsRawHandle;
+///
+/// let file = File::open("C:\\Windows\\win.ini", Priority::unit_test()).await?;
+/// println!("reading from handle {:?}", file.as_raw_handle());
+/// # Ok(())
+/// # }
+/// ```
+impl AsHandle for File {
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.inner.as_std().as_handle()
+    }
+}
+
+impl AsRawHandle for File {
+    fn as_raw_handle(&self) -> RawHandle {
+        self.inner.as_std().as_raw_handle()
+    }
+}
+
+/// Wraps the handle as [`File::from_owned_handle`] does.
+impl From<OwnedHandle> for File {
+    fn from(handle: OwnedHandle) -> Self {
+        File::from_owned_handle(handle)
+    }
+}
+
+impl File {
+    /// Wraps an open handle, such as one inherited from a parent process, as
+    /// [`File::from_std`] wraps a `std::fs::File`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example(handle: std::os::windows::io::OwnedHandle) -> Result<(), async_file::Error> {
+    /// use async_file::{File, Priority};
+    ///
+    /// let file = File::from_owned_handle(handle);
+    /// let data = file.read(4096, Priority::unit_test()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_owned_handle(handle: OwnedHandle) -> Self {
+        File::from_std(std::fs::File::from(handle))
+    }
+}
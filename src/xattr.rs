@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Extended attributes: small named values stored alongside a file's contents.
+//!
+//! Extended attributes are supported on Linux, Android, macOS and iOS. On other
+//! platforms, and on WASM, every call fails. Filesystems may also refuse them
+//! (FAT, some network mounts); [`probe`](crate::probe) reports whether a volume
+//! supports them. On Linux, user-defined attributes must be in the `user.`
+//! namespace.
+
+use crate::{Error, Priority, sys};
+use std::ffi::{OsStr, OsString};
+use std::path::Path;
+
+/// Lists the names of the extended attributes on a file or directory.
+///
+/// Symlinks are followed.
+///
+/// # Errors
+///
+/// Returns an error if `path` does not exist, or if the platform or filesystem
+/// does not support extended attributes.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{list_xattrs, Priority};
+///
+/// for name in list_xattrs("photo.jpg", Priority::unit_test()).await? {
+///     println!("{}", name.to_string_lossy());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn list_xattrs(
+    path: impl AsRef<Path>,
+    priority: Priority,
+) -> Result<Vec<OsString>, Error> {
+    sys::list_xattrs(path, priority).await.map_err(Error)
+}
+
+/// Reads an extended attribute, or returns `None` if it is not set.
+///
+/// Symlinks are followed.
+///
+/// # Errors
+///
+/// Returns an error if `path` does not exist, or if the platform or filesystem
+/// does not support extended attributes.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{get_xattr, Priority};
+///
+/// if let Some(hash) = get_xattr("photo.jpg", "user.sha256", Priority::unit_test()).await? {
+///     println!("cached hash is {} bytes", hash.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn get_xattr(
+    path: impl AsRef<Path>,
+    name: impl AsRef<OsStr>,
+    priority: Priority,
+) -> Result<Option<Vec<u8>>, Error> {
+    sys::get_xattr(path, name.as_ref(), priority)
+        .await
+        .map_err(Error)
+}
+
+/// Sets an extended attribute, creating it or replacing its value.
+///
+/// Symlinks are followed.
+///
+/// # Errors
+///
+/// Returns an error if `path` does not exist, the caller may not change it, the
+/// value is too large for the filesystem, or the platform or filesystem does
+/// not support extended attributes.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() -> Result<(), async_file::Error> {
+/// use async_file::{set_xattr, Priority};
+///
+/// set_xattr("download.zip", "user.quarantine", b"1", Priority::unit_test()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn set_xattr(
+    path: impl AsRef<Path>,
+    name: impl AsRef<OsStr>,
+    value: impl AsRef<[u8]>,
+    priority: Priority,
+) -> Result<(), Error> {
+    sys::set_xattr(path, name.as_ref(), value.as_ref(), priority)
+        .await
+        .map_err(Error)
+}
+
+/// Removes an extended attribute.
+///
+/// Symlinks are followed.
+///
+/// # Errors
+///
+/// Returns an error if `path` does not exist, the attribute is not set, the
+/// caller may not change it, or the platform or filesystem does not support
+/// extended attributes.
+pub async fn remove_xattr(
+    path: impl AsRef<Path>,
+    name: impl AsRef<OsStr>,
+    priority: Priority,
+) -> Result<(), Error> {
+    sys::remove_xattr(path, name.as_ref(), priority)
+        .await
+        .map_err(Error)
+}
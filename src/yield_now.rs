@@ -0,0 +1,32 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! An executor-agnostic yield point.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Yields control back to the executor once.
+///
+/// Long-running crate operations await this between steps so that other tasks
+/// get a chance to run, and so that dropping the operation's future takes
+/// effect before the next step is issued.
+pub(crate) fn yield_now() -> YieldNow {
+    YieldNow(false)
+}
+
+pub(crate) struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}